@@ -269,6 +269,14 @@ pub enum EthRequest {
     )]
     SetNextBlockBaseFeePerGas(#[serde(deserialize_with = "deserialize_number")] U256),
 
+    /// Returns all accounts, their code and storage, and the block history as a JSON document
+    #[serde(rename = "anvil_dumpState", with = "empty_params")]
+    DumpState(()),
+
+    /// Loads a JSON document previously returned by `anvil_dumpState`
+    #[serde(rename = "anvil_loadState", with = "sequence")]
+    LoadState(Bytes),
+
     // Ganache compatible calls
     /// Snapshot the state of the blockchain at the current block.
     #[serde(rename = "evm_snapshot", with = "empty_params")]
@@ -708,6 +716,20 @@ mod tests {
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
+    #[test]
+    fn test_serde_custom_dump_state() {
+        let s = r#"{"method": "anvil_dumpState", "params": [] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
+    #[test]
+    fn test_serde_custom_load_state() {
+        let s = r#"{"method": "anvil_loadState", "params": ["0x7b7d"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
     #[test]
     fn test_serde_custom_snapshot() {
         let s = r#"{"method": "evm_snapshot", "params": [] }"#;
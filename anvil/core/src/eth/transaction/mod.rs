@@ -918,7 +918,7 @@ impl PendingTransaction {
 }
 
 /// Represents all relevant information of an executed transaction
-#[derive(Debug, PartialEq, Clone, Default)]
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
 pub struct TransactionInfo {
     pub transaction_hash: H256,
     pub transaction_index: u32,
@@ -1,9 +1,9 @@
-use ethers_core::types::{H256, U256};
+use ethers_core::types::{Address, Bytes, H256, U256};
 use serde::{
     de::{Error, Visitor},
     Deserialize, Deserializer, Serialize, Serializer,
 };
-use std::fmt;
+use std::{collections::BTreeMap, fmt};
 
 /// Bindings for additional `debug_traceTransaction` options
 ///
@@ -25,6 +25,78 @@ pub struct GethDebugTracingOptions {
     pub timeout: Option<String>,
 }
 
+/// The result of a `debug_traceTransaction` call, depending on the configured tracer
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum GethTrace {
+    /// The output of the default struct logger
+    Default(DefaultFrame),
+    /// The output of the `callTracer`
+    CallTracer(CallFrame),
+}
+
+/// The output of geth's default struct logger
+///
+/// See <https://geth.ethereum.org/docs/dapp/tracing#struct-event-logger>
+#[derive(Clone, Debug, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefaultFrame {
+    pub failed: bool,
+    pub gas: u64,
+    pub return_value: Bytes,
+    pub struct_logs: Vec<StructLog>,
+}
+
+/// A single step of the default struct logger
+#[derive(Clone, Debug, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLog {
+    pub pc: u64,
+    pub op: String,
+    pub gas: u64,
+    pub gas_cost: u64,
+    pub depth: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stack: Option<Vec<U256>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub return_data: Option<Bytes>,
+    /// The memory of the current call, as hex encoded 32 byte words
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<Vec<String>>,
+    /// The storage slots of the current contract that were accessed so far, only set for `SLOAD`
+    /// and `SSTORE`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage: Option<BTreeMap<H256, H256>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refund: Option<u64>,
+}
+
+/// A call frame of the `callTracer`
+///
+/// See <https://geth.ethereum.org/docs/rpc/ns-debug#call-tracer>
+#[derive(Clone, Debug, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFrame {
+    #[serde(rename = "type")]
+    pub typ: String,
+    pub from: Address,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<Address>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<U256>,
+    pub gas: U256,
+    pub gas_used: U256,
+    pub input: Bytes,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<Bytes>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub calls: Option<Vec<CallFrame>>,
+}
+
 /// Represents the params to set forking which can take various forms
 ///  - untagged
 ///  - tagged `forking`
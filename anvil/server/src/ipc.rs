@@ -0,0 +1,157 @@
+//! IPC support via unix domain sockets
+
+use crate::{ws::PubSubConnection, WsRpcHandler};
+use bytes::{Buf, BytesMut};
+use serde::de::IgnoredAny;
+use std::{future::Future, io, os::unix::fs::FileTypeExt, path::PathBuf};
+use tokio::net::UnixListener;
+use tokio_util::codec::{Decoder, Encoder, Framed};
+use tracing::{error, trace, warn};
+
+/// An IPC endpoint that serves JSON-RPC calls via a unix domain socket
+pub struct IpcEndpoint<Handler> {
+    /// the handler for the connections
+    handler: Handler,
+    /// path to the socket file
+    path: PathBuf,
+}
+
+// === impl IpcEndpoint ===
+
+impl<Handler: WsRpcHandler> IpcEndpoint<Handler> {
+    /// Creates a new endpoint that serves requests at the given socket path
+    pub fn new(handler: Handler, path: impl Into<PathBuf>) -> Self {
+        Self { handler, path: path.into() }
+    }
+
+    /// Binds the socket and returns the future that accepts and serves incoming connections
+    ///
+    /// A stale socket file at the path, e.g. from a previous run, is replaced. The socket file is
+    /// removed again when the returned future is dropped.
+    pub fn incoming(self) -> io::Result<impl Future<Output = ()>> {
+        let IpcEndpoint { handler, path } = self;
+        trace!(target: "rpc::ipc", "starting ipc server at {}", path.display());
+
+        if let Ok(metadata) = std::fs::metadata(&path) {
+            if metadata.file_type().is_socket() {
+                std::fs::remove_file(&path)?;
+            }
+        }
+        let listener = UnixListener::bind(&path)?;
+        let guard = SocketGuard(path);
+
+        Ok(async move {
+            let _guard = guard;
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        trace!(target: "rpc::ipc", "ipc client connected");
+                        let conn = Framed::new(stream, JsonRpcCodec);
+                        tokio::spawn(PubSubConnection::new(conn, handler.clone()));
+                    }
+                    Err(err) => {
+                        error!(target: "rpc::ipc", "failed to accept ipc connection {:?}", err);
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Removes the socket file once the endpoint is dropped, e.g. when the node stops
+struct SocketGuard(PathBuf);
+
+impl Drop for SocketGuard {
+    fn drop(&mut self) {
+        trace!(target: "rpc::ipc", "removing ipc socket at {}", self.0.display());
+        if let Err(err) = std::fs::remove_file(&self.0) {
+            warn!(target: "rpc::ipc", "failed to remove ipc socket {}: {}", self.0.display(), err);
+        }
+    }
+}
+
+/// Splits the incoming bytes into JSON-RPC messages and terminates outgoing messages with a
+/// newline
+///
+/// Incoming messages are separated by a newline, but consecutive JSON values without a separator
+/// are accepted as well, as some clients write them like that.
+struct JsonRpcCodec;
+
+impl Decoder for JsonRpcCodec {
+    type Item = String;
+    type Error = io::Error;
+
+    fn decode(&mut self, buf: &mut BytesMut) -> io::Result<Option<Self::Item>> {
+        match buf.iter().position(|b| !b.is_ascii_whitespace()) {
+            Some(start) => buf.advance(start),
+            None => {
+                buf.clear();
+                return Ok(None)
+            }
+        }
+
+        let mut values = serde_json::Deserializer::from_slice(buf).into_iter::<IgnoredAny>();
+        let end = match values.next() {
+            Some(Ok(_)) => values.byte_offset(),
+            Some(Err(err)) if err.is_eof() => return Ok(None),
+            // yield the malformed message up to the end of the line, so it's answered with an
+            // error
+            Some(Err(_)) => match buf.iter().position(|b| *b == b'\n') {
+                Some(end) => end,
+                None => return Ok(None),
+            },
+            None => return Ok(None),
+        };
+
+        let msg = buf.split_to(end);
+        String::from_utf8(msg.to_vec())
+            .map(Some)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+impl Encoder<String> for JsonRpcCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, msg: String, buf: &mut BytesMut) -> io::Result<()> {
+        buf.reserve(msg.len() + 1);
+        buf.extend_from_slice(msg.as_bytes());
+        buf.extend_from_slice(b"\n");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_json_rpc_messages() {
+        let mut codec = JsonRpcCodec;
+        let mut buf = BytesMut::from(
+            &br#"{"jsonrpc":"2.0","method":"eth_chainId","id":1}
+{"jsonrpc":"2.0","method":"eth_blockNumber","id":2}{"jsonrpc":"2.0","#[..],
+        );
+
+        assert_eq!(
+            codec.decode(&mut buf).unwrap().unwrap(),
+            r#"{"jsonrpc":"2.0","method":"eth_chainId","id":1}"#
+        );
+        assert_eq!(
+            codec.decode(&mut buf).unwrap().unwrap(),
+            r#"{"jsonrpc":"2.0","method":"eth_blockNumber","id":2}"#
+        );
+        assert!(codec.decode(&mut buf).unwrap().is_none());
+
+        buf.extend_from_slice(br#""method":"eth_gasPrice","id":3}"#);
+        assert_eq!(
+            codec.decode(&mut buf).unwrap().unwrap(),
+            r#"{"jsonrpc":"2.0","method":"eth_gasPrice","id":3}"#
+        );
+
+        buf.extend_from_slice(b"not json\n ");
+        assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), "not json");
+        assert!(codec.decode(&mut buf).unwrap().is_none());
+        assert!(buf.is_empty());
+    }
+}
@@ -21,9 +21,13 @@ use tracing::{trace, warn};
 mod config;
 /// handlers for axum server
 mod handler;
+#[cfg(unix)]
+mod ipc;
 mod ws;
 pub use crate::ws::{WsContext, WsRpcHandler};
 pub use config::ServerConfig;
+#[cfg(unix)]
+pub use ipc::IpcEndpoint;
 
 /// Configures an [axum::Server] that handles RPC-Calls, both HTTP requests and requests via
 /// websocket
@@ -12,7 +12,7 @@ use axum::{
     response::IntoResponse,
     Extension,
 };
-use futures::{FutureExt, SinkExt, Stream, StreamExt};
+use futures::{ready, FutureExt, Sink, SinkExt, Stream, StreamExt};
 use parking_lot::Mutex;
 use serde::de::DeserializeOwned;
 use std::{
@@ -33,7 +33,7 @@ pub async fn handle_ws<Handler: WsRpcHandler>(
     ws: WebSocketUpgrade,
     Extension(handler): Extension<Handler>,
 ) -> impl IntoResponse {
-    ws.on_upgrade(|socket| WsConnection::new(socket, handler))
+    ws.on_upgrade(|socket| PubSubConnection::new(WsSocket(socket), handler))
 }
 
 /// The general purpose trait for handling RPC requests via websockets
@@ -129,28 +129,78 @@ impl<Handler: WsRpcHandler> RpcHandler for ContextAwareHandler<Handler> {
     }
 }
 
-/// Represents a connection to a client via websocket
+/// Adapts the [WebSocket] to a connection that yields the text messages of the client
+///
+/// Pings are answered by the websocket itself, so they're skipped here.
+struct WsSocket(WebSocket);
+
+impl Stream for WsSocket {
+    type Item = Result<String, axum::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let pin = self.get_mut();
+        loop {
+            match ready!(pin.0.poll_next_unpin(cx)) {
+                Some(Ok(Message::Text(text))) => return Poll::Ready(Some(Ok(text))),
+                Some(Ok(Message::Binary(_))) => {
+                    warn!(target: "rpc::ws", "unexpected binary data");
+                    return Poll::Ready(None)
+                }
+                Some(Ok(Message::Close(_))) => {
+                    trace!(target: "rpc::ws", "ws client disconnected");
+                    return Poll::Ready(None)
+                }
+                Some(Ok(_)) => {}
+                Some(Err(err)) => return Poll::Ready(Some(Err(err))),
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+impl Sink<String> for WsSocket {
+    type Error = axum::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().0.poll_ready_unpin(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: String) -> Result<(), Self::Error> {
+        self.get_mut().0.start_send_unpin(Message::Text(item))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().0.poll_flush_unpin(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().0.poll_close_unpin(cx)
+    }
+}
+
+/// Represents a connection to a client that can send requests and receive subscription events,
+/// like a websocket or an IPC socket
 ///
 /// Contains the state for the entire connection
-struct WsConnection<Handler: WsRpcHandler> {
-    /// the handler for the websocket connection
+pub(crate) struct PubSubConnection<Handler: WsRpcHandler, Connection> {
+    /// the handler for the connection
     handler: Handler,
     /// contains all the subscription related context
     context: WsContext<Handler>,
-    /// The established websocket
-    socket: WebSocket,
+    /// The established connection that yields and accepts JSON-RPC messages
+    connection: Connection,
     /// currently in progress requests
     processing: Vec<Pin<Box<dyn Future<Output = Response> + Send>>>,
     /// pending messages to send
-    pending: VecDeque<Message>,
+    pending: VecDeque<String>,
 }
 
-// === impl WsConnection ===
+// === impl PubSubConnection ===
 
-impl<Handler: WsRpcHandler> WsConnection<Handler> {
-    pub fn new(socket: WebSocket, handler: Handler) -> Self {
+impl<Handler: WsRpcHandler, Connection> PubSubConnection<Handler, Connection> {
+    pub(crate) fn new(connection: Connection, handler: Handler) -> Self {
         Self {
-            socket,
+            connection,
             handler,
             context: Default::default(),
             pending: Default::default(),
@@ -163,74 +213,60 @@ impl<Handler: WsRpcHandler> WsConnection<Handler> {
         ContextAwareHandler { handler: self.handler.clone(), context: self.context.clone() }
     }
 
-    fn on_message(&mut self, msg: Message) -> bool {
-        match msg {
-            Message::Text(text) => {
-                trace!(target: "rpc::ws", "received: {:?}", text);
-                let handler = self.compat_helper();
-                self.processing.push(Box::pin(async move {
-                    match serde_json::from_str::<Request>(&text) {
-                        Ok(req) => handle_request(req, handler)
-                            .await
-                            .unwrap_or_else(|| Response::error(RpcError::invalid_request())),
-                        Err(err) => {
-                            warn!("invalid request={:?}", err);
-                            Response::error(RpcError::invalid_request())
-                        }
-                    }
-                }));
-            }
-            Message::Binary(_) => {
-                warn!(target: "rpc::ws","unexpected binary data");
-                return true
-            }
-            Message::Close(_) => {
-                trace!(target: "rpc::ws", "ws client disconnected");
-                return true
-            }
-            Message::Ping(ping) => {
-                trace!(target: "rpc::ws", "received ping");
-                self.pending.push_back(Message::Pong(ping));
+    fn on_message(&mut self, text: String) {
+        trace!(target: "rpc::pubsub", "received: {:?}", text);
+        let handler = self.compat_helper();
+        self.processing.push(Box::pin(async move {
+            match serde_json::from_str::<Request>(&text) {
+                Ok(req) => handle_request(req, handler)
+                    .await
+                    .unwrap_or_else(|| Response::error(RpcError::invalid_request())),
+                Err(err) => {
+                    warn!("invalid request={:?}", err);
+                    Response::error(RpcError::invalid_request())
+                }
             }
-            _ => {}
-        }
-        false
+        }));
     }
 }
 
-impl<Handler: WsRpcHandler> Future for WsConnection<Handler> {
+impl<Handler, Connection, Err> Future for PubSubConnection<Handler, Connection>
+where
+    Handler: WsRpcHandler,
+    Connection: Sink<String, Error = Err> + Stream<Item = Result<String, Err>> + Unpin,
+    Err: fmt::Debug,
+{
     type Output = ();
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let pin = self.get_mut();
         loop {
             // drive the sink
-            while let Poll::Ready(Ok(())) = pin.socket.poll_ready_unpin(cx) {
-                // only start sending if socket is ready
+            while let Poll::Ready(Ok(())) = pin.connection.poll_ready_unpin(cx) {
+                // only start sending if the connection is ready
                 if let Some(msg) = pin.pending.pop_front() {
-                    trace!(target: "rpc::ws", "sending ws message");
-                    if let Err(err) = pin.socket.start_send_unpin(msg) {
-                        error!(target: "rpc::ws", "Failed to send message {:?}", err);
+                    trace!(target: "rpc::pubsub", "sending message");
+                    if let Err(err) = pin.connection.start_send_unpin(msg) {
+                        error!(target: "rpc::pubsub", "Failed to send message {:?}", err);
                     }
                 } else {
                     break
                 }
             }
+            if let Poll::Ready(Err(err)) = pin.connection.poll_flush_unpin(cx) {
+                trace!(target: "rpc::pubsub", "failed to flush messages, client disconnected {:?}", err);
+                return Poll::Ready(())
+            }
 
             loop {
-                match pin.socket.poll_next_unpin(cx) {
-                    Poll::Ready(Some(msg)) => {
-                        if let Ok(msg) = msg {
-                            if pin.on_message(msg) {
-                                return Poll::Ready(())
-                            }
-                        } else {
-                            trace!(target: "rpc::ws", "client disconnected");
-                            return Poll::Ready(())
-                        }
+                match pin.connection.poll_next_unpin(cx) {
+                    Poll::Ready(Some(Ok(text))) => pin.on_message(text),
+                    Poll::Ready(Some(Err(err))) => {
+                        trace!(target: "rpc::pubsub", "client disconnected {:?}", err);
+                        return Poll::Ready(())
                     }
                     Poll::Ready(None) => {
-                        trace!(target: "rpc::ws", "socket connection finished");
+                        trace!(target: "rpc::pubsub", "connection finished");
                         return Poll::Ready(())
                     }
                     Poll::Pending => break,
@@ -243,7 +279,7 @@ impl<Handler: WsRpcHandler> Future for WsConnection<Handler> {
                 match req.poll_unpin(cx) {
                     Poll::Ready(resp) => {
                         if let Ok(text) = serde_json::to_string(&resp) {
-                            pin.pending.push_back(Message::Text(text));
+                            pin.pending.push_back(text);
                             progress = true;
                         }
                     }
@@ -260,7 +296,7 @@ impl<Handler: WsRpcHandler> Future for WsConnection<Handler> {
                         match sub.poll_next_unpin(cx) {
                             Poll::Ready(Some(res)) => {
                                 if let Ok(text) = serde_json::to_string(&res) {
-                                    pin.pending.push_back(Message::Text(text));
+                                    pin.pending.push_back(text);
                                     progress = true;
                                 }
                             }
@@ -1,8 +1,10 @@
 use anvil_server::ServerConfig;
-use clap::Parser;
+use clap::{Parser, ValueHint};
 use ethers::utils::WEI_IN_ETHER;
+use eyre::WrapErr;
 use std::{
     net::IpAddr,
+    path::{Path, PathBuf},
     sync::{
         atomic::{AtomicUsize, Ordering},
         Arc,
@@ -11,8 +13,12 @@ use std::{
 use tracing::log::trace;
 
 use crate::{
-    config::{Hardfork, DEFAULT_MNEMONIC},
-    eth::pool::transactions::TransactionOrder,
+    config::{Hardfork, DEFAULT_IPC_ENDPOINT, DEFAULT_MNEMONIC},
+    eth::{
+        backend::db::SerializableState,
+        pool::{transactions::TransactionOrder, PoolConfig},
+        EthApi,
+    },
     AccountGenerator, NodeConfig, CHAIN_ID,
 };
 use forge::executor::opts::EvmOpts;
@@ -55,6 +61,15 @@ pub struct NodeArgs {
     #[clap(long, help = "The EVM hardfork to use.", default_value = "latest")]
     pub hardfork: Hardfork,
 
+    #[clap(
+        long,
+        help = "Activates a hardfork at the given block number, e.g. `latest@100`. Can be used multiple times.",
+        value_name = "HARDFORK@BLOCK",
+        multiple_occurrences = true,
+        parse(try_from_str = parse_hardfork_activation)
+    )]
+    pub hardfork_at: Vec<(u64, Hardfork)>,
+
     #[clap(
         short,
         long,
@@ -80,10 +95,50 @@ pub struct NodeArgs {
         clap(long, help = "How transactions are sorted in the mempool", default_value = "fees")
     )]
     pub order: TransactionOrder,
+
+    #[clap(
+        long,
+        help = "The maximum number of transactions in the mempool. If the mempool is full, the transaction with the lowest gas price is evicted for a new one that pays more.",
+        value_name = "NUM"
+    )]
+    pub max_pool_transactions: Option<usize>,
+
+    #[clap(
+        long,
+        help = "The maximum number of transactions of a single sender in the mempool.",
+        value_name = "NUM"
+    )]
+    pub max_pool_transactions_per_sender: Option<usize>,
+
+    #[clap(
+        long,
+        help = "The percentage by which a transaction has to increase the gas price to replace a transaction with the same nonce.",
+        value_name = "PERCENT",
+        default_value = "10"
+    )]
+    pub replacement_fee_bump: u64,
+
+    #[clap(
+        long,
+        help = "Load the state from this file on startup and dump the state to it on shutdown. The file is created if it does not exist.",
+        value_hint = ValueHint::FilePath,
+        value_name = "PATH"
+    )]
+    pub state: Option<PathBuf>,
+
+    #[clap(
+        long,
+        help = "Serve the RPC over an IPC socket at this path (unix only). [default: /tmp/anvil.ipc]",
+        value_hint = ValueHint::FilePath,
+        value_name = "PATH",
+        min_values = 0,
+        max_values = 1
+    )]
+    pub ipc: Option<Option<PathBuf>>,
 }
 
 impl NodeArgs {
-    pub fn into_node_config(self) -> NodeConfig {
+    pub fn into_node_config(self) -> eyre::Result<NodeConfig> {
         let figment = foundry_config::Config::figment_with_root(
             foundry_config::find_project_root_path().unwrap(),
         )
@@ -91,10 +146,13 @@ impl NodeArgs {
         let evm_opts = figment.extract::<EvmOpts>().expect("EvmOpts are subset");
         let genesis_balance = WEI_IN_ETHER.saturating_mul(self.balance.into());
 
-        NodeConfig::default()
+        let init_state = self.init_state()?;
+
+        Ok(NodeConfig::default()
             .with_gas_limit(self.evm_opts.env.gas_limit)
             .with_gas_price(self.evm_opts.env.gas_price)
             .with_hardfork(self.hardfork)
+            .with_hardfork_activations(self.hardfork_at)
             .with_blocktime(self.block_time.map(std::time::Duration::from_secs))
             .with_no_mining(self.no_mining)
             .with_account_generator(self.account_generator())
@@ -109,6 +167,28 @@ impl NodeArgs {
             .set_silent(self.silent)
             .with_chain_id(self.evm_opts.env.chain_id.unwrap_or(CHAIN_ID))
             .with_transaction_order(self.order)
+            .with_pool_config(self.pool_config())
+            .with_init_state(init_state)
+            .with_ipc(self.ipc.map(|path| path.unwrap_or_else(|| DEFAULT_IPC_ENDPOINT.into()))))
+    }
+
+    /// Returns the state stored in the `--state` file, if the file exists
+    fn init_state(&self) -> eyre::Result<Option<SerializableState>> {
+        let path = match self.state.as_ref().filter(|path| path.exists()) {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+        let state = SerializableState::load(path)
+            .wrap_err_with(|| format!("Failed to load state from {}", path.display()))?;
+        Ok(Some(state))
+    }
+
+    fn pool_config(&self) -> PoolConfig {
+        PoolConfig {
+            max_transactions: self.max_pool_transactions,
+            max_transactions_per_sender: self.max_pool_transactions_per_sender,
+            replacement_fee_bump: self.replacement_fee_bump,
+        }
     }
 
     fn account_generator(&self) -> AccountGenerator {
@@ -128,11 +208,15 @@ impl NodeArgs {
     ///
     /// See also [crate::spawn()]
     pub async fn run(self) -> Result<(), Box<dyn std::error::Error>> {
-        let (api, handle) = crate::spawn(self.into_node_config()).await;
+        let state_path = self.state.clone();
+        let (api, handle) = crate::spawn(self.into_node_config()?).await;
 
         // sets the signal handler to gracefully shutdown.
         let fork = api.get_fork().cloned();
         let running = Arc::new(AtomicUsize::new(0));
+        let signal_api = api.clone();
+        let signal_state_path = state_path.clone();
+        let signal_ipc_path = handle.ipc_path().map(Path::to_path_buf);
 
         ctrlc::set_handler(move || {
             let prev = running.fetch_add(1, Ordering::SeqCst);
@@ -143,11 +227,60 @@ impl NodeArgs {
                 if let Some(ref fork) = fork {
                     fork.database.read().flush_cache();
                 }
+                if let Some(ref path) = signal_state_path {
+                    dump_state(&signal_api, path);
+                }
+                // exiting skips the ipc server's cleanup
+                if let Some(ref path) = signal_ipc_path {
+                    let _ = std::fs::remove_file(path);
+                }
                 std::process::exit(0);
             }
         })
         .expect("Error setting Ctrl-C handler");
 
-        Ok(handle.await??)
+        let res = handle.await;
+
+        // the node also stops if the server fails
+        if let Some(ref path) = state_path {
+            dump_state(&api, path);
+        }
+
+        Ok(res??)
+    }
+}
+
+/// Writes the current state of the node to the `--state` file
+fn dump_state(api: &EthApi, path: &Path) {
+    trace!("dumping state to {}", path.display());
+    match api.serialized_state() {
+        Ok(state) => {
+            if let Err(err) = state.write(path) {
+                eprintln!("Failed to dump state to {}: {}", path.display(), err);
+            }
+        }
+        Err(err) => eprintln!("Failed to dump state: {}", err),
+    }
+}
+
+/// Parses a `<hardfork>@<block>` hardfork activation
+fn parse_hardfork_activation(s: &str) -> Result<(u64, Hardfork), String> {
+    let (hardfork, block) =
+        s.split_once('@').ok_or_else(|| format!("Expected `<hardfork>@<block>`, got `{}`", s))?;
+    let block =
+        block.trim().parse::<u64>().map_err(|err| format!("Invalid block number: {}", err))?;
+    Ok((block, hardfork.trim().parse()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_hardfork_activation() {
+        assert_eq!(parse_hardfork_activation("london@100").unwrap(), (100, Hardfork::London));
+        assert_eq!(parse_hardfork_activation("Latest@0").unwrap(), (0, Hardfork::Latest));
+        assert!(parse_hardfork_activation("london").is_err());
+        assert!(parse_hardfork_activation("london@x").is_err());
     }
 }
@@ -1,13 +1,13 @@
 use crate::{
     eth::{
         backend::{
-            db::Db,
+            db::{Db, SerializableState},
             fork::{ClientFork, ClientForkConfig},
             genesis::GenesisConfig,
             mem::fork_db::ForkedDatabase,
         },
         fees::INITIAL_BASE_FEE,
-        pool::transactions::TransactionOrder,
+        pool::{transactions::TransactionOrder, PoolConfig},
     },
     mem,
     mem::in_memory_db::MemDb,
@@ -33,12 +33,16 @@ use foundry_evm::{
     revm::{BlockEnv, CfgEnv, SpecId, TxEnv},
 };
 use parking_lot::RwLock;
-use std::{net::IpAddr, path::PathBuf, str::FromStr, sync::Arc, time::Duration};
+use std::{
+    collections::BTreeMap, net::IpAddr, path::PathBuf, str::FromStr, sync::Arc, time::Duration,
+};
 
 /// Default port the rpc will open
 pub const NODE_PORT: u16 = 8545;
 /// Default chain id of the node
 pub const CHAIN_ID: u64 = 31337;
+/// Default path of the IPC socket
+pub const DEFAULT_IPC_ENDPOINT: &str = "/tmp/anvil.ipc";
 /// Default mnemonic for dev accounts
 pub const DEFAULT_MNEMONIC: &str = "test test test test test test test test test test test junk";
 
@@ -74,6 +78,8 @@ pub struct NodeConfig {
     pub base_fee: U256,
     /// The hardfork to use
     pub hardfork: Hardfork,
+    /// Hardforks that are activated at the given block numbers, replacing `hardfork`
+    pub hardfork_activations: BTreeMap<u64, Hardfork>,
     /// Signer accounts that will be initialised with `genesis_balance` in the genesis block
     pub genesis_accounts: Vec<Wallet<SigningKey>>,
     /// Native token balance of every genesis account in the genesis block
@@ -106,6 +112,12 @@ pub struct NodeConfig {
     pub host: Option<IpAddr>,
     /// How transactions are sorted in the mempool
     pub transaction_order: TransactionOrder,
+    /// The limits of the mempool
+    pub pool_config: PoolConfig,
+    /// The state to load on startup, as returned by `anvil_dumpState`
+    pub init_state: Option<SerializableState>,
+    /// The path of the IPC socket to serve the rpc on, if any
+    pub ipc_path: Option<PathBuf>,
 }
 
 // === impl NodeConfig ===
@@ -127,6 +139,7 @@ impl Default for NodeConfig {
             gas_limit: U256::from(30_000_000),
             gas_price: U256::from(20_000_000_000u64),
             hardfork: Hardfork::default(),
+            hardfork_activations: Default::default(),
             signer_accounts: genesis_accounts.clone(),
             genesis_accounts,
             // 100ETH default balance
@@ -146,6 +159,9 @@ impl Default for NodeConfig {
             server_config: Default::default(),
             host: None,
             transaction_order: Default::default(),
+            pool_config: Default::default(),
+            init_state: None,
+            ipc_path: None,
         }
     }
 }
@@ -209,6 +225,25 @@ impl NodeConfig {
         self
     }
 
+    /// Schedules hardforks to be activated at the given block numbers
+    #[must_use]
+    pub fn with_hardfork_activations(
+        mut self,
+        activations: impl IntoIterator<Item = (u64, Hardfork)>,
+    ) -> Self {
+        self.hardfork_activations.extend(activations);
+        self
+    }
+
+    /// Returns the hardforks of the chain by the block number they're activated at
+    pub fn hardforks(&self) -> HardforkSchedule {
+        self.hardfork_activations
+            .iter()
+            .fold(HardforkSchedule::new(self.hardfork), |schedule, (block, hardfork)| {
+                schedule.with_activation(*block, *hardfork)
+            })
+    }
+
     /// Sets the genesis accounts
     #[must_use]
     pub fn with_genesis_accounts(mut self, accounts: Vec<Wallet<SigningKey>>) -> Self {
@@ -324,6 +359,27 @@ impl NodeConfig {
         self
     }
 
+    /// Sets the limits of the mempool
+    #[must_use]
+    pub fn with_pool_config(mut self, pool_config: PoolConfig) -> Self {
+        self.pool_config = pool_config;
+        self
+    }
+
+    /// Sets the state to load on startup
+    #[must_use]
+    pub fn with_init_state(mut self, init_state: Option<SerializableState>) -> Self {
+        self.init_state = init_state;
+        self
+    }
+
+    /// Sets the path of the IPC socket, `None` disables IPC
+    #[must_use]
+    pub fn with_ipc(mut self, ipc_path: Option<impl Into<PathBuf>>) -> Self {
+        self.ipc_path = ipc_path.map(Into::into);
+        self
+    }
+
     /// Prints the config info
     pub fn print(&self, fork: Option<&ClientFork>) {
         if self.silent {
@@ -434,10 +490,11 @@ Chain ID:       {}
     ///
     /// *Note*: only memory based backend for now
     pub(crate) async fn setup(&mut self) -> mem::Backend {
+        let hardforks = self.hardforks();
         // configure the revm environment
         let mut env = revm::Env {
             cfg: CfgEnv {
-                spec_id: self.hardfork.into(),
+                spec_id: hardforks.spec_id_at(0u64),
                 chain_id: self.chain_id.into(),
                 ..Default::default()
             },
@@ -472,6 +529,7 @@ Chain ID:       {}
                 .unwrap();
 
             env.block.number = fork_block_number.into();
+            env.cfg.spec_id = hardforks.spec_id_at(fork_block_number);
             fork_timestamp = Some(block.timestamp);
 
             let block_hash = block.hash.unwrap();
@@ -517,16 +575,30 @@ Chain ID:       {}
         };
         // only memory based backend for now
 
-        let backend =
-            mem::Backend::with_genesis(db, Arc::new(RwLock::new(env)), genesis, fees, fork);
+        let backend = mem::Backend::with_genesis(
+            db,
+            Arc::new(RwLock::new(env)),
+            genesis,
+            fees,
+            fork,
+            hardforks,
+        );
 
         if let Some(timestamp) = fork_timestamp {
             backend.time().set_start_timestamp(timestamp.as_u64());
         }
+
+        if let Some(state) = self.init_state.take() {
+            backend.load_state(state);
+        }
         backend
     }
 }
 
+/// The supported hardforks.
+///
+/// Hardforks after London (e.g. Shanghai) are not supported, because the EVM anvil runs on doesn't
+/// implement their opcodes yet.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum Hardfork {
     Frontier,
@@ -592,6 +664,43 @@ impl From<Hardfork> for SpecId {
     }
 }
 
+/// The hardforks of a chain, by the block number they're activated at
+#[derive(Debug, Clone)]
+pub struct HardforkSchedule {
+    /// The spec the chain starts with
+    genesis: SpecId,
+    /// All specs that replace the previous one at the block number
+    activations: BTreeMap<u64, SpecId>,
+}
+
+// === impl HardforkSchedule ===
+
+impl HardforkSchedule {
+    /// Creates a schedule that uses the given hardfork for all blocks
+    pub fn new(genesis: impl Into<SpecId>) -> Self {
+        Self { genesis: genesis.into(), activations: Default::default() }
+    }
+
+    /// Activates the hardfork at the given block number
+    #[must_use]
+    pub fn with_activation(mut self, block: u64, hardfork: impl Into<SpecId>) -> Self {
+        self.activations.insert(block, hardfork.into());
+        self
+    }
+
+    /// Returns the spec that's active at the given block number
+    ///
+    /// Block numbers that don't fit into a `u64` are treated as `u64::MAX`.
+    pub fn spec_id_at(&self, block: impl TryInto<u64>) -> SpecId {
+        let block = block.try_into().unwrap_or(u64::MAX);
+        self.activations
+            .range(..=block)
+            .next_back()
+            .map(|(_, spec_id)| *spec_id)
+            .unwrap_or(self.genesis)
+    }
+}
+
 /// Can create dev accounts
 #[derive(Debug, Clone)]
 pub struct AccountGenerator {
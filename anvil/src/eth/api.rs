@@ -1,7 +1,10 @@
 use crate::{
     eth::{
         backend,
-        backend::{notifications::NewBlockNotifications, validate::TransactionValidator},
+        backend::{
+            db::SerializableState, notifications::NewBlockNotifications,
+            validate::TransactionValidator,
+        },
         error::{
             BlockchainError, FeeHistoryError, InvalidTransactionError, Result, ToRpcResponseResult,
         },
@@ -34,7 +37,7 @@ use anvil_core::{
         },
         EthRequest,
     },
-    types::{EvmMineOptions, Forking, GethDebugTracingOptions, Index, Work},
+    types::{EvmMineOptions, Forking, GethDebugTracingOptions, GethTrace, Index, Work},
 };
 use anvil_rpc::response::ResponseResult;
 use ethers::{
@@ -259,6 +262,8 @@ impl EthApi {
             EthRequest::SetNextBlockBaseFeePerGas(gas) => {
                 self.anvil_set_next_block_base_fee_per_gas(gas).await.to_rpc_result()
             }
+            EthRequest::DumpState(_) => self.anvil_dump_state().await.to_rpc_result(),
+            EthRequest::LoadState(state) => self.anvil_load_state(state).await.to_rpc_result(),
             EthRequest::EvmSnapshot(_) => self.evm_snapshot().await.to_rpc_result(),
             EthRequest::EvmRevert(id) => self.evm_revert(id).await.to_rpc_result(),
             EthRequest::EvmIncreaseTime(time) => self.evm_increase_time(time).await.to_rpc_result(),
@@ -1139,11 +1144,11 @@ impl EthApi {
     /// Handler for RPC call: `debug_traceTransaction`
     pub async fn debug_trace_transaction(
         &self,
-        _tx_hash: H256,
-        _opts: GethDebugTracingOptions,
-    ) -> Result<Vec<Trace>> {
+        tx_hash: H256,
+        opts: GethDebugTracingOptions,
+    ) -> Result<GethTrace> {
         node_info!("debug_traceTransaction");
-        Err(BlockchainError::RpcUnimplemented)
+        self.backend.debug_trace_transaction(tx_hash, opts).await
     }
 
     /// Returns traces for the transaction hash via parity's tracing endpoint
@@ -1344,6 +1349,34 @@ impl EthApi {
         Ok(())
     }
 
+    /// Returns all accounts, their code and storage, and the block history of the chain as a
+    /// JSON document.
+    ///
+    /// Handler for RPC call: `anvil_dumpState`
+    pub async fn anvil_dump_state(&self) -> Result<Bytes> {
+        node_info!("anvil_dumpState");
+        let state = self.serialized_state()?;
+        let json =
+            serde_json::to_vec(&state).map_err(|err| BlockchainError::Internal(err.to_string()))?;
+        Ok(json.into())
+    }
+
+    /// Returns all accounts, their code and storage, and the block history of the chain
+    pub fn serialized_state(&self) -> Result<SerializableState> {
+        self.backend.dump_state()
+    }
+
+    /// Loads a state previously returned by `anvil_dumpState`, merging it into the current state.
+    ///
+    /// Handler for RPC call: `anvil_loadState`
+    pub async fn anvil_load_state(&self, state: Bytes) -> Result<bool> {
+        node_info!("anvil_loadState");
+        let state: SerializableState =
+            serde_json::from_slice(&state).map_err(|_| BlockchainError::FailedToDecodeStateDump)?;
+        self.backend.load_state(state);
+        Ok(true)
+    }
+
     /// Snapshot the state of the blockchain at the current block.
     ///
     /// Handler for RPC call: `evm_snapshot`
@@ -1614,6 +1647,11 @@ impl EthApi {
         self.pool.add_ready_listener()
     }
 
+    /// Returns a new listeners for transactions that are evicted from the full pool
+    pub fn dropped_transactions(&self) -> Receiver<TxHash> {
+        self.pool.add_dropped_listener()
+    }
+
     /// Returns a new accessor for certain storage elements
     pub fn storage_info(&self) -> StorageInfo {
         StorageInfo::new(Arc::clone(&self.backend))
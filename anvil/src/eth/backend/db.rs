@@ -1,14 +1,18 @@
 //! Helper types for working with [revm](foundry_evm::revm)
 
-use crate::{revm::AccountInfo, U256};
+use crate::{mem::storage::MinedTransaction, revm::AccountInfo, U256};
+use anvil_core::eth::block::Block;
 use ethers::{
     prelude::{Address, Bytes, H160},
     types::H256,
+    utils::keccak256,
 };
 use foundry_evm::{
     executor::DatabaseRef,
-    revm::{db::CacheDB, Database, DatabaseCommit, InMemoryDB},
+    revm::{db::CacheDB, Database, DatabaseCommit, InMemoryDB, KECCAK_EMPTY},
 };
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::Path};
 
 /// This bundles all required revm traits
 pub trait Db: DatabaseRef + Database + DatabaseCommit + Send + Sync {
@@ -54,6 +58,36 @@ pub trait Db: DatabaseRef + Database + DatabaseCommit + Send + Sync {
 
     /// Returns the current, standalone state of the Db
     fn current_state(&self) -> StateDb;
+
+    /// Returns all accounts of the Db with their code and storage
+    ///
+    /// Returns `None` if the Db does not support dumping its state
+    fn dump_state(&self) -> Option<SerializableState> {
+        None
+    }
+
+    /// Inserts all accounts of the given state, replacing existing accounts
+    fn load_state(&mut self, state: SerializableState) {
+        for (address, account) in state.accounts {
+            let code_hash = if account.code.as_ref().is_empty() {
+                KECCAK_EMPTY
+            } else {
+                H256::from_slice(&keccak256(account.code.as_ref()))
+            };
+            self.insert_account(
+                address,
+                AccountInfo {
+                    balance: account.balance,
+                    nonce: account.nonce,
+                    code_hash,
+                    code: Some(account.code.0),
+                },
+            );
+            for (slot, value) in account.storage {
+                self.set_storage_at(address, slot, value);
+            }
+        }
+    }
 }
 
 /// Convenience impl only used to use any `Db` on the fly as the db layer for revm's CacheDB
@@ -82,6 +116,70 @@ impl<T: DatabaseRef + Send + Sync + Clone> Db for CacheDB<T> {
     }
 }
 
+/// The state of a node in a portable format, as returned by `anvil_dumpState`
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SerializableState {
+    /// All accounts with their code and storage
+    pub accounts: BTreeMap<Address, SerializableAccountRecord>,
+    /// All blocks of the chain, ordered by number
+    #[serde(default)]
+    pub blocks: Vec<Block>,
+    /// All mined transactions with their receipts
+    #[serde(default)]
+    pub transactions: Vec<MinedTransaction>,
+}
+
+// === impl SerializableState ===
+
+impl SerializableState {
+    /// Loads the state from a JSON file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, StateFileError> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Writes the state to a JSON file
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), StateFileError> {
+        std::fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+/// An error that occurred while reading or writing a state file
+#[derive(thiserror::Error, Debug)]
+pub enum StateFileError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/// A single account of a [SerializableState]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SerializableAccountRecord {
+    pub nonce: u64,
+    pub balance: U256,
+    pub code: Bytes,
+    pub storage: BTreeMap<U256, U256>,
+}
+
+// === impl SerializableAccountRecord ===
+
+impl SerializableAccountRecord {
+    /// Creates a new record from the account info, using `db` to look up the code if it is not
+    /// part of the info
+    pub fn new(info: &AccountInfo, storage: BTreeMap<U256, U256>, db: &impl DatabaseRef) -> Self {
+        let code = match info.code.clone() {
+            Some(code) => code,
+            None if info.code_hash == KECCAK_EMPTY || info.code_hash.is_zero() => {
+                Default::default()
+            }
+            None => db.code_by_hash(info.code_hash),
+        };
+        Self { nonce: info.nonce, balance: info.balance, code: code.into(), storage }
+    }
+}
+
 /// Represents a state at certain point
 pub struct StateDb(Box<dyn DatabaseRef + Send + Sync>);
 
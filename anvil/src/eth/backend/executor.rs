@@ -11,7 +11,7 @@ use anvil_core::eth::{
 };
 use ethers::{
     abi::ethereum_types::BloomInput,
-    types::{Bloom, H256, U256},
+    types::{Address, Bloom, H256, U256},
     utils::rlp,
 };
 use foundry_evm::{
@@ -20,7 +20,7 @@ use foundry_evm::{
     revm::{BlockEnv, CfgEnv, Env, Return, TransactOut},
     trace::node::CallTraceNode,
 };
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 use tracing::{trace, warn};
 
 /// Represents an executed transaction (transacted on the DB)
@@ -93,6 +93,11 @@ pub struct TransactionExecutor<'a, Db: ?Sized, Validator: TransactionValidator>
     pub parent_hash: H256,
     /// Cumulative gas used by all executed transactions
     pub gas_used: U256,
+    /// senders of transactions that didn't fit into the block anymore
+    ///
+    /// Their subsequent transactions are skipped as well, since they depend on the nonce of the
+    /// skipped transaction
+    pub exhausted: HashSet<Address>,
 }
 
 impl<'a, DB: Db + ?Sized, Validator: TransactionValidator> TransactionExecutor<'a, DB, Validator> {
@@ -112,7 +117,7 @@ impl<'a, DB: Db + ?Sized, Validator: TransactionValidator> TransactionExecutor<'
         let beneficiary = self.block_env.coinbase;
         let timestamp = self.block_env.timestamp.as_u64();
 
-        for (idx, tx) in self.enumerate() {
+        for tx in &mut self {
             let tx = match tx {
                 TransactionExecutionOutcome::Executed(tx) => {
                     included.push(tx.transaction.clone());
@@ -137,7 +142,7 @@ impl<'a, DB: Db + ?Sized, Validator: TransactionValidator> TransactionExecutor<'
             };
             let info = TransactionInfo {
                 transaction_hash: *transaction.hash(),
-                transaction_index: idx as u32,
+                transaction_index: transactions.len() as u32,
                 from: *transaction.pending_transaction.sender(),
                 to: transaction.pending_transaction.transaction.to().copied(),
                 contract_address,
@@ -197,14 +202,14 @@ impl<'a, 'b, DB: Db + ?Sized, Validator: TransactionValidator> Iterator
 
     fn next(&mut self) -> Option<Self::Item> {
         let transaction = self.pending.next()?;
-        let account = self.db.basic(*transaction.pending_transaction.sender());
-        let env = self.env_for(&transaction.pending_transaction);
-        // check that we comply with the block's gas limit
-        let max_gas = self.gas_used.saturating_add(U256::from(env.tx.gas_limit));
-        if max_gas > env.block.gas_limit {
+        let sender = *transaction.pending_transaction.sender();
+        if self.exhausted.contains(&sender) {
             return Some(TransactionExecutionOutcome::Exhausted(transaction))
         }
 
+        let account = self.db.basic(sender);
+        let env = self.env_for(&transaction.pending_transaction);
+
         // validate before executing
         if let Err(err) = self.validator.validate_pool_transaction_for(
             &transaction.pending_transaction,
@@ -215,6 +220,15 @@ impl<'a, 'b, DB: Db + ?Sized, Validator: TransactionValidator> Iterator
             return Some(TransactionExecutionOutcome::Invalid(transaction, err))
         }
 
+        // check that we comply with the block's gas limit, the transaction stays in the pool for
+        // the next block if not
+        let max_gas = self.gas_used.saturating_add(U256::from(env.tx.gas_limit));
+        if max_gas > env.block.gas_limit {
+            trace!(target: "backend", "[{:?}] exceeds the remaining block gas", transaction.hash());
+            self.exhausted.insert(sender);
+            return Some(TransactionExecutionOutcome::Exhausted(transaction))
+        }
+
         let mut evm = revm::EVM::new();
         evm.env = env;
         evm.database(&mut self.db);
@@ -233,7 +247,7 @@ impl<'a, 'b, DB: Db + ?Sized, Validator: TransactionValidator> Iterator
 
         trace!(target: "backend", "[{:?}] executed with out={:?}, gas ={}", transaction.hash(), out, gas);
 
-        self.gas_used.saturating_add(U256::from(gas));
+        self.gas_used = self.gas_used.saturating_add(U256::from(gas));
 
         trace!(target: "backend::executor", "transacted [{:?}], result: {:?} gas {}", transaction.hash(), exit, gas);
 
@@ -1,6 +1,6 @@
 use crate::{
     eth::{
-        backend::db::{Db, StateDb},
+        backend::db::{Db, SerializableAccountRecord, SerializableState, StateDb},
         error::BlockchainError,
     },
     mem::snapshot::Snapshots,
@@ -70,6 +70,28 @@ impl Db for ForkedDatabase {
     fn current_state(&self) -> StateDb {
         StateDb::new(self.create_snapshot())
     }
+
+    /// Dumps all accounts that were fetched from the remote client, with the local changes applied
+    fn dump_state(&self) -> Option<SerializableState> {
+        let db = self.db.db();
+        let mut infos = db.accounts.read().clone();
+        let mut storage = db.storage.read().clone();
+
+        infos.extend(self.cache_db.cache().iter().map(|(k, v)| (*k, v.clone())));
+        for (address, slots) in self.cache_db.storage() {
+            storage.entry(*address).or_default().extend(slots.iter().map(|(k, v)| (*k, *v)));
+        }
+
+        let accounts = infos
+            .into_iter()
+            .map(|(address, info)| {
+                let storage = storage.remove(&address).unwrap_or_default();
+                (address, SerializableAccountRecord::new(&info, storage, &self.cache_db))
+            })
+            .collect();
+
+        Some(SerializableState { accounts, ..Default::default() })
+    }
 }
 
 /// a [revm::Database] that's forked off another client
@@ -1,7 +1,7 @@
 //! The in memory DB
 
 use crate::{
-    eth::backend::db::{Db, StateDb},
+    eth::backend::db::{Db, SerializableAccountRecord, SerializableState, StateDb},
     mem::{snapshot::Snapshots, state::state_merkle_trie_root},
     revm::{db::DatabaseRef, Account, AccountInfo, Database, DatabaseCommit},
     Address, U256,
@@ -102,4 +102,23 @@ impl Db for MemDb {
     fn current_state(&self) -> StateDb {
         StateDb::new(self.inner.clone())
     }
+
+    fn dump_state(&self) -> Option<SerializableState> {
+        let accounts = self
+            .inner
+            .cache()
+            .iter()
+            .map(|(address, info)| {
+                let storage = self
+                    .inner
+                    .storage()
+                    .get(address)
+                    .map(|storage| storage.iter().map(|(k, v)| (*k, *v)).collect())
+                    .unwrap_or_default();
+                (*address, SerializableAccountRecord::new(info, storage, &self.inner))
+            })
+            .collect();
+
+        Some(SerializableState { accounts, ..Default::default() })
+    }
 }
@@ -1,15 +1,17 @@
 //! In memory blockchain backend
 
 use crate::{
+    config::HardforkSchedule,
     eth::{
         backend::{
             cheats,
             cheats::CheatsManager,
-            db::Db,
+            db::{Db, SerializableState},
             executor::{ExecutedTransactions, TransactionExecutor},
             fork::ClientFork,
             genesis::GenesisConfig,
             notifications::{NewBlockNotification, NewBlockNotifications},
+            struct_logger::StructLogger,
             time::{utc_from_secs, TimeManager},
             validate::TransactionValidator,
         },
@@ -33,7 +35,7 @@ use anvil_core::{
         transaction::{PendingTransaction, TransactionInfo, TypedTransaction},
         utils::to_access_list,
     },
-    types::{Forking, Index},
+    types::{DefaultFrame, Forking, GethDebugTracingOptions, GethTrace, Index},
 };
 use anvil_rpc::error::RpcError;
 use ethers::{
@@ -46,7 +48,9 @@ use ethers::{
 };
 use foundry_evm::{
     revm,
-    revm::{db::CacheDB, Account, CreateScheme, Env, Return, TransactOut, TransactTo, TxEnv},
+    revm::{
+        db::CacheDB, Account, BlockEnv, CreateScheme, Env, Return, TransactOut, TransactTo, TxEnv,
+    },
     utils::u256_to_h256_be,
 };
 use futures::channel::mpsc::{unbounded, UnboundedSender};
@@ -90,11 +94,14 @@ pub struct Backend {
     new_block_listeners: Arc<Mutex<Vec<UnboundedSender<NewBlockNotification>>>>,
     /// keeps track of active snapshots at a specific block
     active_snapshots: Arc<Mutex<HashMap<U256, (u64, H256)>>>,
+    /// the hardforks by the block number they're activated at
+    hardforks: HardforkSchedule,
 }
 
 impl Backend {
     /// Create a new instance of in-mem backend.
     pub fn new(db: Arc<RwLock<dyn Db>>, env: Arc<RwLock<Env>>, fees: FeeManager) -> Self {
+        let hardforks = HardforkSchedule::new(env.read().cfg.spec_id);
         Self {
             db,
             blockchain: Blockchain::default(),
@@ -107,6 +114,7 @@ impl Backend {
             fees,
             genesis: Default::default(),
             active_snapshots: Arc::new(Mutex::new(Default::default())),
+            hardforks,
         }
     }
 
@@ -124,6 +132,7 @@ impl Backend {
         genesis: GenesisConfig,
         fees: FeeManager,
         fork: Option<ClientFork>,
+        hardforks: HardforkSchedule,
     ) -> Self {
         // if this is a fork then adjust the blockchain storage
         let blockchain = if let Some(ref fork) = fork {
@@ -145,6 +154,7 @@ impl Backend {
             fees,
             genesis,
             active_snapshots: Arc::new(Mutex::new(Default::default())),
+            hardforks,
         };
 
         backend.apply_genesis();
@@ -192,6 +202,7 @@ impl Backend {
                 let mut env = self.env.write();
                 env.cfg.chain_id = fork.chain_id().into();
                 env.block.number = fork.block_number().into();
+                env.cfg.spec_id = self.hardforks.spec_id_at(fork.block_number());
                 self.time.set_start_timestamp(fork.timestamp());
             }
 
@@ -243,10 +254,11 @@ impl Backend {
         num.into()
     }
 
-    /// Sets the block number
+    /// Sets the block number and the hardfork that's active at that block
     pub fn set_block_number(&self, number: U256) {
         let mut env = self.env.write();
         env.block.number = number;
+        env.cfg.spec_id = self.hardforks.spec_id_at(number);
     }
 
     /// Returns the client coinbase address.
@@ -364,11 +376,51 @@ impl Backend {
         self.db.write().revert(id)
     }
 
+    /// Returns all accounts and the block history of the chain in a portable format
+    pub fn dump_state(&self) -> Result<SerializableState, BlockchainError> {
+        let mut state = self.db.read().dump_state().ok_or_else(|| {
+            BlockchainError::Internal("Dumping the state is not supported".to_string())
+        })?;
+        let (blocks, transactions) = self.blockchain.storage.read().serialized_history();
+        state.blocks = blocks;
+        state.transactions = transactions;
+        Ok(state)
+    }
+
+    /// Loads the accounts and the block history of a previously dumped state
+    ///
+    /// If the dumped chain is ahead of the current chain, the chain continues from the latest
+    /// dumped block.
+    pub fn load_state(&self, mut state: SerializableState) {
+        let blocks = std::mem::take(&mut state.blocks);
+        let transactions = std::mem::take(&mut state.transactions);
+        self.db.write().load_state(state);
+
+        let (best_number, best_timestamp) = {
+            let mut storage = self.blockchain.storage.write();
+            storage.load_history(blocks, transactions);
+            let timestamp = storage.blocks.get(&storage.best_hash).map(|b| b.header.timestamp);
+            (storage.best_number, timestamp)
+        };
+
+        trace!(target: "backend", "loaded state at block {}", best_number);
+        if best_number.as_u64() > self.best_number().as_u64() {
+            self.set_block_number(best_number.as_u64().into());
+        }
+        if let Some(timestamp) = best_timestamp {
+            // the timestamps of new blocks must not go backwards
+            if timestamp > self.time.current_call_timestamp() {
+                self.time.set_start_timestamp(timestamp);
+            }
+        }
+    }
+
     /// Returns the environment for the next block
     fn next_env(&self) -> Env {
         let mut env = self.env.read().clone();
         // increase block number for this block
         env.block.number = env.block.number.saturating_add(U256::one());
+        env.cfg.spec_id = self.hardforks.spec_id_at(env.block.number);
         env.block.basefee = self.base_fee();
         env.block.timestamp = self.time.current_call_timestamp().into();
         env
@@ -408,6 +460,7 @@ impl Backend {
             cfg_env: env.cfg,
             parent_hash: storage.best_hash,
             gas_used: U256::zero(),
+            exhausted: Default::default(),
         };
 
         // create a new pending block
@@ -434,6 +487,13 @@ impl Backend {
         // increase block number for this block
         env.block.number = env.block.number.saturating_add(U256::one());
         env.block.basefee = current_base_fee;
+
+        // switch to the hardfork that's active at this block
+        let spec_id = self.hardforks.spec_id_at(env.block.number);
+        if spec_id != env.cfg.spec_id {
+            trace!(target: "backend", "activating {:?} at block {}", spec_id, env.block.number);
+            env.cfg.spec_id = spec_id;
+        }
         env.block.timestamp = self.time.next_timestamp().into();
 
         let executor = TransactionExecutor {
@@ -444,6 +504,7 @@ impl Backend {
             cfg_env: env.cfg.clone(),
             parent_hash: storage.best_hash,
             gas_used: U256::zero(),
+            exhausted: Default::default(),
         };
 
         // create the new block with the current timestamp
@@ -469,6 +530,7 @@ impl Backend {
 
         storage.blocks.insert(block_hash, block);
         storage.hashes.insert(block_number, block_hash);
+        storage.base_fees.insert(block_hash, env.block.basefee);
 
         node_info!("");
         // insert all transactions
@@ -554,6 +616,7 @@ impl Backend {
             {
                 let mut evm = revm::EVM::new();
                 env.block.number = block_number;
+                env.cfg.spec_id = self.hardforks.spec_id_at(block_number);
                 evm.env = env;
                 evm.database(state);
 
@@ -1021,6 +1084,100 @@ impl Backend {
         self.blockchain.storage.read().transactions.get(&hash).map(|tx| tx.parity_traces())
     }
 
+    /// Returns the geth style trace of the given transaction for `debug_traceTransaction`
+    pub async fn debug_trace_transaction(
+        &self,
+        hash: H256,
+        opts: GethDebugTracingOptions,
+    ) -> Result<GethTrace, BlockchainError> {
+        tokio::task::block_in_place(|| self.mined_geth_trace_transaction(hash, &opts))
+            .unwrap_or(Err(BlockchainError::TransactionNotFound(hash)))
+    }
+
+    /// Returns the geth style trace of the given mined transaction
+    ///
+    /// The default struct logger replays the transaction on top of the state of its parent block
+    /// and all transactions that precede it in the block.
+    pub fn mined_geth_trace_transaction(
+        &self,
+        hash: H256,
+        opts: &GethDebugTracingOptions,
+    ) -> Option<Result<GethTrace, BlockchainError>> {
+        let storage = self.blockchain.storage.read();
+        let tx = storage.transactions.get(&hash)?;
+
+        match opts.tracer.as_deref() {
+            None => {}
+            Some("callTracer") => {
+                return Some(Ok(GethTrace::CallTracer(tx.geth_call_frame().unwrap_or_default())))
+            }
+            Some(tracer) => {
+                return Some(Err(RpcError::invalid_params(format!(
+                    "unsupported tracer `{}`",
+                    tracer
+                ))
+                .into()))
+            }
+        }
+
+        let block = storage.blocks.get(&tx.block_hash)?;
+        let states = self.states.read();
+        let state = match states.get(&block.header.parent_hash) {
+            Some(state) => state,
+            None => {
+                warn!(target: "backend", "Not historic state found for block={}", tx.block_number);
+                return Some(Err(BlockchainError::BlockOutOfRange(
+                    self.best_number().as_u64(),
+                    tx.block_number,
+                )))
+            }
+        };
+        let mut cache_db = CacheDB::new(state);
+
+        let mut env = self.env.read().clone();
+        env.block = BlockEnv {
+            number: block.header.number,
+            coinbase: block.header.beneficiary,
+            timestamp: block.header.timestamp.into(),
+            difficulty: block.header.difficulty,
+            // blocks that were not mined by this node, e.g. forked blocks, have no base fee stored
+            basefee: storage.base_fees.get(&tx.block_hash).copied().unwrap_or_default(),
+            gas_limit: block.header.gas_limit,
+        };
+        env.cfg.spec_id = self.hardforks.spec_id_at(block.header.number);
+
+        for transaction in block.transactions.iter() {
+            let tx_hash = transaction.hash();
+            let sender = storage.transactions.get(&tx_hash)?.info.from;
+            env.tx = PendingTransaction::with_sender(transaction.clone(), sender).to_revm_tx_env();
+
+            let mut evm = revm::EVM::new();
+            evm.env = env.clone();
+            evm.database(&mut cache_db);
+
+            if tx_hash != hash {
+                evm.transact_commit();
+                continue
+            }
+
+            let mut logger = StructLogger::new(opts);
+            let (exit, out, gas, _) = evm.inspect_commit(&mut logger);
+            let return_value = match out {
+                TransactOut::None => Default::default(),
+                TransactOut::Call(out) => out.into(),
+                TransactOut::Create(out, _) => out.into(),
+            };
+            return Some(Ok(GethTrace::Default(DefaultFrame {
+                failed: exit as u8 > Return::SelfDestruct as u8,
+                gas,
+                return_value,
+                struct_logs: logger.logs,
+            })))
+        }
+
+        None
+    }
+
     /// Returns the traces for the given transaction
     pub fn mined_parity_trace_block(&self, block: u64) -> Option<Vec<Trace>> {
         let block = self.get_block(block)?;
@@ -7,9 +7,12 @@ use anvil_core::eth::{
     block::{Block, PartialHeader},
     receipt::TypedReceipt,
     transaction::TransactionInfo,
+    types::CallFrame,
 };
-use ethers::prelude::{BlockId, BlockNumber, Trace, H256, H256 as TxHash, U64};
+use ethers::prelude::{BlockId, BlockNumber, Trace, H256, H256 as TxHash, U256, U64};
+use foundry_evm::{revm::Return, CallKind};
 use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
 use std::{
     collections::{HashMap, VecDeque},
     fmt,
@@ -91,6 +94,8 @@ pub struct BlockchainStorage {
     /// Mapping from the transaction hash to a tuple containing the transaction as well as the
     /// transaction receipt
     pub transactions: HashMap<TxHash, MinedTransaction>,
+    /// The base fee of the mined blocks (block hash -> base fee)
+    pub base_fees: HashMap<H256, U256>,
 }
 
 impl BlockchainStorage {
@@ -102,6 +107,7 @@ impl BlockchainStorage {
             best_number: block_number.into(),
             genesis_hash: Default::default(),
             transactions: Default::default(),
+            base_fees: Default::default(),
         }
     }
 
@@ -114,6 +120,7 @@ impl BlockchainStorage {
             best_number: Default::default(),
             genesis_hash: Default::default(),
             transactions: Default::default(),
+            base_fees: Default::default(),
         }
     }
 }
@@ -137,6 +144,7 @@ impl Default for BlockchainStorage {
             best_number,
             genesis_hash,
             transactions: Default::default(),
+            base_fees: Default::default(),
         }
     }
 }
@@ -153,6 +161,46 @@ impl BlockchainStorage {
             BlockNumber::Number(num) => self.hashes.get(&num).copied(),
         }
     }
+
+    /// Returns all blocks and all mined transactions, ordered by block number
+    pub fn serialized_history(&self) -> (Vec<Block>, Vec<MinedTransaction>) {
+        let mut blocks: Vec<_> = self.blocks.values().cloned().collect();
+        blocks.sort_by_key(|block| block.header.number);
+
+        let mut transactions: Vec<_> = self.transactions.values().cloned().collect();
+        transactions.sort_by_key(|tx| (tx.block_number, tx.info.transaction_index));
+
+        (blocks, transactions)
+    }
+
+    /// Inserts the given blocks and mined transactions, replacing existing blocks with the same
+    /// number
+    ///
+    /// The latest block becomes the best block if it is not older than the current best block.
+    pub fn load_history(&mut self, blocks: Vec<Block>, transactions: Vec<MinedTransaction>) {
+        for block in blocks {
+            let number: U64 = block.header.number.as_u64().into();
+            let hash = block.header.hash();
+
+            if let Some(replaced) = self.hashes.insert(number, hash) {
+                if replaced != hash {
+                    self.blocks.remove(&replaced);
+                }
+            }
+            if number.is_zero() {
+                self.genesis_hash = hash;
+            }
+            if number >= self.best_number {
+                self.best_number = number;
+                self.best_hash = hash;
+            }
+            self.blocks.insert(hash, block);
+        }
+
+        for tx in transactions {
+            self.transactions.insert(tx.info.transaction_hash, tx);
+        }
+    }
 }
 
 /// A simple in-memory blockchain
@@ -196,7 +244,7 @@ pub struct MinedBlockOutcome {
 }
 
 /// Container type for a mined transaction
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MinedTransaction {
     pub info: TransactionInfo,
     pub receipt: TypedReceipt,
@@ -230,4 +278,51 @@ impl MinedTransaction {
 
         traces
     }
+
+    /// Returns the call frame of the transaction for the `callTracer` of `debug_traceTransaction`
+    pub fn geth_call_frame(&self) -> Option<CallFrame> {
+        if self.info.traces.is_empty() {
+            return None
+        }
+        Some(self.call_frame(0))
+    }
+
+    fn call_frame(&self, idx: usize) -> CallFrame {
+        let node = &self.info.traces[idx];
+        let trace = &node.trace;
+
+        let typ = if trace.status == Return::SelfDestruct {
+            "SELFDESTRUCT"
+        } else {
+            match trace.kind {
+                CallKind::Call => "CALL",
+                CallKind::StaticCall => "STATICCALL",
+                CallKind::CallCode => "CALLCODE",
+                CallKind::DelegateCall => "DELEGATECALL",
+                CallKind::Create => "CREATE",
+            }
+        };
+
+        let error = match trace.status {
+            _ if trace.success => None,
+            Return::Revert => Some("execution reverted".to_string()),
+            status => Some(format!("{:?}", status)),
+        };
+
+        let calls: Vec<_> = node.children.iter().map(|child| self.call_frame(*child)).collect();
+
+        CallFrame {
+            typ: typ.to_string(),
+            from: trace.caller,
+            to: Some(trace.address),
+            value: (trace.kind != CallKind::StaticCall && trace.kind != CallKind::DelegateCall)
+                .then(|| trace.value),
+            gas: trace.gas_limit.into(),
+            gas_used: trace.gas_cost.into(),
+            input: trace.data.to_raw().into(),
+            output: Some(trace.output.to_raw().into()),
+            error,
+            calls: (!calls.is_empty()).then(|| calls),
+        }
+    }
 }
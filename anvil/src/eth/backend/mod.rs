@@ -13,4 +13,5 @@ pub mod fork;
 pub mod genesis;
 pub mod info;
 pub mod notifications;
+pub mod struct_logger;
 pub mod validate;
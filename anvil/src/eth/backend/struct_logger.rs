@@ -0,0 +1,211 @@
+//! A [revm] inspector that records geth style struct logs
+
+use anvil_core::types::{GethDebugTracingOptions, StructLog};
+use ethers::{
+    types::{Address, H256},
+    utils::hex,
+};
+use foundry_evm::{
+    revm::{opcode, spec_opcode_gas, Database, EVMData, Inspector, Interpreter, OpCode, Return},
+    utils::u256_to_h256_be,
+};
+use std::collections::{BTreeMap, HashMap};
+
+/// Gas accounting of a single call frame
+#[derive(Debug)]
+struct Frame {
+    /// The depth of the frame in the journal
+    depth: u64,
+    /// The gas spent in the previous gas block, see `Contract::gas_block`
+    previous_gas_block: u64,
+    /// The gas spent in the current gas block
+    current_gas_block: u64,
+    /// The index of the last log recorded in this frame
+    last_log: Option<usize>,
+}
+
+/// An inspector that records a [StructLog] for every executed opcode, like geth's default tracer
+#[derive(Debug, Default)]
+pub struct StructLogger {
+    /// All recorded logs
+    pub logs: Vec<StructLog>,
+    /// Whether to record the storage of the current contract on `SLOAD` and `SSTORE`
+    storage_enabled: bool,
+    /// Whether to record the stack
+    stack_enabled: bool,
+    /// Whether to record the memory
+    memory_enabled: bool,
+    /// Whether to record the return data of the last call
+    return_data_enabled: bool,
+    /// The storage slots of every contract that were accessed so far
+    storage: HashMap<Address, BTreeMap<H256, H256>>,
+    /// The currently active call frames
+    frames: Vec<Frame>,
+    /// The first gas block of an interpreter that was initialized but did not execute yet
+    new_frame: Option<u64>,
+}
+
+// === impl StructLogger ===
+
+impl StructLogger {
+    /// Creates a new logger that honours the given options
+    pub fn new(opts: &GethDebugTracingOptions) -> Self {
+        Self {
+            storage_enabled: !opts.disable_storage.unwrap_or_default(),
+            stack_enabled: !opts.disable_stack.unwrap_or_default(),
+            memory_enabled: opts.enable_memory.unwrap_or_default(),
+            return_data_enabled: opts.enable_return_data.unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+
+    /// Updates the active frames, entering a new one if a new interpreter was initialized
+    fn enter_frame(&mut self, depth: u64) {
+        if let Some(first_gas_block) = self.new_frame.take() {
+            self.frames.retain(|frame| frame.depth < depth);
+            self.frames.push(Frame {
+                depth,
+                previous_gas_block: first_gas_block,
+                current_gas_block: 0,
+                last_log: None,
+            });
+        } else {
+            // drop all frames that returned to this one
+            while self.frames.len() > 1 && self.frames.last().map_or(false, |f| f.depth > depth) {
+                self.frames.pop();
+            }
+        }
+    }
+
+    /// Updates the recorded storage of the contract on `SLOAD` and `SSTORE` and returns a copy of
+    /// it
+    fn record_storage<DB: Database>(
+        &mut self,
+        op: u8,
+        interpreter: &Interpreter,
+        data: &mut EVMData<'_, DB>,
+    ) -> Option<BTreeMap<H256, H256>> {
+        let address = interpreter.contract().address;
+        let key = interpreter.stack().peek(0).ok()?;
+        let value = match op {
+            opcode::SSTORE => interpreter.stack().peek(1).ok()?,
+            opcode::SLOAD => {
+                // read the value without touching the journal, so the slot is not marked as warm
+                let cached = data
+                    .subroutine
+                    .state()
+                    .get(&address)
+                    .and_then(|account| account.storage.get(&key).copied());
+                match cached {
+                    Some(value) => value,
+                    None => data.db.storage(address, key),
+                }
+            }
+            _ => return None,
+        };
+
+        let storage = self.storage.entry(address).or_default();
+        storage.insert(u256_to_h256_be(key), u256_to_h256_be(value));
+        Some(storage.clone())
+    }
+}
+
+impl<DB: Database> Inspector<DB> for StructLogger {
+    fn initialize_interp(
+        &mut self,
+        interp: &mut Interpreter,
+        _: &mut EVMData<'_, DB>,
+        _: bool,
+    ) -> Return {
+        self.new_frame = Some(interp.contract.first_gas_block());
+        Return::Continue
+    }
+
+    fn step(
+        &mut self,
+        interpreter: &mut Interpreter,
+        data: &mut EVMData<'_, DB>,
+        _is_static: bool,
+    ) -> Return {
+        let pc = interpreter.program_counter();
+        let op = interpreter.contract.code[pc];
+        let opcode_infos = spec_opcode_gas(data.env.cfg.spec_id);
+        let opcode_info = &opcode_infos[op as usize];
+
+        // Calculate the remaining gas, see the debugger inspector for the gas block accounting
+        let gas = interpreter.gas();
+        self.enter_frame(data.subroutine.depth() as u64);
+        let frame = self.frames.last_mut().expect("no active frame");
+        let total_gas_spent = gas
+            .spend()
+            .saturating_sub(frame.previous_gas_block)
+            .saturating_add(frame.current_gas_block);
+        if opcode_info.is_gas_block_end {
+            frame.previous_gas_block = interpreter.contract.gas_block(pc);
+            frame.current_gas_block = 0;
+        } else {
+            frame.current_gas_block += opcode_info.gas;
+        }
+        let remaining = gas.limit().saturating_sub(total_gas_spent);
+
+        // The cost of the previous opcode of this frame is only known now, this includes the gas
+        // used by any calls made by it
+        let previous = frame.last_log.replace(self.logs.len());
+        if let Some(idx) = previous {
+            self.logs[idx].gas_cost = self.logs[idx].gas.saturating_sub(remaining);
+        }
+        let depth = self.frames.len() as u64;
+
+        let storage = if self.storage_enabled && (op == opcode::SLOAD || op == opcode::SSTORE) {
+            self.record_storage(op, interpreter, data)
+        } else {
+            None
+        };
+
+        let refund = gas.refunded();
+        self.logs.push(StructLog {
+            pc: pc as u64,
+            op: OpCode::try_from_u8(op).map_or_else(
+                || format!("opcode 0x{:x} not defined", op),
+                |op| op.as_str().to_string(),
+            ),
+            gas: remaining,
+            gas_cost: opcode_info.gas,
+            depth,
+            error: None,
+            stack: self.stack_enabled.then(|| interpreter.stack().data().clone()),
+            return_data: self
+                .return_data_enabled
+                .then(|| interpreter.return_data_buffer.clone().into()),
+            memory: self
+                .memory_enabled
+                .then(|| interpreter.memory.data().chunks(32).map(hex::encode).collect::<Vec<_>>()),
+            storage,
+            refund: (refund > 0).then(|| refund as u64),
+        });
+
+        Return::Continue
+    }
+
+    fn step_end(
+        &mut self,
+        _: &mut Interpreter,
+        _: &mut EVMData<'_, DB>,
+        _: bool,
+        status: Return,
+    ) -> Return {
+        if !matches!(
+            status,
+            Return::Continue |
+                Return::Stop |
+                Return::Return |
+                Return::SelfDestruct |
+                Return::Revert
+        ) {
+            if let Some(log) = self.logs.last_mut() {
+                log.error = Some(format!("{:?}", status));
+            }
+        }
+        Return::Continue
+    }
+}
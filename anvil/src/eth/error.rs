@@ -8,7 +8,7 @@ use anvil_rpc::{
 use ethers::{
     providers::ProviderError,
     signers::WalletError,
-    types::{Bytes, SignatureError, U256},
+    types::{Bytes, SignatureError, H256, U256},
 };
 use foundry_evm::revm::Return;
 use serde::Serialize;
@@ -32,6 +32,8 @@ pub enum BlockchainError {
     FailedToDecodeSignedTransaction,
     #[error("Failed to decode transaction")]
     FailedToDecodeTransaction,
+    #[error("Failed to decode state dump")]
+    FailedToDecodeStateDump,
     #[error(transparent)]
     SignatureError(#[from] SignatureError),
     #[error(transparent)]
@@ -56,6 +58,8 @@ pub enum BlockchainError {
     BlockOutOfRange(u64, u64),
     #[error("Resource not found")]
     BlockNotFound,
+    #[error("Transaction {0:?} not found")]
+    TransactionNotFound(H256),
 }
 
 impl From<RpcError> for BlockchainError {
@@ -74,6 +78,12 @@ pub enum PoolError {
     ReplacementUnderpriced(Box<PoolTransaction>),
     #[error("Tx: [{0:?}] already Imported")]
     AlreadyImported(Box<PoolTransaction>),
+    /// Thrown if the sender already has the maximum number of transactions in the pool
+    #[error("Tx: [{0:?}] exceeds the transaction limit of the sender")]
+    SenderLimitReached(Box<PoolTransaction>),
+    /// Thrown if the pool is full and the transaction doesn't pay more than the cheapest one
+    #[error("Tx: [{0:?}] pool is full")]
+    PoolFull(Box<PoolTransaction>),
 }
 
 /// Errors that can occur with `eth_feeHistory`
@@ -154,6 +164,10 @@ impl<T: Serialize> ToRpcResponseResult for Result<T> {
                         PoolError::AlreadyImported(_) => {
                             RpcError::transaction_rejected("transaction already imported")
                         }
+                        PoolError::SenderLimitReached(_) => RpcError::transaction_rejected(
+                            "too many pending transactions from sender",
+                        ),
+                        PoolError::PoolFull(_) => RpcError::transaction_rejected("txpool is full"),
                     }
                 }
                 BlockchainError::NoSignerAvailable => {
@@ -180,6 +194,9 @@ impl<T: Serialize> ToRpcResponseResult for Result<T> {
                 BlockchainError::FailedToDecodeTransaction => {
                     RpcError::invalid_params("Failed to decode transaction")
                 }
+                BlockchainError::FailedToDecodeStateDump => {
+                    RpcError::invalid_params("Failed to decode state dump")
+                }
                 BlockchainError::SignatureError(err) => RpcError::invalid_params(err.to_string()),
                 BlockchainError::WalletError(err) => RpcError::invalid_params(err.to_string()),
                 BlockchainError::RpcUnimplemented => {
@@ -207,6 +224,9 @@ impl<T: Serialize> ToRpcResponseResult for Result<T> {
                     message: err.to_string().into(),
                     data: None,
                 },
+                err @ BlockchainError::TransactionNotFound(_) => {
+                    RpcError::invalid_params(err.to_string())
+                }
             }
             .into(),
         }
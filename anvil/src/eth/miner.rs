@@ -208,6 +208,10 @@ impl ReadyTransactionMiner {
             self.ready.insert(hash);
         }
 
+        // transactions that didn't fit into the previous block are still in the pool and trigger
+        // the next block
+        self.ready.retain(|hash| pool.contains(hash));
+
         if self.ready.is_empty() {
             return Poll::Pending
         }
@@ -215,11 +219,15 @@ impl ReadyTransactionMiner {
         let transactions =
             pool.ready_transactions().take(self.max_transactions).collect::<Vec<_>>();
 
-        if transactions.len() < self.max_transactions {
-            // save to clear the buffer since we completely emptied the pool
+        if transactions.is_empty() {
             self.ready.clear();
+            return Poll::Pending
         }
 
+        // the block is filled up to its gas limit, so keep track of all transactions that are
+        // about to be mined
+        self.ready.extend(transactions.iter().map(|tx| *tx.hash()));
+
         Poll::Ready(transactions)
     }
 }
@@ -24,6 +24,12 @@
 //! a `nonce > nonce on chain` will _require_ `(nonce -1, account)` first, before it is ready to be
 //! included in a block.
 //!
+//! The size of the pool can be limited via the [PoolConfig], per sender and in total. If the pool
+//! is full, a new transaction that pays more evicts the cheapest transaction that no other
+//! transaction depends on, which is the one with the highest nonce of its sender. A transaction
+//! with the same nonce as one in the pool replaces it, if its gas price is higher by at least the
+//! configured replacement bump.
+//!
 //! This implementation is adapted from <https://github.com/paritytech/substrate/tree/master/client/transaction-pool>
 
 use crate::{
@@ -39,15 +45,57 @@ use crate::{
 use anvil_core::eth::transaction::PendingTransaction;
 use ethers::{
     prelude::TxpoolStatus,
-    types::{TxHash, U64},
+    types::{TxHash, U256, U64},
 };
 use futures::channel::mpsc::{channel, Receiver, Sender};
 use parking_lot::{Mutex, RwLock};
-use std::{collections::VecDeque, fmt, sync::Arc};
+use std::{
+    collections::{hash_map::Entry, HashMap, VecDeque},
+    fmt,
+    sync::Arc,
+};
 use tracing::{debug, trace, warn};
 
 pub mod transactions;
 
+/// The default percentage by which a replacement transaction has to increase the gas price
+pub const DEFAULT_REPLACEMENT_FEE_BUMP: u64 = 10;
+
+/// Limits of the [Pool]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PoolConfig {
+    /// The maximum number of transactions in the pool, unlimited if `None`
+    pub max_transactions: Option<usize>,
+    /// The maximum number of transactions of a single sender in the pool, unlimited if `None`
+    pub max_transactions_per_sender: Option<usize>,
+    /// The percentage by which the gas price of a transaction has to exceed the gas price of the
+    /// transaction with the same nonce it replaces
+    pub replacement_fee_bump: u64,
+}
+
+// === impl PoolConfig ===
+
+impl PoolConfig {
+    /// Returns the minimum gas price of a transaction that replaces a transaction with the given
+    /// gas price
+    pub fn min_replacement_gas_price(&self, gas_price: U256) -> U256 {
+        let bumped = gas_price.saturating_mul(U256::from(100u64 + self.replacement_fee_bump)) /
+            U256::from(100u64);
+        // the replacement always needs to pay more
+        bumped.max(gas_price.saturating_add(U256::one()))
+    }
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_transactions: None,
+            max_transactions_per_sender: None,
+            replacement_fee_bump: DEFAULT_REPLACEMENT_FEE_BUMP,
+        }
+    }
+}
+
 /// Transaction pool that performs validation.
 #[derive(Default)]
 pub struct Pool {
@@ -55,11 +103,22 @@ pub struct Pool {
     inner: RwLock<PoolInner>,
     /// listeners for new ready transactions
     transaction_listener: Mutex<Vec<Sender<TxHash>>>,
+    /// listeners for transactions that were evicted from the pool
+    dropped_listener: Mutex<Vec<Sender<TxHash>>>,
 }
 
 // == impl Pool ==
 
 impl Pool {
+    /// Creates a new, empty pool with the given limits
+    pub fn new(config: PoolConfig) -> Self {
+        Self {
+            inner: RwLock::new(PoolInner { config, ..Default::default() }),
+            transaction_listener: Default::default(),
+            dropped_listener: Default::default(),
+        }
+    }
+
     /// Returns an iterator that yields all transactions that are currently ready
     pub fn ready_transactions(&self) -> TransactionsIterator {
         self.inner.read().ready_transactions()
@@ -114,20 +173,26 @@ impl Pool {
 
     /// Adds a new transaction to the pool
     pub fn add_transaction(&self, tx: PoolTransaction) -> Result<AddedTransaction, PoolError> {
-        let added = self.inner.write().add_transaction(tx)?;
+        let (added, evicted) = self.inner.write().add_transaction(tx)?;
         if let AddedTransaction::Ready(ref ready) = added {
             self.notify_listener(ready.hash)
         }
+        for tx in evicted {
+            notify_listener(&self.dropped_listener, *tx.hash())
+        }
         Ok(added)
     }
 
     /// Adds a new transaction listener to the pool that gets notified about every new ready
     /// transaction
     pub fn add_ready_listener(&self) -> Receiver<TxHash> {
-        const TX_LISTENER_BUFFER_SIZE: usize = 2048;
-        let (tx, rx) = channel(TX_LISTENER_BUFFER_SIZE);
-        self.transaction_listener.lock().push(tx);
-        rx
+        add_listener(&self.transaction_listener)
+    }
+
+    /// Adds a new transaction listener to the pool that gets notified about every transaction
+    /// that is evicted from the pool in favor of a new transaction
+    pub fn add_dropped_listener(&self) -> Receiver<TxHash> {
+        add_listener(&self.dropped_listener)
     }
 
     /// Returns true if this pool already contains the transaction
@@ -162,28 +227,41 @@ impl Pool {
 
     /// notifies all listeners about the transaction
     fn notify_listener(&self, hash: TxHash) {
-        let mut listener = self.transaction_listener.lock();
-        // this is basically a retain but with mut reference
-        for n in (0..listener.len()).rev() {
-            let mut listener_tx = listener.swap_remove(n);
-            let retain = match listener_tx.try_send(hash) {
-                Ok(()) => true,
-                Err(e) => {
-                    if e.is_full() {
-                        warn!(
-                            target: "txpool",
-                            "[{:?}] Failed to send tx notification because channel is full",
-                            hash,
-                        );
-                        true
-                    } else {
-                        false
-                    }
+        notify_listener(&self.transaction_listener, hash)
+    }
+}
+
+/// Registers a new listener
+fn add_listener(listeners: &Mutex<Vec<Sender<TxHash>>>) -> Receiver<TxHash> {
+    const TX_LISTENER_BUFFER_SIZE: usize = 2048;
+    let (tx, rx) = channel(TX_LISTENER_BUFFER_SIZE);
+    listeners.lock().push(tx);
+    rx
+}
+
+/// notifies all listeners about the transaction
+fn notify_listener(listeners: &Mutex<Vec<Sender<TxHash>>>, hash: TxHash) {
+    let mut listener = listeners.lock();
+    // this is basically a retain but with mut reference
+    for n in (0..listener.len()).rev() {
+        let mut listener_tx = listener.swap_remove(n);
+        let retain = match listener_tx.try_send(hash) {
+            Ok(()) => true,
+            Err(e) => {
+                if e.is_full() {
+                    warn!(
+                        target: "txpool",
+                        "[{:?}] Failed to send tx notification because channel is full",
+                        hash,
+                    );
+                    true
+                } else {
+                    false
                 }
-            };
-            if retain {
-                listener.push(listener_tx)
             }
+        };
+        if retain {
+            listener.push(listener_tx)
         }
     }
 }
@@ -195,6 +273,7 @@ impl Pool {
 struct PoolInner {
     ready_transactions: ReadyTransactions,
     pending_transactions: PendingTransactions,
+    config: PoolConfig,
 }
 
 // == impl PoolInner ==
@@ -222,22 +301,121 @@ impl PoolInner {
         self.pending_transactions.contains(tx_hash) || self.ready_transactions.contains(tx_hash)
     }
 
-    fn add_transaction(&mut self, tx: PoolTransaction) -> Result<AddedTransaction, PoolError> {
+    /// Returns the number of all transactions in the pool
+    fn len(&self) -> usize {
+        self.pending_transactions.len() + self.ready_transactions.len()
+    }
+
+    /// Returns all transactions in the pool, ready and pending
+    fn transactions(&self) -> impl Iterator<Item = Arc<PoolTransaction>> + '_ {
+        self.ready_transactions
+            .transactions()
+            .into_iter()
+            .chain(self.pending_transactions.transactions())
+    }
+
+    /// Checks the limits of the pool for the new transaction
+    ///
+    /// A transaction that replaces another one with the same nonce must pay the replacement bump,
+    /// otherwise the sender must not exceed its limit. If the pool is full, this returns the
+    /// transaction to evict in favor of the new one, which must pay more than it.
+    ///
+    /// Only the transaction with the highest nonce of another sender can be evicted, so that no
+    /// remaining transaction depends on it. Of those, the one with the lowest gas price is chosen.
+    fn ensure_limits(&self, tx: &PoolTransaction) -> Result<Option<TxHash>, PoolError> {
+        if let Some(replaced) = self.transactions().find(|other| other.provides == tx.provides) {
+            let min_gas_price = self.config.min_replacement_gas_price(replaced.gas_price());
+            if tx.gas_price() < min_gas_price {
+                warn!(target: "txpool", "[{:?}] replacement transaction underpriced, required gas price {}", tx.hash(), min_gas_price);
+                return Err(PoolError::ReplacementUnderpriced(Box::new(tx.clone())))
+            }
+            return Ok(None)
+        }
+
+        if let Some(max) = self.config.max_transactions_per_sender {
+            let sender = tx.pending_transaction.sender();
+            let count = self
+                .transactions()
+                .filter(|other| other.pending_transaction.sender() == sender)
+                .count();
+            if count >= max {
+                warn!(target: "txpool", "[{:?}] sender {:?} exceeds the limit of {} transactions", tx.hash(), sender, max);
+                return Err(PoolError::SenderLimitReached(Box::new(tx.clone())))
+            }
+        }
+
+        if let Some(max) = self.config.max_transactions {
+            if self.len() >= max {
+                let sender = tx.pending_transaction.sender();
+                let mut tails = HashMap::new();
+                for other in self.transactions() {
+                    let other_sender = *other.pending_transaction.sender();
+                    if other_sender == *sender {
+                        continue
+                    }
+                    match tails.entry(other_sender) {
+                        Entry::Vacant(entry) => {
+                            entry.insert(other);
+                        }
+                        Entry::Occupied(mut entry) => {
+                            if entry.get().pending_transaction.nonce() <
+                                other.pending_transaction.nonce()
+                            {
+                                entry.insert(other);
+                            }
+                        }
+                    }
+                }
+                let cheapest = tails.into_values().min_by_key(|other| other.gas_price());
+                return match cheapest {
+                    Some(cheapest) if cheapest.gas_price() < tx.gas_price() => {
+                        Ok(Some(*cheapest.hash()))
+                    }
+                    _ => {
+                        warn!(target: "txpool", "[{:?}] pool is full", tx.hash());
+                        Err(PoolError::PoolFull(Box::new(tx.clone())))
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Adds the transaction to the pool
+    ///
+    /// Returns the transactions that were evicted because the pool was full.
+    fn add_transaction(
+        &mut self,
+        tx: PoolTransaction,
+    ) -> Result<(AddedTransaction, Vec<Arc<PoolTransaction>>), PoolError> {
         if self.contains(tx.hash()) {
             warn!(target: "txpool", "[{:?}] Already imported", tx.hash());
             return Err(PoolError::AlreadyImported(Box::new(tx)))
         }
 
+        let evict = self.ensure_limits(&tx)?;
+
         let tx = PendingPoolTransaction::new(tx, self.ready_transactions.provided_markers());
         trace!(target: "txpool", "[{:?}] {:?}", tx.transaction.hash(), tx);
 
         // If all markers are not satisfied import to future
-        if !tx.is_ready() {
+        let added = if !tx.is_ready() {
             let hash = *tx.transaction.hash();
             self.pending_transactions.add_transaction(tx)?;
-            return Ok(AddedTransaction::Pending { hash })
-        }
-        self.add_ready_transaction(tx)
+            AddedTransaction::Pending { hash }
+        } else {
+            self.add_ready_transaction(tx)?
+        };
+
+        // only evict once the new transaction is in the pool
+        let evicted = match evict {
+            Some(hash) => {
+                trace!(target: "txpool", "pool is full, evicting [{:?}]", hash);
+                self.remove_invalid(vec![hash])
+            }
+            None => Vec::new(),
+        };
+        Ok((added, evicted))
     }
 
     /// Adds the transaction to the ready queue
@@ -388,6 +388,20 @@ impl ReadyTransactions {
         self.ready_tx.read().contains_key(hash)
     }
 
+    /// Returns the number of ready transactions
+    pub fn len(&self) -> usize {
+        self.ready_tx.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ready_tx.read().is_empty()
+    }
+
+    /// Returns all ready transactions, unordered
+    pub fn transactions(&self) -> Vec<Arc<PoolTransaction>> {
+        self.ready_tx.read().values().map(|tx| tx.transaction.transaction.clone()).collect()
+    }
+
     /// Returns the transaction for the hash if it's in the ready pool but not yet mined
     pub fn get(&self, hash: &TxHash) -> Option<ReadyTransaction> {
         self.ready_tx.read().get(hash).cloned()
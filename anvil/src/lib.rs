@@ -12,7 +12,9 @@ use crate::{
     logging::{LoggingManager, NodeLogLayer},
     service::NodeService,
 };
-pub use config::{AccountGenerator, NodeConfig, CHAIN_ID, VERSION_MESSAGE};
+pub use config::{
+    AccountGenerator, Hardfork, HardforkSchedule, NodeConfig, CHAIN_ID, VERSION_MESSAGE,
+};
 use eth::backend::fork::ClientFork;
 use ethers::{
     core::k256::ecdsa::SigningKey,
@@ -27,6 +29,7 @@ use parking_lot::Mutex;
 use std::{
     future::Future,
     net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::Path,
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
@@ -86,10 +89,11 @@ pub async fn spawn(mut config: NodeConfig) -> (EthApi, NodeHandle) {
         server_config,
         no_mining,
         transaction_order,
+        pool_config,
         ..
     } = config.clone();
 
-    let pool = Arc::new(Pool::default());
+    let pool = Arc::new(Pool::new(pool_config));
 
     let mode = if let Some(block_time) = block_time {
         MiningMode::interval(block_time)
@@ -137,6 +141,21 @@ pub async fn spawn(mut config: NodeConfig) -> (EthApi, NodeHandle) {
     // launch the rpc server
     let serve = tokio::task::spawn(server::serve(socket, api.clone(), server_config));
 
+    // launch the ipc server
+    #[cfg(unix)]
+    let ipc = config.ipc_path.clone().and_then(|path| {
+        match server::serve_ipc(path.clone(), api.clone()) {
+            Ok(ipc) => Some(tokio::task::spawn(ipc)),
+            Err(err) => {
+                eprintln!("Failed to bind IPC socket at {}: {}", path.display(), err);
+                config.ipc_path = None;
+                None
+            }
+        }
+    });
+    #[cfg(not(unix))]
+    let ipc: Option<tokio::task::JoinHandle<()>> = None;
+
     // select over both tasks
     let inner = futures::future::select(node_service, serve);
 
@@ -144,7 +163,12 @@ pub async fn spawn(mut config: NodeConfig) -> (EthApi, NodeHandle) {
         config,
         inner: Box::pin(async move {
             // wait for the first task to finish
-            inner.await.into_inner().0
+            let res = inner.await.into_inner().0;
+            // the ipc server removes its socket file once it's stopped
+            if let Some(ipc) = ipc {
+                ipc.abort();
+            }
+            res
         }),
         address: socket,
     };
@@ -174,7 +198,10 @@ impl NodeHandle {
     pub(crate) fn print(&self, fork: Option<&ClientFork>) {
         self.config.print(fork);
         if !self.config.silent {
-            println!("Listening on {}", self.socket_address())
+            println!("Listening on {}", self.socket_address());
+            if let Some(path) = self.ipc_path() {
+                println!("IPC socket at {}", path.display());
+            }
         }
     }
 
@@ -183,6 +210,11 @@ impl NodeHandle {
         &self.address
     }
 
+    /// The path of the IPC socket, if the node serves the rpc via IPC
+    pub fn ipc_path(&self) -> Option<&Path> {
+        self.config.ipc_path.as_deref()
+    }
+
     /// Returns the http endpoint
     pub fn http_endpoint(&self) -> String {
         format!("http://{}", self.socket_address())
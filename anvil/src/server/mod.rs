@@ -16,3 +16,13 @@ pub fn serve(
     let ws = WsEthRpcHandler::new(api);
     anvil_server::serve_http_ws(addr, config, http, ws)
 }
+
+/// Binds the IPC socket at the given path and returns the future that serves [EthApi] related
+/// JSON-RPC calls via IPC
+#[cfg(unix)]
+pub fn serve_ipc(
+    path: std::path::PathBuf,
+    api: EthApi,
+) -> std::io::Result<impl Future<Output = ()>> {
+    anvil_server::IpcEndpoint::new(WsEthRpcHandler::new(api), path).incoming()
+}
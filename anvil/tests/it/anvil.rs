@@ -1,8 +1,11 @@
 //! tests for anvil specific logic
 
 use crate::next_port;
-use anvil::{spawn, NodeConfig};
-use ethers::prelude::Middleware;
+use anvil::{spawn, Hardfork, NodeConfig};
+use ethers::{
+    prelude::{Middleware, TransactionRequest},
+    types::{transaction::eip2718::TypedTransaction, BlockNumber, Bytes},
+};
 
 #[tokio::test(flavor = "multi_thread")]
 async fn test_can_change_mining_mode() {
@@ -35,3 +38,31 @@ async fn can_get_default_dev_keys() {
     let accounts = provider.get_accounts().await.unwrap();
     assert_eq!(dev_accounts, accounts);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_activate_hardfork_at_block() {
+    let (_api, handle) = spawn(
+        NodeConfig::test()
+            .with_port(next_port())
+            .with_hardfork(Hardfork::Berlin)
+            .with_hardfork_activations([(3, Hardfork::London)]),
+    )
+    .await;
+    let provider = handle.http_provider();
+    let from = handle.dev_accounts().next().unwrap();
+
+    // init code that executes `BASEFEE`, which is only available since london
+    let code: Bytes = vec![0x48, 0x50, 0x00].into();
+
+    for block in 1..=4u64 {
+        let tx = TransactionRequest::new().from(from).data(code.clone()).gas(100_000u64);
+        let receipt = provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+        assert_eq!(receipt.block_number.unwrap().as_u64(), block);
+        assert_eq!(receipt.status.unwrap().as_u64(), (block >= 3) as u64);
+    }
+
+    // calls on historic state use the hardfork that was active at that block
+    let tx: TypedTransaction = TransactionRequest::new().from(from).data(code).into();
+    assert!(provider.call(&tx, Some(BlockNumber::Number(1u64.into()).into())).await.is_err());
+    assert!(provider.call(&tx, Some(BlockNumber::Number(3u64.into()).into())).await.is_ok());
+}
@@ -4,7 +4,7 @@ use crate::next_port;
 use anvil::{spawn, NodeConfig};
 use ethers::{
     prelude::Middleware,
-    types::{Address, TransactionRequest},
+    types::{Address, Bytes, TransactionRequest, H256},
     utils::WEI_IN_ETHER,
 };
 
@@ -54,3 +54,35 @@ async fn can_mine_manually() {
         assert_eq!(num, start_num + idx + 1);
     }
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_dump_and_load_state() {
+    let (api, handle) = spawn(NodeConfig::test().with_port(next_port())).await;
+    let provider = handle.http_provider();
+
+    let account = Address::random();
+    let code: Bytes = vec![0x60, 0x00, 0x60, 0x00, 0xf3].into();
+    api.anvil_set_code(account, code.clone()).await.unwrap();
+    api.anvil_set_storage_at(account, 1u64.into(), 1337u64.into()).await.unwrap();
+
+    let from = handle.dev_accounts().next().unwrap();
+    let tx = TransactionRequest::new().from(from).to(account).value(1337u64);
+    let receipt = provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+
+    let state = api.anvil_dump_state().await.unwrap();
+
+    let (api, handle) = spawn(NodeConfig::test().with_port(next_port())).await;
+    let provider = handle.http_provider();
+    assert!(api.anvil_load_state(state).await.unwrap());
+
+    assert_eq!(provider.get_code(account, None).await.unwrap(), code);
+    assert_eq!(
+        provider.get_storage_at(account, H256::from_low_u64_be(1), None).await.unwrap(),
+        H256::from_low_u64_be(1337)
+    );
+    assert_eq!(provider.get_balance(account, None).await.unwrap(), 1337u64.into());
+    assert_eq!(provider.get_block_number().await.unwrap(), receipt.block_number.unwrap());
+
+    let loaded = provider.get_transaction_receipt(receipt.transaction_hash).await.unwrap().unwrap();
+    assert_eq!(loaded.block_hash, receipt.block_hash);
+}
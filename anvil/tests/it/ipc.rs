@@ -0,0 +1,73 @@
+//! tests for the IPC endpoint
+
+use crate::next_port;
+use anvil::{spawn, NodeConfig};
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines},
+    net::{unix::OwnedReadHalf, UnixStream},
+};
+
+fn ipc_path(port: u16) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("anvil-{}.ipc", port))
+}
+
+async fn next_message(lines: &mut Lines<BufReader<OwnedReadHalf>>) -> Value {
+    let line = lines.next_line().await.unwrap().unwrap();
+    serde_json::from_str(&line).unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_call_via_ipc() {
+    let port = next_port();
+    let (_api, handle) =
+        spawn(NodeConfig::test().with_port(port).with_ipc(Some(ipc_path(port)))).await;
+
+    let (reader, mut writer) =
+        UnixStream::connect(handle.ipc_path().unwrap()).await.unwrap().into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    let request = json!({"jsonrpc": "2.0", "id": 1, "method": "eth_chainId", "params": []});
+    writer.write_all(format!("{}\n", request).as_bytes()).await.unwrap();
+    let response = next_message(&mut lines).await;
+    assert_eq!(response["id"], 1);
+    assert_eq!(response["result"], format!("0x{:x}", handle.config().chain_id));
+
+    writer.write_all(b"not json\n").await.unwrap();
+    let response = next_message(&mut lines).await;
+    assert!(response["error"].is_object());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_sub_new_heads_via_ipc() {
+    let port = next_port();
+    let (api, handle) =
+        spawn(NodeConfig::test().with_port(port).with_ipc(Some(ipc_path(port)))).await;
+
+    let (reader, mut writer) =
+        UnixStream::connect(handle.ipc_path().unwrap()).await.unwrap().into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    let request =
+        json!({"jsonrpc": "2.0", "id": 1, "method": "eth_subscribe", "params": ["newHeads"]});
+    writer.write_all(format!("{}\n", request).as_bytes()).await.unwrap();
+    let subscription = next_message(&mut lines).await["result"].clone();
+    assert!(subscription.is_string());
+
+    api.mine_one();
+
+    let notification = next_message(&mut lines).await;
+    assert_eq!(notification["method"], "eth_subscription");
+    assert_eq!(notification["params"]["subscription"], subscription);
+    assert_eq!(notification["params"]["result"]["number"], "0x1");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_launch_when_ipc_bind_fails() {
+    let port = next_port();
+    let path = std::env::temp_dir().join(format!("anvil-{}", port)).join("missing/anvil.ipc");
+    let (api, handle) = spawn(NodeConfig::test().with_port(port).with_ipc(Some(path))).await;
+
+    assert!(handle.ipc_path().is_none());
+    assert_eq!(api.chain_id(), handle.config().chain_id);
+}
@@ -5,6 +5,8 @@ mod anvil_api;
 mod api;
 mod fork;
 mod ganache;
+#[cfg(unix)]
+mod ipc;
 mod pubsub;
 mod traces;
 mod transaction;
@@ -1,6 +1,13 @@
 use crate::next_port;
 use anvil::{spawn, NodeConfig};
-use ethers::prelude::{Middleware, Signer, TransactionRequest};
+use anvil_core::types::{GethDebugTracingOptions, GethTrace};
+use ethers::{
+    contract::abigen,
+    prelude::{Middleware, Signer, SignerMiddleware, TransactionRequest},
+};
+use std::sync::Arc;
+
+abigen!(Greeter, "test-data/greeter.json");
 
 #[tokio::test(flavor = "multi_thread")]
 async fn test_get_transfer_parity_traces() {
@@ -26,3 +33,64 @@ async fn test_get_transfer_parity_traces() {
 
     assert_eq!(traces, block_traces);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_debug_trace_transaction() {
+    let (api, handle) = spawn(NodeConfig::test().with_port(next_port())).await;
+    let provider = handle.http_provider();
+
+    let wallet = handle.dev_wallets().next().unwrap();
+    let client = Arc::new(SignerMiddleware::new(provider, wallet));
+
+    let greeter_contract =
+        Greeter::deploy(client, "Hello World!".to_string()).unwrap().send().await.unwrap();
+    let receipt = greeter_contract
+        .set_greeting("Another Message".to_string())
+        .send()
+        .await
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let trace =
+        api.debug_trace_transaction(receipt.transaction_hash, Default::default()).await.unwrap();
+    let frame = match trace {
+        GethTrace::Default(frame) => frame,
+        _ => panic!("expected struct logs"),
+    };
+    assert!(!frame.failed);
+    assert_eq!(frame.gas, receipt.gas_used.unwrap().as_u64());
+    assert!(frame.struct_logs.iter().all(|log| log.stack.is_some() && log.memory.is_none()));
+    let sstore = frame.struct_logs.iter().find(|log| log.op == "SSTORE").unwrap();
+    assert!(!sstore.storage.as_ref().unwrap().is_empty());
+
+    let opts = GethDebugTracingOptions {
+        disable_stack: Some(true),
+        disable_storage: Some(true),
+        enable_memory: Some(true),
+        ..Default::default()
+    };
+    let trace = api.debug_trace_transaction(receipt.transaction_hash, opts).await.unwrap();
+    let frame = match trace {
+        GethTrace::Default(frame) => frame,
+        _ => panic!("expected struct logs"),
+    };
+    assert!(frame
+        .struct_logs
+        .iter()
+        .all(|log| log.stack.is_none() && log.storage.is_none() && log.memory.is_some()));
+
+    let opts =
+        GethDebugTracingOptions { tracer: Some("callTracer".to_string()), ..Default::default() };
+    let trace = api.debug_trace_transaction(receipt.transaction_hash, opts).await.unwrap();
+    let call = match trace {
+        GethTrace::CallTracer(call) => call,
+        _ => panic!("expected call frame"),
+    };
+    assert_eq!(call.typ, "CALL");
+    assert_eq!(call.to, Some(greeter_contract.address()));
+    assert!(call.error.is_none());
+    // `gas` is the gas limit of the call, not the gas it used
+    assert!(call.gas > call.gas_used);
+}
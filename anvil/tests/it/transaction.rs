@@ -1,5 +1,5 @@
 use crate::next_port;
-use anvil::{spawn, NodeConfig};
+use anvil::{eth::pool::PoolConfig, spawn, NodeConfig};
 use ethers::{
     contract::{ContractFactory, EthEvent},
     prelude::{
@@ -154,9 +154,13 @@ async fn can_replace_transaction() {
     let lower_priced_pending_tx =
         provider.send_transaction(tx.clone().gas_price(gas_price), None).await.unwrap();
 
+    // replacing requires a gas price increase of at least 10%
+    let res = provider.send_transaction(tx.clone().gas_price(gas_price + 1u64), None).await;
+    assert!(res.is_err());
+
     // send the same transaction with higher gas price
     let higher_priced_pending_tx =
-        provider.send_transaction(tx.gas_price(gas_price + 1u64), None).await.unwrap();
+        provider.send_transaction(tx.gas_price(gas_price * 11u64 / 10u64), None).await.unwrap();
 
     // mine exactly one block
     api.mine_one();
@@ -173,6 +177,149 @@ async fn can_replace_transaction() {
     assert_eq!(vec![higher_priced_receipt.transaction_hash], block.transactions);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn can_fill_blocks_up_to_gas_limit() {
+    // room for two transfers per block
+    let (api, handle) =
+        spawn(NodeConfig::test().with_port(next_port()).with_gas_limit(Some(50_000u64))).await;
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let provider = handle.http_provider();
+    let accounts: Vec<_> = handle.dev_wallets().collect();
+    let to = accounts[0].address();
+
+    let mut pending = Vec::new();
+    for account in &accounts[1..4] {
+        let tx =
+            TransactionRequest::new().to(to).value(1u64).from(account.address()).gas(21_000u64);
+        pending.push(provider.send_transaction(tx, None).await.unwrap());
+    }
+
+    // the transaction that doesn't fit stays in the pool
+    api.mine_one();
+    let block = provider.get_block(1u64).await.unwrap().unwrap();
+    assert_eq!(block.transactions.len(), 2);
+    assert_eq!(block.gas_used, U256::from(42_000u64));
+
+    api.mine_one();
+    let block = provider.get_block(2u64).await.unwrap().unwrap();
+    assert_eq!(block.transactions.len(), 1);
+
+    for tx in join_all(pending).await {
+        assert!(tx.unwrap().is_some());
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_mine_leftover_transactions_in_next_block() {
+    let (api, handle) =
+        spawn(NodeConfig::test().with_port(next_port()).with_gas_limit(Some(50_000u64))).await;
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let provider = handle.http_provider();
+    let accounts: Vec<_> = handle.dev_wallets().collect();
+    let to = accounts[0].address();
+
+    let mut pending = Vec::new();
+    for account in &accounts[1..4] {
+        let tx =
+            TransactionRequest::new().to(to).value(1u64).from(account.address()).gas(21_000u64);
+        pending.push(provider.send_transaction(tx, None).await.unwrap());
+    }
+
+    // automine keeps mining blocks until the pool is empty
+    api.anvil_set_auto_mine(true).await.unwrap();
+    let tx =
+        TransactionRequest::new().to(to).value(1u64).from(accounts[4].address()).gas(21_000u64);
+    provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+
+    for tx in join_all(pending).await {
+        assert!(tx.unwrap().is_some());
+    }
+    assert_eq!(provider.get_block_number().await.unwrap().as_u64(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_enforce_pool_limits() {
+    let pool_config = PoolConfig {
+        max_transactions: Some(2),
+        max_transactions_per_sender: Some(1),
+        ..Default::default()
+    };
+    let (api, handle) =
+        spawn(NodeConfig::test().with_port(next_port()).with_pool_config(pool_config)).await;
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let provider = handle.http_provider();
+    let accounts: Vec<_> = handle.dev_wallets().collect();
+    let to = accounts[0].address();
+    let gas_price = provider.get_gas_price().await.unwrap();
+
+    let tx = TransactionRequest::new().to(to).value(1u64).from(accounts[1].address());
+    let cheapest = provider.send_transaction(tx.clone().gas_price(gas_price), None).await.unwrap();
+
+    // only one pending transaction per sender
+    let nonce = provider.get_transaction_count(accounts[1].address(), None).await.unwrap();
+    let res = provider.send_transaction(tx.nonce(nonce + 1u64).gas_price(gas_price), None).await;
+    assert!(res.is_err());
+
+    let tx = TransactionRequest::new().to(to).value(1u64).from(accounts[2].address());
+    provider.send_transaction(tx.gas_price(gas_price + 1u64), None).await.unwrap();
+
+    // the pool is full and the new transaction doesn't pay more than the cheapest one
+    let tx = TransactionRequest::new().to(to).value(1u64).from(accounts[3].address());
+    let res = provider.send_transaction(tx.clone().gas_price(gas_price), None).await;
+    assert!(res.is_err());
+
+    // a transaction paying more evicts the cheapest one
+    let mut dropped = api.dropped_transactions();
+    let evicting = provider.send_transaction(tx.gas_price(gas_price + 2u64), None).await.unwrap();
+    assert_eq!(dropped.next().await, Some(cheapest.tx_hash()));
+    api.mine_one();
+
+    let block = provider.get_block(1u64).await.unwrap().unwrap();
+    assert_eq!(block.transactions.len(), 2);
+    assert!(block.transactions.contains(&evicting.tx_hash()));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_evict_highest_nonce_of_sender() {
+    let pool_config = PoolConfig { max_transactions: Some(3), ..Default::default() };
+    let (api, handle) =
+        spawn(NodeConfig::test().with_port(next_port()).with_pool_config(pool_config)).await;
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let provider = handle.http_provider();
+    let accounts: Vec<_> = handle.dev_wallets().collect();
+    let to = accounts[0].address();
+    let gas_price = provider.get_gas_price().await.unwrap();
+
+    // the cheapest transaction is required by a more expensive one of the same sender
+    let tx = TransactionRequest::new().to(to).value(1u64).from(accounts[1].address());
+    let first = provider.send_transaction(tx.clone().gas_price(gas_price), None).await.unwrap();
+    let nonce = provider.get_transaction_count(accounts[1].address(), None).await.unwrap();
+    let second = provider
+        .send_transaction(tx.nonce(nonce + 1u64).gas_price(gas_price + 5u64), None)
+        .await
+        .unwrap();
+
+    let tx = TransactionRequest::new().to(to).value(1u64).from(accounts[2].address());
+    let cheapest_tail =
+        provider.send_transaction(tx.gas_price(gas_price + 3u64), None).await.unwrap();
+
+    let mut dropped = api.dropped_transactions();
+    let tx = TransactionRequest::new().to(to).value(1u64).from(accounts[3].address());
+    let evicting = provider.send_transaction(tx.gas_price(gas_price + 4u64), None).await.unwrap();
+    assert_eq!(dropped.next().await, Some(cheapest_tail.tx_hash()));
+
+    api.mine_one();
+    let block = provider.get_block(1u64).await.unwrap().unwrap();
+    assert_eq!(block.transactions.len(), 3);
+    for tx in [first, second, evicting] {
+        assert!(block.transactions.contains(&tx.tx_hash()));
+    }
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn can_reject_too_high_gas_limits() {
     let (api, handle) = spawn(NodeConfig::test().with_port(next_port())).await;
@@ -1,6 +1,9 @@
 //! Generate [ethers-rs]("https://github.com/gakonst/ethers-rs") bindings for solidity projects in a build script.
 
-use crate::utils::{GitReference, GitRemote};
+use crate::{
+    linking::LinkingModule,
+    utils::{GitReference, GitRemote},
+};
 use ethers_contract::MultiAbigen;
 pub use foundry_config::Config;
 use std::{
@@ -11,6 +14,7 @@ use tempfile::{tempdir, TempDir};
 use tracing::trace;
 pub use url::Url;
 
+pub mod linking;
 pub mod utils;
 
 /// Contains all the options to configure the gen process
@@ -95,6 +99,10 @@ impl Binder {
 
     /// If `deployable` set to `true` then the generated contract bindings will include the
     /// generated bytecode which makes the contracts deployable
+    ///
+    /// Contracts that depend on external libraries additionally get deployers in a `linking`
+    /// module, which link the bytecode against given library addresses or deploy the libraries
+    /// first.
     #[must_use]
     pub fn set_deployable(mut self, deployable: bool) -> Self {
         self.deployable = deployable;
@@ -184,7 +192,15 @@ impl Binder {
 
         trace!("Writing bindings to `src/contracts`");
         let module = self.bindings.clone().unwrap_or_else(|| "src/contracts".into());
-        bindings.write_to_module(module, false)?;
+        bindings.write_to_module(&module, false)?;
+
+        if self.deployable {
+            let linking = LinkingModule::from_json_files(project.artifacts_path())?;
+            if !linking.is_empty() {
+                trace!("Writing deployers for {} linked contracts", linking.len());
+                linking.write_to_module(&module)?;
+            }
+        }
 
         Ok(())
     }
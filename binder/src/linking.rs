@@ -0,0 +1,680 @@
+//! Generate deployers for contracts that depend on external libraries.
+//!
+//! The bytecode of such contracts contains placeholders for the library addresses which have to be
+//! replaced before the contract can be deployed. The generated module contains a submodule per
+//! contract, that either links the bytecode against the given library addresses, or deploys all
+//! required libraries first, in the same order `foundry_utils::link` deploys them for tests and
+//! scripts.
+
+use ethers_core::{
+    types::{Address, Bytes},
+    utils::get_contract_address,
+};
+use ethers_solc::{
+    artifacts::{CompactBytecode, CompactContractBytecode},
+    Artifact, ArtifactId, ConfigurableContractArtifact,
+};
+use eyre::WrapErr;
+use foundry_utils::PostLinkInput;
+use proc_macro2::{Ident, Literal, Span, TokenStream};
+use quote::{format_ident, quote};
+use semver::Version;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write as _,
+    fs,
+    io::Write as _,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+use walkdir::WalkDir;
+
+/// The name of the generated module
+pub const LINKING_MODULE: &str = "linking";
+
+/// The length of a library address placeholder in bytes
+const ADDRESS_LEN: usize = 20;
+
+/// Contains the linking helpers for all contracts of a project that require libraries
+#[derive(Debug, Clone, Default)]
+pub struct LinkingModule {
+    /// All contracts that need to be linked and all libraries they depend on, ordered by artifact
+    contracts: Vec<LinkedContract>,
+}
+
+/// The libraries a contract is linked against, as `(fully qualified name, library, offsets)`
+type LinkReferences<'a> = Vec<(String, &'a ArtifactId, Vec<usize>)>;
+
+// === impl LinkingModule ===
+
+impl LinkingModule {
+    /// Reads all contract artifacts in the directory and collects the contracts that require
+    /// libraries, including all the libraries they depend on
+    pub fn from_json_files(artifacts: impl AsRef<Path>) -> eyre::Result<Self> {
+        let mut contracts = BTreeMap::new();
+        for entry in WalkDir::new(artifacts)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_file())
+            .filter(|e| e.path().extension().map(|ext| ext == "json").unwrap_or_default())
+        {
+            // skip everything that's not a contract artifact, like build infos
+            let artifact = match fs::read_to_string(entry.path())
+                .ok()
+                .and_then(|s| serde_json::from_str::<ConfigurableContractArtifact>(&s).ok())
+            {
+                Some(artifact) => artifact,
+                None => continue,
+            };
+            let path = entry.into_path();
+            let (name, source) = match (path.file_stem(), path.parent().and_then(Path::file_name)) {
+                (Some(name), Some(source)) => {
+                    (name.to_string_lossy().to_string(), PathBuf::from(source))
+                }
+                _ => continue,
+            };
+            let id = ArtifactId { path, name, source, version: Version::new(0, 0, 0) };
+            contracts.insert(id, artifact.into_contract_bytecode());
+        }
+        Self::from_contracts(contracts)
+    }
+
+    fn from_contracts(
+        contracts: BTreeMap<ArtifactId, CompactContractBytecode>,
+    ) -> eyre::Result<Self> {
+        let by_slug = contracts.keys().map(|id| (id.slug(), id)).collect::<BTreeMap<_, _>>();
+        let bytecode = |id: &ArtifactId| contracts[id].bytecode.as_ref();
+
+        // resolve the artifacts of all libraries, starting with all contracts that have link
+        // references
+        let mut pending = contracts
+            .iter()
+            .filter(|(_, contract)| {
+                contract.bytecode.as_ref().map_or(false, |b| !b.link_references.is_empty())
+            })
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+        let mut modules = BTreeMap::<&ArtifactId, LinkReferences<'_>>::new();
+        let mut qualified_names = BTreeMap::new();
+
+        while let Some(id) = pending.pop() {
+            if modules.contains_key(id) {
+                continue
+            }
+
+            let mut libraries = Vec::new();
+            for (file, links) in bytecode(id).map(|b| &b.link_references).into_iter().flatten() {
+                for (name, offsets) in links {
+                    let library = *by_slug.get(&library_slug(name)).ok_or_else(|| {
+                        eyre::eyre!(
+                            "Could not find the artifact of library `{}:{}` required by {}",
+                            file,
+                            name,
+                            id.path.display()
+                        )
+                    })?;
+                    let qualified_name = format!("{}:{}", file, name);
+                    qualified_names.entry(library).or_insert_with(|| qualified_name.clone());
+                    pending.push(library);
+                    libraries.push((
+                        qualified_name,
+                        library,
+                        offsets.iter().map(|offset| offset.start as usize).collect(),
+                    ));
+                }
+            }
+            modules.insert(id, libraries);
+        }
+
+        // link all contracts like tests and scripts to get the order the libraries are deployed in
+        let mut linked = BTreeMap::<ArtifactId, (Bytes, Vec<Bytes>)>::new();
+        foundry_utils::link(
+            contracts.clone(),
+            &mut linked,
+            Address::zero(),
+            &mut (),
+            |file, key| (library_slug(&key), file, key),
+            |PostLinkInput { contract, known_contracts, id, dependencies, .. }| {
+                if let Some(bytecode) = contract.bytecode.and_then(|b| b.object.into_bytes()) {
+                    known_contracts.insert(id, (bytecode, dependencies));
+                }
+                Ok(())
+            },
+        )?;
+
+        // assign unique identifiers to all contracts
+        let mut idents = BTreeMap::new();
+        let mut used = BTreeSet::new();
+        for id in modules.keys() {
+            let base = to_snake_case(&id.name);
+            let mut ident = base.clone();
+            let mut n = 1;
+            while !used.insert(ident.clone()) {
+                n += 1;
+                ident = format!("{}_{}", base, n);
+            }
+            idents.insert(*id, ident);
+        }
+
+        let contracts = modules
+            .iter()
+            .map(|(id, libraries)| {
+                let (linked_bytecode, dependencies) = linked
+                    .get(*id)
+                    .ok_or_else(|| eyre::eyre!("Failed to link {}", id.path.display()))?;
+                let order = deploy_order(linked_bytecode, libraries, dependencies, &modules)
+                    .wrap_err_with(|| {
+                        format!("Failed to resolve the libraries of {}", id.path.display())
+                    })?;
+                let abi = contracts[*id]
+                    .abi
+                    .as_ref()
+                    .ok_or_else(|| eyre::eyre!("No ABI in artifact {}", id.path.display()))?;
+
+                Ok(LinkedContract {
+                    ident: idents[id].clone(),
+                    name: qualified_names.get(id).cloned().unwrap_or_else(|| id.name.clone()),
+                    abi: serde_json::to_string(abi)?,
+                    bytecode: bytecode(id)
+                        .and_then(zeroed_bytecode)
+                        .ok_or_else(|| eyre::eyre!("Invalid bytecode in {}", id.path.display()))?,
+                    libraries: libraries
+                        .iter()
+                        .zip(order.positions)
+                        .map(|((name, library, offsets), position)| LinkedLibrary {
+                            name: name.clone(),
+                            ident: idents[library].clone(),
+                            offsets: offsets.clone(),
+                            position,
+                        })
+                        .collect(),
+                    deploy_order: order
+                        .libraries
+                        .into_iter()
+                        .map(|(library, deps)| {
+                            let deps = deps
+                                .into_iter()
+                                .map(|(dep, position)| (idents[dep].clone(), position))
+                                .collect();
+                            (idents[library].clone(), deps)
+                        })
+                        .collect(),
+                })
+            })
+            .collect::<eyre::Result<_>>()?;
+
+        Ok(Self { contracts })
+    }
+
+    /// Returns the number of contracts in the module
+    pub fn len(&self) -> usize {
+        self.contracts.len()
+    }
+
+    /// Returns `true` if there are no contracts that need to be linked
+    pub fn is_empty(&self) -> bool {
+        self.contracts.is_empty()
+    }
+
+    /// Returns the rust code of the module
+    pub fn contents(&self) -> String {
+        let header = header();
+        let contracts = self.contracts.iter().map(LinkedContract::tokens);
+        format_code(quote!(#header #(#contracts)*).to_string())
+    }
+
+    /// Writes the module next to the bindings in the given module directory and declares it in
+    /// its `mod.rs`
+    pub fn write_to_module(&self, module: impl AsRef<Path>) -> eyre::Result<()> {
+        self.write(module.as_ref(), "mod.rs")
+    }
+
+    /// Writes the module into the `src` dir of the bindings crate at the given path and declares
+    /// it in its `lib.rs`
+    pub fn write_to_crate(&self, lib: impl AsRef<Path>) -> eyre::Result<()> {
+        self.write(&lib.as_ref().join("src"), "lib.rs")
+    }
+
+    fn write(&self, dir: &Path, root_file: &str) -> eyre::Result<()> {
+        fs::write(dir.join(format!("{}.rs", LINKING_MODULE)), self.contents())?;
+
+        let root_file = dir.join(root_file);
+        let mut root = fs::read_to_string(&root_file)
+            .wrap_err_with(|| format!("Failed to read {}", root_file.display()))?;
+        if !root.is_empty() && !root.ends_with('\n') {
+            root.push('\n');
+        }
+        writeln!(root, "pub mod {};", LINKING_MODULE)?;
+        fs::write(root_file, root)?;
+        Ok(())
+    }
+}
+
+/// Returns the slug of a library's artifact, the same way tests and scripts are linked
+fn library_slug(name: &str) -> String {
+    format!("{name}.json:{name}")
+}
+
+/// Returns the bytecode with all library placeholders replaced by zeros
+fn zeroed_bytecode(bytecode: &CompactBytecode) -> Option<String> {
+    let mut zeroed = bytecode.clone();
+    for (file, links) in &bytecode.link_references {
+        for name in links.keys() {
+            zeroed.link(file, name, Address::zero());
+        }
+    }
+    Some(format!("0x{}", hex::encode(zeroed.object.into_bytes()?)))
+}
+
+/// The libraries `foundry_utils::link` deploys for a contract
+struct DeployOrder<'a> {
+    /// The deployed libraries, in order, with the libraries each one is linked against as
+    /// `(library, position)`
+    libraries: Vec<(&'a ArtifactId, Vec<(&'a ArtifactId, usize)>)>,
+    /// The position of every library the contract is linked against
+    positions: Vec<usize>,
+}
+
+/// Identifies the libraries `foundry_utils::link` deployed for a contract.
+///
+/// The libraries are deployed from the zero address starting at nonce 1 in the order of
+/// `dependencies`, after the libraries they depend on, so every placeholder of the linked bytecode
+/// holds the address of the library deployed at some position.
+fn deploy_order<'a>(
+    bytecode: &[u8],
+    libraries: &LinkReferences<'a>,
+    dependencies: &[Bytes],
+    modules: &BTreeMap<&'a ArtifactId, LinkReferences<'a>>,
+) -> eyre::Result<DeployOrder<'a>> {
+    let addresses = (0..dependencies.len())
+        .map(|i| get_contract_address(Address::zero(), 1 + i))
+        .collect::<Vec<_>>();
+    // returns the position of the library whose address is linked at the offsets
+    let position = |code: &[u8], offsets: &[usize]| {
+        offsets
+            .first()
+            .and_then(|offset| code.get(*offset..*offset + ADDRESS_LEN))
+            .and_then(|linked| addresses.iter().position(|address| address.as_bytes() == linked))
+            .ok_or_else(|| eyre::eyre!("Unknown library address in linked bytecode"))
+    };
+
+    let mut ids = vec![None; dependencies.len()];
+    let positions = libraries
+        .iter()
+        .map(|(_, library, offsets)| {
+            let position = position(bytecode, offsets)?;
+            ids[position] = Some(*library);
+            Ok(position)
+        })
+        .collect::<eyre::Result<Vec<_>>>()?;
+
+    // every library is deployed after its dependencies, so resolving them backwards identifies
+    // all of them
+    let mut order = Vec::with_capacity(dependencies.len());
+    for (i, code) in dependencies.iter().enumerate().rev() {
+        let id = ids[i].ok_or_else(|| eyre::eyre!("Unknown library at position {}", i))?;
+        let deps = modules[id]
+            .iter()
+            .map(|(_, library, offsets)| {
+                let position = position(code, offsets)?;
+                ids[position] = Some(*library);
+                Ok((*library, position))
+            })
+            .collect::<eyre::Result<Vec<_>>>()?;
+        order.push((id, deps));
+    }
+    order.reverse();
+
+    Ok(DeployOrder { libraries: order, positions })
+}
+
+/// Converts a contract name into a snake case identifier, `SafeERC20` becomes `safe_erc20`
+fn to_snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut snake = String::with_capacity(name.len() + 4);
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_is_lower =
+                chars.get(i + 1).map(|c| c.is_ascii_lowercase()).unwrap_or_default();
+            if prev.is_ascii_lowercase() ||
+                prev.is_ascii_digit() ||
+                (prev.is_ascii_uppercase() && next_is_lower)
+            {
+                snake.push('_');
+            }
+        }
+        if c.is_ascii_alphanumeric() {
+            snake.push(c.to_ascii_lowercase());
+        } else if !snake.ends_with('_') {
+            snake.push('_');
+        }
+    }
+    if snake.starts_with(|c: char| c.is_ascii_digit()) {
+        snake.insert(0, '_');
+    }
+    snake
+}
+
+/// Returns the identifier as a valid rust identifier
+fn ident(name: &str) -> Ident {
+    const KEYWORDS: &[&str] = &[
+        "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern",
+        "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut",
+        "pub", "ref", "return", "static", "struct", "trait", "true", "type", "unsafe", "use",
+        "where", "while", "abstract", "become", "box", "do", "final", "macro", "override", "priv",
+        "try", "typeof", "unsized", "virtual", "yield",
+    ];
+    if KEYWORDS.contains(&name) {
+        Ident::new_raw(name, Span::call_site())
+    } else {
+        Ident::new(name, Span::call_site())
+    }
+}
+
+/// Formats the generated code with `rustfmt`, if it's installed
+fn format_code(code: String) -> String {
+    let mut rustfmt = match Command::new("rustfmt")
+        .args(["--edition", "2021"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(rustfmt) => rustfmt,
+        Err(_) => return code,
+    };
+    let written = rustfmt
+        .stdin
+        .take()
+        .map(|mut stdin| stdin.write_all(code.as_bytes()).is_ok())
+        .unwrap_or_default();
+    match rustfmt.wait_with_output() {
+        Ok(output) if written && output.status.success() => {
+            String::from_utf8(output.stdout).unwrap_or(code)
+        }
+        _ => code,
+    }
+}
+
+/// A contract that needs to be linked, or a library that's required by one
+#[derive(Debug, Clone)]
+struct LinkedContract {
+    /// The identifier of the contract's module
+    ident: String,
+    /// The fully qualified name for libraries, otherwise the contract name
+    name: String,
+    /// The ABI as json
+    abi: String,
+    /// The deployment bytecode with zeroed placeholders
+    bytecode: String,
+    /// The libraries the contract is linked against
+    libraries: Vec<LinkedLibrary>,
+    /// All libraries that need to be deployed, in order, with the idents and positions of the
+    /// libraries they are linked against
+    deploy_order: Vec<(String, Vec<(String, usize)>)>,
+}
+
+/// A library a contract is linked against
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LinkedLibrary {
+    /// The fully qualified name of the library
+    name: String,
+    /// The identifier of the library's module
+    ident: String,
+    /// The byte offsets of the placeholders of the library
+    offsets: Vec<usize>,
+    /// The position of the library in the deploy order of the contract
+    position: usize,
+}
+
+// === impl LinkedContract ===
+
+impl LinkedContract {
+    fn tokens(&self) -> TokenStream {
+        let name = &self.name;
+        let module = ident(&self.ident);
+        let abi = &self.abi;
+        let bytecode = &self.bytecode;
+
+        let names = self.libraries.iter().map(|library| &library.name).collect::<Vec<_>>();
+        let fields = self.libraries.iter().map(|library| ident(&library.ident)).collect::<Vec<_>>();
+        let field_docs = names.iter().map(|name| format!("The address of `{}`", name));
+        let offsets = self.libraries.iter().map(|library| {
+            let offsets = library.offsets.iter().map(|offset| Literal::usize_unsuffixed(*offset));
+            quote!(&[#(#offsets),*])
+        });
+
+        let module_doc = format!("Linking helpers for `{}`", name);
+        let abi_doc = format!("The ABI of `{}`", name);
+        let bytecode_doc =
+            format!("The deployment bytecode of `{}` with zeroed library placeholders", name);
+        let libraries_doc = format!("The addresses of the libraries `{}` is linked against", name);
+        let deploy_doc =
+            format!("Returns a deployer for `{}` linked against the given libraries", name);
+
+        let deploy_libraries = (!self.deploy_order.is_empty()).then(|| {
+            let doc = format!(
+                "Deploys all libraries `{}` depends on in dependency order and returns their addresses",
+                name
+            );
+            let deployments = self.deploy_order.iter().enumerate().map(|(i, (library, deps))| {
+                let var = format_ident!("lib_{}", i);
+                let library = ident(library);
+                let deps = deps.iter().map(|(dep, position)| {
+                    let field = ident(dep);
+                    let var = format_ident!("lib_{}", position);
+                    quote!(#field: #var)
+                });
+                quote! {
+                    let #var = deploy_library(
+                        client.clone(),
+                        super::#library::bytecode(&super::#library::Libraries { #(#deps),* }),
+                    )
+                    .await?;
+                }
+            });
+            let vars = self.libraries.iter().map(|library| format_ident!("lib_{}", library.position));
+            quote! {
+                #[doc = #doc]
+                pub async fn deploy_libraries<M: Middleware>(
+                    client: Arc<M>,
+                ) -> Result<Libraries, ContractError<M>> {
+                    #(#deployments)*
+                    Ok(Libraries { #(#fields: #vars),* })
+                }
+            }
+        });
+
+        quote! {
+            #[doc = #module_doc]
+            pub mod #module {
+                use super::*;
+
+                #[doc = #abi_doc]
+                pub const ABI: &str = #abi;
+                #[doc = #bytecode_doc]
+                pub const BYTECODE: &str = #bytecode;
+                /// The byte offsets of the library placeholders in `BYTECODE`
+                pub const LINK_REFERENCES: &[(&str, &[usize])] = &[#((#names, #offsets)),*];
+
+                #[doc = #libraries_doc]
+                #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+                pub struct Libraries {
+                    #(
+                        #[doc = #field_docs]
+                        pub #fields: Address,
+                    )*
+                }
+
+                impl Libraries {
+                    /// Returns the addresses by fully qualified library name
+                    pub fn addresses(&self) -> Vec<(&'static str, Address)> {
+                        vec![#((#names, self.#fields)),*]
+                    }
+                }
+
+                /// Returns the deployment bytecode linked against the given libraries
+                pub fn bytecode(libraries: &Libraries) -> Bytes {
+                    link_bytecode(BYTECODE, LINK_REFERENCES, &libraries.addresses())
+                }
+
+                #[doc = #deploy_doc]
+                pub fn deploy<M: Middleware, T: Tokenize>(
+                    client: Arc<M>,
+                    libraries: &Libraries,
+                    constructor_args: T,
+                ) -> Result<Deployer<M>, ContractError<M>> {
+                    let abi = Abi::load(ABI.as_bytes()).expect("valid abi");
+                    ContractFactory::new(abi, bytecode(libraries), client).deploy(constructor_args)
+                }
+
+                #deploy_libraries
+            }
+        }
+    }
+}
+
+/// The imports and shared helpers of the generated module
+fn header() -> TokenStream {
+    quote! {
+        #![doc = "This module contains helpers to deploy contracts that depend on external libraries."]
+        #![doc = "It is autogenerated and should not be edited manually."]
+        #![allow(clippy::all)]
+        use ethers::{
+            contract::{ContractError, ContractFactory, Deployer},
+            core::{
+                abi::{Abi, Tokenize},
+                types::{Address, Bytes},
+            },
+            providers::Middleware,
+        };
+        use std::sync::Arc;
+
+        /// Replaces the library placeholders at the given offsets with the addresses of the
+        /// libraries
+        ///
+        /// # Panics
+        ///
+        /// If the address of a referenced library is missing
+        pub fn link_bytecode(
+            bytecode: &str,
+            link_references: &[(&str, &[usize])],
+            libraries: &[(&str, Address)],
+        ) -> Bytes {
+            let mut code = bytecode.parse::<Bytes>().expect("valid bytecode").to_vec();
+            for (library, offsets) in link_references {
+                let (_, address) = libraries
+                    .iter()
+                    .find(|(name, _)| name == library)
+                    .unwrap_or_else(|| panic!("missing address of library `{}`", library));
+                for offset in offsets.iter() {
+                    code[*offset..*offset + 20].copy_from_slice(address.as_bytes());
+                }
+            }
+            code.into()
+        }
+
+        /// Deploys a library with the given linked bytecode and returns its address
+        pub async fn deploy_library<M: Middleware>(
+            client: Arc<M>,
+            bytecode: Bytes,
+        ) -> Result<Address, ContractError<M>> {
+            let library =
+                ContractFactory::new(Abi::default(), bytecode, client).deploy(())?.send().await?;
+            Ok(library.address())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::utils::keccak256;
+
+    /// Returns the placeholder solc emits for the library with the fully qualified name
+    fn placeholder(name: &str) -> String {
+        format!("__${}$__", hex::encode(&keccak256(name)[..17]))
+    }
+
+    #[test]
+    fn converts_to_snake_case() {
+        assert_eq!(to_snake_case("SafeMath"), "safe_math");
+        assert_eq!(to_snake_case("SafeERC20"), "safe_erc20");
+        assert_eq!(to_snake_case("ERC20Lib"), "erc20_lib");
+        assert_eq!(to_snake_case("Math"), "math");
+        assert_eq!(ident("type").to_string(), "r#type");
+    }
+
+    #[test]
+    fn orders_library_deployments() {
+        let artifact = |name: &str, object: String, links: serde_json::Value| {
+            let bytecode = serde_json::json!({ "object": object, "linkReferences": links });
+            let contract = serde_json::from_value::<CompactContractBytecode>(serde_json::json!({
+                "abi": [],
+                "bytecode": bytecode,
+                "deployedBytecode": bytecode,
+            }))
+            .unwrap();
+            let id = ArtifactId {
+                path: PathBuf::from(format!("out/{name}.sol/{name}.json")),
+                name: name.to_string(),
+                source: PathBuf::from(format!("src/{name}.sol")),
+                version: Version::new(0, 8, 10),
+            };
+            (id, contract)
+        };
+        let contracts = BTreeMap::from([
+            artifact(
+                "Token",
+                format!("0x60{}60", placeholder("src/Math.sol:Math")),
+                serde_json::json!({
+                    "src/Math.sol": { "Math": [{ "start": 1, "length": 20 }] }
+                }),
+            ),
+            artifact(
+                "Math",
+                format!("0x{}", placeholder("src/Utils.sol:Utils")),
+                serde_json::json!({
+                    "src/Utils.sol": { "Utils": [{ "start": 0, "length": 20 }] }
+                }),
+            ),
+            artifact("Utils", "0x6000".to_string(), serde_json::json!({})),
+            artifact("Other", "0x6001".to_string(), serde_json::json!({})),
+        ]);
+
+        let module = LinkingModule::from_contracts(contracts).unwrap();
+        assert_eq!(module.len(), 3);
+
+        let token = module.contracts.iter().find(|c| c.ident == "token").unwrap();
+        assert_eq!(token.bytecode, format!("0x60{}60", "0".repeat(40)));
+        assert_eq!(
+            token.libraries,
+            vec![LinkedLibrary {
+                name: "src/Math.sol:Math".to_string(),
+                ident: "math".to_string(),
+                offsets: vec![1],
+                position: 1,
+            }]
+        );
+        assert_eq!(
+            token.deploy_order,
+            vec![
+                ("utils".to_string(), vec![]),
+                ("math".to_string(), vec![("utils".to_string(), 0)])
+            ]
+        );
+
+        let utils = module.contracts.iter().find(|c| c.ident == "utils").unwrap();
+        assert_eq!(utils.name, "src/Utils.sol:Utils");
+        assert!(utils.deploy_order.is_empty());
+
+        let contents = module.contents();
+        let file = syn::parse_file(&contents).unwrap();
+        assert!(file
+            .items
+            .iter()
+            .any(|item| { matches!(item, syn::Item::Mod(module) if module.ident == "token") }));
+        assert!(contents.contains("deploy_libraries"));
+    }
+}
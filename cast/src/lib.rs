@@ -1100,6 +1100,36 @@ impl SimpleCast {
         Ok(utils::to_checksum(address, None))
     }
 
+    /// Computes the address of a contract deployed with `CREATE2` from the address of the
+    /// deployer, the salt and the keccak256 hash of the init code, see [EIP-1014](https://eips.ethereum.org/EIPS/eip-1014)
+    ///
+    /// ```
+    /// use cast::SimpleCast as Cast;
+    /// use ethers_core::{types::{Address, H256}, utils::keccak256};
+    /// use std::str::FromStr;
+    ///
+    /// # fn main() -> eyre::Result<()> {
+    /// let init_code_hash = H256(keccak256([0x00]));
+    /// let addr = Cast::compute_create2_address(Address::zero(), H256::zero(), init_code_hash);
+    /// assert_eq!(addr, Address::from_str("0x4D1A2e2bB4F88F0250f26Ffff098B0b30B26BF38")?);
+    ///
+    /// let deployer = Address::from_str("0xdeadbeef00000000000000000000000000000000")?;
+    /// let salt = H256::from_str("0x000000000000000000000000feed000000000000000000000000000000000000")?;
+    /// let addr = Cast::compute_create2_address(deployer, salt, init_code_hash);
+    /// assert_eq!(addr, Address::from_str("0xD04116cDd17beBE565EB2422F2497E06cC1C9833")?);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn compute_create2_address(deployer: Address, salt: H256, init_code_hash: H256) -> Address {
+        let mut bytes = [0u8; 85];
+        bytes[0] = 0xff;
+        bytes[1..21].copy_from_slice(deployer.as_bytes());
+        bytes[21..53].copy_from_slice(salt.as_bytes());
+        bytes[53..].copy_from_slice(init_code_hash.as_bytes());
+        Address::from_slice(&keccak256(bytes)[12..])
+    }
+
     /// Converts hexdata into bytes32 value
     /// ```
     /// use cast::SimpleCast as Cast;
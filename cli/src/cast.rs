@@ -236,12 +236,18 @@ async fn main() -> eyre::Result<()> {
             let provider = Provider::try_from(rpc_url)?;
             println!("{}", provider.client_version().await?);
         }
-        Subcommands::ComputeAddress { rpc_url, address, nonce } => {
-            let rpc_url = consume_config_rpc_url(rpc_url);
-
+        Subcommands::ComputeAddress { rpc_url, address, nonce, salt, init_code_hash } => {
             let pubkey = Address::from_str(&address).expect("invalid pubkey provided");
-            let provider = Provider::try_from(rpc_url)?;
-            let addr = Cast::new(&provider).compute_address(pubkey, nonce).await?;
+            let addr = match (salt, init_code_hash) {
+                (Some(salt), Some(init_code_hash)) => {
+                    SimpleCast::compute_create2_address(pubkey, salt, init_code_hash)
+                }
+                _ => {
+                    let rpc_url = consume_config_rpc_url(rpc_url);
+                    let provider = Provider::try_from(rpc_url)?;
+                    Cast::new(&provider).compute_address(pubkey, nonce).await?
+                }
+            };
             println!("Computed Address: {:?}", addr);
         }
         Subcommands::Code { block, who, rpc_url } => {
@@ -599,13 +605,7 @@ async fn main() -> eyre::Result<()> {
             }
             println!("{name}");
         }
-        Subcommands::Storage { address, slot, rpc_url, block } => {
-            let rpc_url = consume_config_rpc_url(rpc_url);
-
-            let provider = Provider::try_from(rpc_url)?;
-            let value = provider.get_storage_at(address, slot, block).await?;
-            println!("{:?}", value);
-        }
+        Subcommands::Storage(cmd) => cmd.run()?.await?,
         Subcommands::Proof { address, slots, rpc_url, block } => {
             let rpc_url = consume_config_rpc_url(rpc_url);
 
@@ -663,6 +663,7 @@ async fn main() -> eyre::Result<()> {
             println!("0x{}", hex::encode(selector));
         }
         Subcommands::FindBlock(cmd) => cmd.run()?.await?,
+        Subcommands::Create2(cmd) => cmd.run()?,
         Subcommands::Wallet { command } => match command {
             WalletSubcommands::New { path, password, unsafe_password } => {
                 let mut rng = thread_rng();
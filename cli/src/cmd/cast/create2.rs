@@ -0,0 +1,152 @@
+//! cast create2 subcommand
+
+use crate::{cmd::Cmd, utils::DEFAULT_CREATE2_DEPLOYER};
+use cast::SimpleCast;
+use clap::Parser;
+use ethers::{
+    core::rand::{thread_rng, Rng},
+    types::{Address, H256},
+    utils::{hex, keccak256},
+};
+use eyre::Result;
+use rayon::prelude::*;
+use std::time::Instant;
+
+#[derive(Debug, Clone, Parser)]
+pub struct Create2Args {
+    #[clap(
+        long,
+        help = "Prefix for the contract address.",
+        required_unless_present = "ends-with",
+        value_name = "HEX"
+    )]
+    starts_with: Option<String>,
+    #[clap(long, help = "Suffix for the contract address.", value_name = "HEX")]
+    ends_with: Option<String>,
+    #[clap(
+        long,
+        help = "The address of the contract that deploys with CREATE2.",
+        default_value = DEFAULT_CREATE2_DEPLOYER,
+        value_name = "ADDRESS"
+    )]
+    deployer: Address,
+    #[clap(
+        long,
+        help = "The init code of the contract to be deployed.",
+        required_unless_present = "init-code-hash",
+        value_name = "HEX"
+    )]
+    init_code: Option<String>,
+    #[clap(
+        long,
+        help = "The keccak256 hash of the init code of the contract to be deployed.",
+        conflicts_with = "init-code",
+        value_name = "HASH"
+    )]
+    init_code_hash: Option<H256>,
+    #[clap(
+        long,
+        short,
+        help = "Number of threads to use. Defaults to the number of logical cores.",
+        value_name = "JOBS"
+    )]
+    jobs: Option<usize>,
+}
+
+impl Cmd for Create2Args {
+    type Output = ();
+
+    fn run(self) -> Result<Self::Output> {
+        let Create2Args { starts_with, ends_with, deployer, init_code, init_code_hash, jobs } =
+            self;
+
+        let prefix = parse_pattern(starts_with.as_deref().unwrap_or_default(), "prefix")?;
+        let suffix = parse_pattern(ends_with.as_deref().unwrap_or_default(), "suffix")?;
+        if prefix.len() + suffix.len() > 40 {
+            eyre::bail!("vanity patterns length exceeded. cannot be more than 40 characters")
+        }
+
+        let init_code_hash = match (init_code_hash, init_code) {
+            (Some(init_code_hash), _) => init_code_hash,
+            (None, Some(init_code)) => {
+                H256(keccak256(hex::decode(init_code.trim_start_matches("0x"))?))
+            }
+            (None, None) => eyre::bail!("either the init code or its hash must be provided"),
+        };
+
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(jobs.unwrap_or_default()).build()?;
+
+        println!("Starting to generate deterministic contract address...");
+        let timer = Instant::now();
+
+        // The salts share random upper 24 bytes and the counter in the lower 8 bytes is split among
+        // the threads, so every run finds a different salt
+        let base: [u8; 32] = thread_rng().gen();
+        let salt = pool
+            .install(|| {
+                (0..u64::MAX).into_par_iter().map(|n| salt_with_counter(base, n)).find_any(|salt| {
+                    let addr = hex::encode(
+                        SimpleCast::compute_create2_address(deployer, *salt, init_code_hash)
+                            .as_bytes(),
+                    );
+                    addr.starts_with(&prefix) && addr.ends_with(&suffix)
+                })
+            })
+            .ok_or_else(|| eyre::eyre!("could not find a matching salt"))?;
+
+        let address = SimpleCast::compute_create2_address(deployer, salt, init_code_hash);
+        println!(
+            "Successfully found contract address in {} seconds.\nAddress: {}\nSalt: {:?}",
+            timer.elapsed().as_secs(),
+            SimpleCast::checksum_address(&address)?,
+            salt,
+        );
+
+        Ok(())
+    }
+}
+
+/// Replaces the lower 8 bytes of the salt with the counter
+fn salt_with_counter(mut salt: [u8; 32], counter: u64) -> H256 {
+    salt[24..].copy_from_slice(&counter.to_be_bytes());
+    H256(salt)
+}
+
+/// Validates a vanity pattern and returns it in lowercase, to match the hex encoded address
+fn parse_pattern(pattern: &str, kind: &str) -> Result<String> {
+    let pattern = pattern.trim_start_matches("0x").to_lowercase();
+    if !pattern.chars().all(|c| c.is_ascii_hexdigit()) {
+        eyre::bail!("invalid {} hex provided: {}", kind, pattern)
+    }
+    Ok(pattern)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn salt_counter_is_big_endian() {
+        let salt = salt_with_counter([0xff; 32], 1);
+        assert_eq!(
+            salt,
+            H256::from_str("0xffffffffffffffffffffffffffffffffffffffffffffffff0000000000000001")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn finds_salt() {
+        let init_code_hash = H256(keccak256([0x00]));
+        let args = Create2Args::parse_from([
+            "create2",
+            "--starts-with",
+            "0xAA",
+            "--init-code-hash",
+            &format!("{:?}", init_code_hash),
+        ]);
+        assert_eq!(args.deployer, Address::from_str(DEFAULT_CREATE2_DEPLOYER).unwrap());
+        args.run().unwrap();
+    }
+}
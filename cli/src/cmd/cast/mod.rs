@@ -5,5 +5,7 @@
 //! implement `figment::Provider` which allows the subcommand to override the config's defaults, see
 //! [`foundry_config::Config`].
 
+pub mod create2;
 pub mod find_block;
 pub mod run;
+pub mod storage;
@@ -0,0 +1,800 @@
+//! cast storage subcommand
+
+use crate::{
+    cmd::Cmd,
+    compile,
+    opts::{
+        cast::{parse_block_id, parse_name_or_address, parse_slot},
+        ClapChain,
+    },
+    utils::consume_config_rpc_url,
+};
+use clap::Parser;
+use comfy_table::Table;
+use ethers::{
+    abi::{self, ParamType, Token},
+    etherscan::Client,
+    prelude::{Http, Middleware, Provider},
+    solc::artifacts::output_selection::ContractOutputSelection,
+    types::{Address, BlockId, NameOrAddress, H256, U256},
+    utils::keccak256,
+};
+use eyre::{Context, Result};
+use foundry_config::Config;
+use foundry_utils::{format_token, parse_tokens};
+use futures::future::BoxFuture;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// The maximum number of elements of an array that are read, every element requires a separate
+/// request
+const MAX_ARRAY_ELEMENTS: u64 = 100;
+
+#[derive(Debug, Clone, Parser)]
+pub struct StorageArgs {
+    #[clap(help = "The contract address.", parse(try_from_str = parse_name_or_address), value_name = "ADDRESS")]
+    address: NameOrAddress,
+    #[clap(
+        help = "The storage slot number (hex or decimal), or the state variable to read.",
+        long_help = r#"The storage slot number (hex or decimal), or the state variable to read.
+
+A state variable is decoded according to the storage layout of the contract, e.g. `owner`, `balances[0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045]`, `values[2]` or `config.fee`."#,
+        required_unless_present = "dump-all",
+        value_name = "SLOT_OR_VARIABLE"
+    )]
+    slot: Option<String>,
+    #[clap(long, help = "Print every state variable of the contract.", conflicts_with = "slot")]
+    dump_all: bool,
+    #[clap(
+        long,
+        help = "The name of the local contract whose storage layout to use.",
+        long_help = "The name of the local contract whose storage layout to use. If not set, the verified source of the contract is fetched from Etherscan.",
+        value_name = "CONTRACT"
+    )]
+    contract: Option<String>,
+    #[clap(short, long, env = "ETH_RPC_URL", value_name = "URL")]
+    rpc_url: Option<String>,
+    #[clap(
+        long,
+        short = 'B',
+        help = "The block height you want to query at.",
+        long_help = "The block height you want to query at. Can also be the tags earliest, latest, or pending.",
+        parse(try_from_str = parse_block_id),
+        value_name = "BLOCK"
+    )]
+    block: Option<BlockId>,
+    #[clap(long, env = "ETHERSCAN_API_KEY", value_name = "KEY")]
+    etherscan_api_key: Option<String>,
+    #[clap(flatten)]
+    chain: ClapChain,
+}
+
+impl Cmd for StorageArgs {
+    type Output = BoxFuture<'static, Result<()>>;
+
+    fn run(self) -> Result<Self::Output> {
+        Ok(Box::pin(self.run_storage()))
+    }
+}
+
+impl StorageArgs {
+    async fn run_storage(self) -> Result<()> {
+        let rpc_url = consume_config_rpc_url(self.rpc_url.clone());
+        let provider = Provider::try_from(rpc_url)?;
+
+        // a variable can't start with a digit, so this is a raw slot
+        if let Some(slot) = self.slot.as_ref().filter(|slot| slot.starts_with(char::is_numeric)) {
+            let slot = parse_slot(slot)?;
+            let value = provider.get_storage_at(self.address.clone(), slot, self.block).await?;
+            println!("{:?}", value);
+            return Ok(())
+        }
+
+        let address = match self.address {
+            NameOrAddress::Address(address) => address,
+            NameOrAddress::Name(ref name) => provider.resolve_name(name).await?,
+        };
+        let layout = self.storage_layout(address).await?;
+        let reader = ProviderReader { provider: &provider, address, block: self.block };
+
+        if self.dump_all {
+            let mut table = Table::new();
+            table.set_header(vec!["Name", "Type", "Slot", "Offset", "Value"]);
+            for variable in &layout.storage {
+                let location = variable.location(U256::zero())?;
+                let value = layout.read_value(&reader, &location).await?;
+                table.add_row(vec![
+                    variable.label.clone(),
+                    layout.ty(&variable.ty)?.label.clone(),
+                    variable.slot.clone(),
+                    variable.offset.to_string(),
+                    value,
+                ]);
+            }
+            println!("{table}");
+        } else {
+            let variable = self.slot.as_deref().expect("required unless dump-all");
+            let location = layout.resolve(&reader, variable).await?;
+            println!("{}", layout.read(&reader, &location).await?);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the storage layout of the local `--contract`, or of the verified contract on
+    /// Etherscan
+    async fn storage_layout(&self, address: Address) -> Result<StorageLayout> {
+        let (config, contract) = if let Some(ref contract) = self.contract {
+            let mut config = Config::load();
+            config.extra_output.push(ContractOutputSelection::StorageLayout);
+            (config, contract.clone())
+        } else {
+            let api_key = self
+                .etherscan_api_key
+                .clone()
+                .or_else(|| Config::load().etherscan_api_key)
+                .ok_or_else(|| eyre::eyre!("No local contract was specified with `--contract` and no Etherscan API Key is set. Consider using the ETHERSCAN_API_KEY env var, or setting the --etherscan-api-key CLI argument or etherscan-api-key in foundry.toml"))?;
+            let client = Client::new(self.chain.inner, api_key)?;
+            let meta = client.contract_source_code(address).await?;
+            let contract = meta
+                .items
+                .first()
+                .map(|item| item.contract_name.clone())
+                .filter(|name| !name.is_empty())
+                .ok_or_else(|| eyre::eyre!("unverified contract"))?;
+
+            // compile the verified sources as a standalone project
+            let root = std::env::temp_dir().join(format!("cast-storage-{:?}", address));
+            meta.source_tree()?.write_to(&root)?;
+            let config = Config {
+                src: root.clone(),
+                libs: vec![root.clone()],
+                extra_output: vec![ContractOutputSelection::StorageLayout],
+                ..Config::with_root(&root)
+            };
+            (config, contract)
+        };
+
+        let project = config.ephemeral_no_artifacts_project()?;
+        let outcome = compile::suppress_compile(&project)?;
+        let artifact = outcome.find(&contract).ok_or_else(|| {
+            eyre::eyre!("Could not find artifact `{contract}` in the compiled artifacts")
+        })?;
+        let layout = artifact
+            .storage_layout
+            .as_ref()
+            .ok_or_else(|| eyre::eyre!("No storage layout for `{contract}`"))?;
+        serde_json::from_value(serde_json::to_value(layout)?)
+            .wrap_err("Failed to read the storage layout")
+    }
+}
+
+/// Reads raw storage slots
+trait SlotReader: Sync {
+    /// Returns the word stored at the given slot
+    fn read_slot(&self, slot: U256) -> BoxFuture<'_, Result<H256>>;
+}
+
+/// Reads the slots of a deployed contract
+struct ProviderReader<'a> {
+    provider: &'a Provider<Http>,
+    address: Address,
+    block: Option<BlockId>,
+}
+
+impl<'a> SlotReader for ProviderReader<'a> {
+    fn read_slot(&self, slot: U256) -> BoxFuture<'_, Result<H256>> {
+        Box::pin(async move {
+            Ok(self.provider.get_storage_at(self.address, word(slot), self.block).await?)
+        })
+    }
+}
+
+/// The storage layout of a contract, as emitted by solc's `storageLayout` output
+#[derive(Debug, Clone, Deserialize)]
+struct StorageLayout {
+    storage: Vec<StorageVariable>,
+    types: Option<BTreeMap<String, StorageType>>,
+}
+
+/// A state variable, or a member of a struct
+#[derive(Debug, Clone, Deserialize)]
+struct StorageVariable {
+    label: String,
+    offset: usize,
+    slot: String,
+    #[serde(rename = "type")]
+    ty: String,
+}
+
+impl StorageVariable {
+    /// Returns the location of the variable, relative to the given slot
+    fn location(&self, base: U256) -> Result<Location> {
+        Ok(Location {
+            slot: base + parse_u256(&self.slot)?,
+            offset: self.offset,
+            ty: self.ty.clone(),
+        })
+    }
+}
+
+/// A type of the storage layout, e.g. `t_uint256`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StorageType {
+    /// One of `inplace`, `mapping`, `dynamic_array` or `bytes`
+    encoding: String,
+    label: String,
+    number_of_bytes: String,
+    /// The key type of a mapping
+    key: Option<String>,
+    /// The value type of a mapping
+    value: Option<String>,
+    /// The element type of an array
+    base: Option<String>,
+    /// The members of a struct
+    members: Option<Vec<StorageVariable>>,
+}
+
+/// The location of a value in storage
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Location {
+    slot: U256,
+    /// The offset of the value in the slot, in bytes
+    offset: usize,
+    /// The type identifier of the value
+    ty: String,
+}
+
+impl StorageLayout {
+    fn ty(&self, ty: &str) -> Result<&StorageType> {
+        self.types
+            .as_ref()
+            .and_then(|types| types.get(ty))
+            .ok_or_else(|| eyre::eyre!("Unknown storage type `{ty}`"))
+    }
+
+    /// Returns the location of the state variable with the given name
+    fn variable(&self, name: &str) -> Result<Location> {
+        self.storage
+            .iter()
+            .find(|variable| variable.label == name)
+            .ok_or_else(|| eyre::eyre!("No state variable named `{name}`"))?
+            .location(U256::zero())
+    }
+
+    /// Returns the location of an expression like `balances[0x..]` or `config.fee`
+    ///
+    /// The length of dynamic arrays is read to check the bounds of their indices.
+    async fn resolve(&self, reader: &dyn SlotReader, expr: &str) -> Result<Location> {
+        let (name, accessors) = parse_variable(expr)?;
+        let mut location = self.variable(name)?;
+
+        for accessor in accessors {
+            let ty = self.ty(&location.ty)?;
+            location = match accessor {
+                Accessor::Index(key) => match ty.encoding.as_str() {
+                    "mapping" => {
+                        let key_ty = self.ty(ty.key.as_deref().unwrap_or_default())?;
+                        let mut preimage = encode_key(key, &key_ty.label)?;
+                        preimage.extend_from_slice(word(location.slot).as_bytes());
+                        Location {
+                            slot: U256::from(keccak256(preimage)),
+                            offset: 0,
+                            ty: ty.value.clone().unwrap_or_default(),
+                        }
+                    }
+                    "dynamic_array" => {
+                        let index = parse_u256(key)?;
+                        let len = U256::from(reader.read_slot(location.slot).await?.as_bytes());
+                        if index >= len {
+                            eyre::bail!(
+                                "Index {index} out of bounds for `{}` of length {len}",
+                                ty.label
+                            )
+                        }
+                        let start = U256::from(keccak256(word(location.slot)));
+                        self.element(ty, start, index)?
+                    }
+                    "inplace" if ty.base.is_some() => {
+                        let index = parse_u256(key)?;
+                        if index >= static_len(ty)?.into() {
+                            eyre::bail!("Index {index} out of bounds for `{}`", ty.label)
+                        }
+                        self.element(ty, location.slot, index)?
+                    }
+                    _ => eyre::bail!("Can't index into `{}`", ty.label),
+                },
+                Accessor::Member(member) => ty
+                    .members
+                    .as_ref()
+                    .and_then(|members| members.iter().find(|m| m.label == member))
+                    .ok_or_else(|| eyre::eyre!("No member `{member}` in `{}`", ty.label))?
+                    .location(location.slot)?,
+            };
+        }
+
+        Ok(location)
+    }
+
+    /// Returns the location of the element at `index` of an array that starts at `start`
+    fn element(&self, array: &StorageType, start: U256, index: U256) -> Result<Location> {
+        let base = array.base.clone().unwrap_or_default();
+        let size = parse_usize(&self.ty(&base)?.number_of_bytes)?;
+        Ok(if size > 16 {
+            // every element occupies whole slots, slot arithmetic wraps around like in solc
+            let slots = U256::from((size + 31) / 32);
+            Location {
+                slot: start.overflowing_add(index.overflowing_mul(slots).0).0,
+                offset: 0,
+                ty: base,
+            }
+        } else {
+            // multiple elements are packed into a slot
+            let per_slot = 32 / size.max(1);
+            Location {
+                slot: start.overflowing_add(index / per_slot).0,
+                offset: (index % per_slot).as_usize() * size,
+                ty: base,
+            }
+        })
+    }
+
+    /// Reads the value at the location and formats it according to its type
+    ///
+    /// Fails for a mapping, because its values can only be read by key.
+    async fn read(&self, reader: &dyn SlotReader, location: &Location) -> Result<String> {
+        let ty = self.ty(&location.ty)?;
+        if ty.encoding == "mapping" {
+            eyre::bail!(
+                "`{}` is a mapping, specify a key to read, e.g. `variable[<key>]`",
+                ty.label
+            )
+        }
+        self.read_value(reader, location).await
+    }
+
+    /// Reads the value at the location and formats it according to its type
+    ///
+    /// Mappings, also those nested in structs and arrays, are formatted as `-`.
+    fn read_value<'a>(
+        &'a self,
+        reader: &'a dyn SlotReader,
+        location: &'a Location,
+    ) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            let ty = self.ty(&location.ty)?;
+            match ty.encoding.as_str() {
+                "mapping" => Ok("-".to_string()),
+                "bytes" => {
+                    let bytes = read_bytes(reader, location.slot).await?;
+                    Ok(if ty.label == "string" {
+                        format_token(&Token::String(String::from_utf8_lossy(&bytes).into_owned()))
+                    } else {
+                        format_token(&Token::Bytes(bytes))
+                    })
+                }
+                "dynamic_array" => {
+                    let len = U256::from(reader.read_slot(location.slot).await?.as_bytes());
+                    let start = U256::from(keccak256(word(location.slot)));
+                    let mut values = Vec::new();
+                    for index in 0..len.min(MAX_ARRAY_ELEMENTS.into()).as_u64() {
+                        let element = self.element(ty, start, index.into())?;
+                        values.push(self.read_value(reader, &element).await?);
+                    }
+                    Ok(format_array(&values, len))
+                }
+                _ => {
+                    if let Some(ref members) = ty.members {
+                        let mut values = Vec::with_capacity(members.len());
+                        for member in members {
+                            let member = member.location(location.slot)?;
+                            values.push(self.read_value(reader, &member).await?);
+                        }
+                        Ok(format!("({})", values.join(", ")))
+                    } else if ty.base.is_some() {
+                        let len = static_len(ty)?;
+                        let mut values = Vec::new();
+                        for index in 0..len.min(MAX_ARRAY_ELEMENTS) {
+                            let element = self.element(ty, location.slot, index.into())?;
+                            values.push(self.read_value(reader, &element).await?);
+                        }
+                        Ok(format_array(&values, len.into()))
+                    } else {
+                        let word = reader.read_slot(location.slot).await?;
+                        let size = parse_usize(&ty.number_of_bytes)?;
+                        decode_value(&word, location.offset, size, &ty.label)
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Formats the elements of an array of length `len`, of which at most [MAX_ARRAY_ELEMENTS] were
+/// read
+fn format_array(values: &[String], len: U256) -> String {
+    if len > MAX_ARRAY_ELEMENTS.into() {
+        format!(
+            "[{}, ...] (length {len}, showing the first {MAX_ARRAY_ELEMENTS})",
+            values.join(", ")
+        )
+    } else {
+        format!("[{}]", values.join(", "))
+    }
+}
+
+/// Returns the length of a static array, e.g. `3` for `uint256[3]`
+fn static_len(array: &StorageType) -> Result<u64> {
+    array
+        .label
+        .rsplit_once('[')
+        .and_then(|(_, len)| len.strip_suffix(']'))
+        .and_then(|len| len.parse().ok())
+        .ok_or_else(|| eyre::eyre!("Unknown length of `{}`", array.label))
+}
+
+/// Accesses a member of a value
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Accessor<'a> {
+    /// `[key]`
+    Index(&'a str),
+    /// `.member`
+    Member(&'a str),
+}
+
+/// Splits an expression like `balances[0x..].amount` into the variable name and the accessors
+fn parse_variable(expr: &str) -> Result<(&str, Vec<Accessor<'_>>)> {
+    let expr = expr.trim();
+    let end = expr.find(|c| c == '[' || c == '.').unwrap_or(expr.len());
+    let (name, mut rest) = expr.split_at(end);
+    if name.is_empty() {
+        eyre::bail!("Invalid variable `{expr}`")
+    }
+
+    let mut accessors = Vec::new();
+    while !rest.is_empty() {
+        if let Some(index) = rest.strip_prefix('[') {
+            let (key, remaining) = index
+                .split_once(']')
+                .ok_or_else(|| eyre::eyre!("Missing `]` in variable `{expr}`"))?;
+            accessors.push(Accessor::Index(key.trim()));
+            rest = remaining;
+        } else if let Some(member) = rest.strip_prefix('.') {
+            let end = member.find(|c| c == '[' || c == '.').unwrap_or(member.len());
+            let (member, remaining) = member.split_at(end);
+            if member.is_empty() {
+                eyre::bail!("Invalid variable `{expr}`")
+            }
+            accessors.push(Accessor::Member(member));
+            rest = remaining;
+        } else {
+            eyre::bail!("Invalid variable `{expr}`")
+        }
+    }
+
+    Ok((name, accessors))
+}
+
+/// Returns the ABI type of a value type's storage label, e.g. `contract Token` is an `address`
+fn param_type(label: &str) -> Option<ParamType> {
+    let size = |prefix: &str, default: usize| {
+        label.strip_prefix(prefix).and_then(|size| {
+            if size.is_empty() {
+                Some(default)
+            } else {
+                size.parse::<usize>().ok()
+            }
+        })
+    };
+    Some(match label {
+        "bool" => ParamType::Bool,
+        "address" | "address payable" => ParamType::Address,
+        "string" => ParamType::String,
+        "bytes" => ParamType::Bytes,
+        label if label.starts_with("contract ") => ParamType::Address,
+        label if label.starts_with("enum ") => ParamType::Uint(8),
+        label if label.starts_with("uint") => ParamType::Uint(size("uint", 256)?),
+        label if label.starts_with("int") => ParamType::Int(size("int", 256)?),
+        label if label.starts_with("bytes") => ParamType::FixedBytes(size("bytes", 32)?),
+        _ => return None,
+    })
+}
+
+/// Encodes a mapping key the way solc hashes it together with the slot of the mapping
+fn encode_key(key: &str, label: &str) -> Result<Vec<u8>> {
+    let ty = param_type(label).ok_or_else(|| eyre::eyre!("Unsupported mapping key `{label}`"))?;
+    Ok(match ty {
+        ParamType::String => key.trim_matches('"').as_bytes().to_vec(),
+        ParamType::Bytes => hex::decode(key.strip_prefix("0x").unwrap_or(key))?,
+        ty => abi::encode(&parse_tokens(std::iter::once((&ty, key)), true)?),
+    })
+}
+
+/// Decodes the `size` bytes at `offset` of the storage slot
+fn decode_value(word: &H256, offset: usize, size: usize, label: &str) -> Result<String> {
+    if offset + size > 32 {
+        eyre::bail!("Invalid offset {offset} of `{label}`")
+    }
+    // values are packed from the lower order bytes
+    let bytes = &word.as_bytes()[32 - offset - size..32 - offset];
+    let value = U256::from(bytes);
+    let token = match param_type(label) {
+        Some(ParamType::Bool) => Token::Bool(!value.is_zero()),
+        Some(ParamType::Address) => Token::Address(Address::from_slice(&bytes[bytes.len() - 20..])),
+        Some(ParamType::Int(_)) => {
+            // sign extend the value
+            let negative = bytes.first().map_or(false, |byte| byte & 0x80 != 0);
+            let mut padded = if negative { [0xff; 32] } else { [0; 32] };
+            padded[32 - size..].copy_from_slice(bytes);
+            Token::Int(U256::from(padded))
+        }
+        Some(ParamType::FixedBytes(_)) => Token::FixedBytes(bytes.to_vec()),
+        _ => Token::Uint(value),
+    };
+    Ok(format_token(&token))
+}
+
+/// Reads a `bytes` or `string` value
+async fn read_bytes(reader: &dyn SlotReader, slot: U256) -> Result<Vec<u8>> {
+    let head = reader.read_slot(slot).await?;
+    let last = head.as_bytes()[31];
+    if last & 1 == 0 {
+        // short values are stored in the slot, together with `length * 2`
+        let len = (last / 2) as usize;
+        return Ok(head.as_bytes()[..len.min(31)].to_vec())
+    }
+
+    // long values are stored at `keccak(slot)`, the slot contains `length * 2 + 1`
+    let len = U256::from(head.as_bytes()) / 2;
+    let len = usize::try_from(len).map_err(|_| eyre::eyre!("Invalid length {len} of value"))?;
+    let start = U256::from(keccak256(word(slot)));
+    let slots = len / 32 + usize::from(len % 32 != 0);
+    let mut bytes = Vec::new();
+    for idx in 0..slots {
+        bytes.extend_from_slice(
+            reader.read_slot(start.overflowing_add(idx.into()).0).await?.as_bytes(),
+        );
+    }
+    bytes.truncate(len);
+    Ok(bytes)
+}
+
+/// Returns the slot as a 32 byte word
+fn word(slot: U256) -> H256 {
+    let mut word = H256::zero();
+    slot.to_big_endian(word.as_bytes_mut());
+    word
+}
+
+fn parse_u256(s: &str) -> Result<U256> {
+    U256::from_dec_str(s).wrap_err_with(|| format!("Invalid number `{s}`"))
+}
+
+fn parse_usize(s: &str) -> Result<usize> {
+    s.parse().wrap_err_with(|| format!("Invalid number `{s}`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::solc::utils::RuntimeOrHandle;
+
+    /// Storage layout of
+    ///
+    /// ```solidity
+    /// contract Storage {
+    ///     struct Config { uint128 fee; bool paused; address admin; }
+    ///     address owner;
+    ///     bool locked;
+    ///     uint8 decimals;
+    ///     mapping(address => uint256) balances;
+    ///     uint64[] values;
+    ///     Config config;
+    ///     string name;
+    ///     int16 delta;
+    ///     struct Account { uint256 id; mapping(address => uint256) allowances; }
+    ///     Account account;
+    ///     uint256[1000000] large;
+    /// }
+    /// ```
+    const LAYOUT: &str = r#"{
+        "storage": [
+            {"astId": 1, "contract": "Storage.sol:Storage", "label": "owner", "offset": 0, "slot": "0", "type": "t_address"},
+            {"astId": 2, "contract": "Storage.sol:Storage", "label": "locked", "offset": 20, "slot": "0", "type": "t_bool"},
+            {"astId": 3, "contract": "Storage.sol:Storage", "label": "decimals", "offset": 21, "slot": "0", "type": "t_uint8"},
+            {"astId": 4, "contract": "Storage.sol:Storage", "label": "balances", "offset": 0, "slot": "1", "type": "t_mapping(t_address,t_uint256)"},
+            {"astId": 5, "contract": "Storage.sol:Storage", "label": "values", "offset": 0, "slot": "2", "type": "t_array(t_uint64)dyn_storage"},
+            {"astId": 6, "contract": "Storage.sol:Storage", "label": "config", "offset": 0, "slot": "3", "type": "t_struct(Config)1_storage"},
+            {"astId": 7, "contract": "Storage.sol:Storage", "label": "name", "offset": 0, "slot": "5", "type": "t_string_storage"},
+            {"astId": 8, "contract": "Storage.sol:Storage", "label": "delta", "offset": 0, "slot": "6", "type": "t_int16"},
+            {"astId": 12, "contract": "Storage.sol:Storage", "label": "account", "offset": 0, "slot": "7", "type": "t_struct(Account)2_storage"},
+            {"astId": 15, "contract": "Storage.sol:Storage", "label": "large", "offset": 0, "slot": "9", "type": "t_array(t_uint256)1000000_storage"}
+        ],
+        "types": {
+            "t_address": {"encoding": "inplace", "label": "address", "numberOfBytes": "20"},
+            "t_bool": {"encoding": "inplace", "label": "bool", "numberOfBytes": "1"},
+            "t_int16": {"encoding": "inplace", "label": "int16", "numberOfBytes": "2"},
+            "t_uint8": {"encoding": "inplace", "label": "uint8", "numberOfBytes": "1"},
+            "t_uint64": {"encoding": "inplace", "label": "uint64", "numberOfBytes": "8"},
+            "t_uint128": {"encoding": "inplace", "label": "uint128", "numberOfBytes": "16"},
+            "t_uint256": {"encoding": "inplace", "label": "uint256", "numberOfBytes": "32"},
+            "t_string_storage": {"encoding": "bytes", "label": "string", "numberOfBytes": "32"},
+            "t_mapping(t_address,t_uint256)": {"encoding": "mapping", "key": "t_address", "label": "mapping(address => uint256)", "numberOfBytes": "32", "value": "t_uint256"},
+            "t_array(t_uint64)dyn_storage": {"base": "t_uint64", "encoding": "dynamic_array", "label": "uint64[]", "numberOfBytes": "32"},
+            "t_array(t_uint256)1000000_storage": {"base": "t_uint256", "encoding": "inplace", "label": "uint256[1000000]", "numberOfBytes": "32000000"},
+            "t_struct(Account)2_storage": {"encoding": "inplace", "label": "struct Storage.Account", "numberOfBytes": "64", "members": [
+                {"astId": 13, "contract": "Storage.sol:Storage", "label": "id", "offset": 0, "slot": "0", "type": "t_uint256"},
+                {"astId": 14, "contract": "Storage.sol:Storage", "label": "allowances", "offset": 0, "slot": "1", "type": "t_mapping(t_address,t_uint256)"}
+            ]},
+            "t_struct(Config)1_storage": {"encoding": "inplace", "label": "struct Storage.Config", "numberOfBytes": "64", "members": [
+                {"astId": 9, "contract": "Storage.sol:Storage", "label": "fee", "offset": 0, "slot": "0", "type": "t_uint128"},
+                {"astId": 10, "contract": "Storage.sol:Storage", "label": "paused", "offset": 16, "slot": "0", "type": "t_bool"},
+                {"astId": 11, "contract": "Storage.sol:Storage", "label": "admin", "offset": 0, "slot": "1", "type": "t_address"}
+            ]}
+        }
+    }"#;
+
+    impl SlotReader for BTreeMap<U256, H256> {
+        fn read_slot(&self, slot: U256) -> BoxFuture<'_, Result<H256>> {
+            Box::pin(async move { Ok(self.get(&slot).copied().unwrap_or_default()) })
+        }
+    }
+
+    fn layout() -> StorageLayout {
+        serde_json::from_str(LAYOUT).unwrap()
+    }
+
+    fn h256(s: &str) -> H256 {
+        format!("{:0>64}", s).parse().unwrap()
+    }
+
+    #[test]
+    fn parses_variables() {
+        assert_eq!(parse_variable("owner").unwrap(), ("owner", vec![]));
+        assert_eq!(
+            parse_variable("users[0xabc].balances[1].amount").unwrap(),
+            (
+                "users",
+                vec![
+                    Accessor::Index("0xabc"),
+                    Accessor::Member("balances"),
+                    Accessor::Index("1"),
+                    Accessor::Member("amount"),
+                ]
+            )
+        );
+        assert!(parse_variable("users[0xabc").is_err());
+        assert!(parse_variable("users.").is_err());
+        assert!(parse_variable("[1]").is_err());
+    }
+
+    #[test]
+    fn resolves_locations() {
+        let layout = layout();
+        // values.length = 6
+        let storage: BTreeMap<U256, H256> = BTreeMap::from([(2.into(), h256("6"))]);
+        let resolve = |expr: &str| RuntimeOrHandle::new().block_on(layout.resolve(&storage, expr));
+
+        assert_eq!(
+            resolve("decimals").unwrap(),
+            Location { slot: 0.into(), offset: 21, ty: "t_uint8".to_string() }
+        );
+
+        // keccak256(abi.encode(key, 1))
+        let key = "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045";
+        let mut preimage = abi::encode(&[Token::Address(key.parse().unwrap())]);
+        preimage.extend_from_slice(word(1.into()).as_bytes());
+        assert_eq!(
+            resolve(&format!("balances[{key}]")).unwrap(),
+            Location {
+                slot: U256::from(keccak256(preimage)),
+                offset: 0,
+                ty: "t_uint256".to_string()
+            }
+        );
+
+        // four uint64 values are packed into a slot starting at keccak256(2)
+        let start = U256::from(keccak256(word(2.into())));
+        assert_eq!(
+            resolve("values[5]").unwrap(),
+            Location { slot: start + 1, offset: 8, ty: "t_uint64".to_string() }
+        );
+
+        assert_eq!(
+            resolve("config.paused").unwrap(),
+            Location { slot: 3.into(), offset: 16, ty: "t_bool".to_string() }
+        );
+        assert_eq!(
+            resolve("config.admin").unwrap(),
+            Location { slot: 4.into(), offset: 0, ty: "t_address".to_string() }
+        );
+
+        assert!(resolve("unknown").is_err());
+        assert!(resolve("owner[1]").is_err());
+        assert!(resolve("config.unknown").is_err());
+        assert!(resolve("values[6]").is_err());
+    }
+
+    #[test]
+    fn reads_values() {
+        let layout = layout();
+        let start = U256::from(keccak256(word(2.into())));
+        let storage: BTreeMap<U256, H256> = BTreeMap::from([
+            // decimals = 18, locked = true, owner
+            (0.into(), h256("1201d8da6bf26964af9d7eed9e03e53415d37aa96045")),
+            // values.length = 2
+            (2.into(), h256("2")),
+            (start, h256("00000000000000070000000000000003")),
+            // config.paused = true, config.fee = 100
+            (3.into(), h256("0100000000000000000000000000000064")),
+            // name = "anvil"
+            (
+                5.into(),
+                "0x616e76696c00000000000000000000000000000000000000000000000000000a"
+                    .parse()
+                    .unwrap(),
+            ),
+            // delta = -2
+            (6.into(), h256("fffe")),
+        ]);
+        let read = |expr: &str| {
+            RuntimeOrHandle::new()
+                .block_on(async {
+                    let location = layout.resolve(&storage, expr).await?;
+                    layout.read(&storage, &location).await
+                })
+                .unwrap()
+        };
+
+        assert_eq!(read("owner"), "0xd8da6bf26964af9d7eed9e03e53415d37aa96045");
+        assert_eq!(read("locked"), "true");
+        assert_eq!(read("decimals"), "18");
+        assert_eq!(read("values"), "[3, 7]");
+        assert_eq!(read("values[1]"), "7");
+        assert_eq!(read("config"), "(100, true, 0x0000000000000000000000000000000000000000)");
+        assert_eq!(read("name"), "\"anvil\"");
+        assert_eq!(read("delta"), "-2");
+    }
+
+    #[test]
+    fn reads_first_elements_of_long_arrays() {
+        let layout = layout();
+        // values.length = 1000
+        let storage: BTreeMap<U256, H256> = BTreeMap::from([(2.into(), h256("3e8"))]);
+        let location = RuntimeOrHandle::new().block_on(layout.resolve(&storage, "values")).unwrap();
+        let value = RuntimeOrHandle::new().block_on(layout.read(&storage, &location)).unwrap();
+
+        let elements = vec!["0"; MAX_ARRAY_ELEMENTS as usize].join(", ");
+        assert_eq!(value, format!("[{elements}, ...] (length 1000, showing the first 100)"));
+
+        let location = RuntimeOrHandle::new().block_on(layout.resolve(&storage, "large")).unwrap();
+        let value = RuntimeOrHandle::new().block_on(layout.read(&storage, &location)).unwrap();
+        assert_eq!(value, format!("[{elements}, ...] (length 1000000, showing the first 100)"));
+    }
+
+    #[test]
+    fn reads_nested_mappings_as_placeholder() {
+        let layout = layout();
+        // account.id = 5
+        let storage: BTreeMap<U256, H256> = BTreeMap::from([(7.into(), h256("5"))]);
+        let read = |expr: &str| {
+            RuntimeOrHandle::new().block_on(async {
+                let location = layout.resolve(&storage, expr).await?;
+                Ok::<_, eyre::Report>((
+                    layout.read(&storage, &location).await,
+                    layout.read_value(&storage, &location).await?,
+                ))
+            })
+        };
+
+        let (value, dumped) = read("account").unwrap();
+        assert_eq!(value.unwrap(), "(5, -)");
+        assert_eq!(dumped, "(5, -)");
+
+        // only a mapping that is read directly requires a key
+        let (value, dumped) = read("balances").unwrap();
+        assert!(value.is_err());
+        assert_eq!(dumped, "-");
+    }
+}
@@ -2,6 +2,7 @@ use crate::cmd::utils::Cmd;
 
 use clap::{Parser, ValueHint};
 use ethers::contract::MultiAbigen;
+use foundry_binder::linking::LinkingModule;
 use foundry_config::{
     figment::{
         self,
@@ -12,7 +13,11 @@ use foundry_config::{
     impl_figment_convert, Config,
 };
 use serde::Serialize;
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use walkdir::WalkDir;
 
 impl_figment_convert!(BindArgs);
 
@@ -106,8 +111,18 @@ No contract artifacts found. Hint: Have you built your contracts yet? `forge bin
         Ok(multi)
     }
 
+    /// Instantiate the deployers for contracts that need to be linked against libraries
+    fn get_linking(&self) -> eyre::Result<LinkingModule> {
+        LinkingModule::from_json_files(self.artifacts())
+    }
+
     /// Check that the existing bindings match the expected abigen output
     fn check_existing_bindings(&self) -> eyre::Result<()> {
+        let linking = self.get_linking()?;
+        if !linking.is_empty() {
+            return self.check_existing_linked_bindings()
+        }
+
         let bindings = self.get_multi()?.build()?;
         println!("Checking bindings for {} contracts.", bindings.len());
         if !self.module {
@@ -125,21 +140,71 @@ No contract artifacts found. Hint: Have you built your contracts yet? `forge bin
         Ok(())
     }
 
+    /// Check the existing bindings of a project with linked contracts
+    ///
+    /// The `linking` module is declared in the bindings' `mod.rs` or `lib.rs`, so the bindings are
+    /// generated into a temporary directory and compared file by file.
+    fn check_existing_linked_bindings(&self) -> eyre::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let expected = tmp.path().join("bindings");
+        let (bindings, linked) = self.write_bindings(&expected)?;
+        println!("Checking bindings for {} contracts and {} linked contracts.", bindings, linked);
+
+        for entry in WalkDir::new(&expected)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_file())
+        {
+            let path = entry.path().strip_prefix(&expected)?;
+            if self.skip_cargo_toml && path == Path::new("Cargo.toml") {
+                continue
+            }
+            let existing = self.bindings_root().join(path);
+            eyre::ensure!(existing.is_file(), "Expected {} to exist", existing.display());
+            eyre::ensure!(
+                fs::read(&existing)? == fs::read(entry.path())?,
+                "File contents of {} do not match the expected bindings",
+                existing.display()
+            );
+        }
+        println!("OK.");
+        Ok(())
+    }
+
     /// Generate the bindings
     fn generate_bindings(&self) -> eyre::Result<()> {
+        let (bindings, linked) = self.write_bindings(&self.bindings_root())?;
+        println!("Generated bindings for {} contracts", bindings);
+        if linked > 0 {
+            println!("Generated deployers for {} linked contracts", linked);
+        }
+        Ok(())
+    }
+
+    /// Writes the bindings and the deployers of linked contracts to the given path
+    ///
+    /// Returns the number of contracts and the number of linked contracts
+    fn write_bindings(&self, root: &Path) -> eyre::Result<(usize, usize)> {
         let bindings = self.get_multi()?.build()?;
-        println!("Generating bindings for {} contracts", bindings.len());
+        let contracts = bindings.len();
+        let linking = self.get_linking()?;
         if !self.module {
             bindings.write_to_crate(
                 &self.crate_name,
                 &self.crate_version,
-                self.bindings_root(),
+                root,
                 self.single_file,
             )?;
+            if !linking.is_empty() {
+                linking.write_to_crate(root)?;
+            }
         } else {
-            bindings.write_to_module(self.bindings_root(), self.single_file)?;
+            bindings.write_to_module(root, self.single_file)?;
+            if !linking.is_empty() {
+                linking.write_to_module(root)?;
+            }
         }
-        Ok(())
+        Ok((contracts, linking.len()))
     }
 }
 
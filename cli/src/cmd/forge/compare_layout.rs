@@ -0,0 +1,612 @@
+//! compare-layout command
+
+use crate::{
+    cmd::{
+        forge::build::{self, CoreBuildArgs},
+        Cmd,
+    },
+    compile,
+    opts::forge::CompilerArgs,
+};
+use clap::{Parser, ValueHint};
+use ethers::{
+    prelude::artifacts::{output_selection::ContractOutputSelection, StorageLayout},
+    solc::ProjectCompileOutput,
+    types::U256,
+};
+use eyre::{Context, Result};
+use serde_json::Value;
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+use walkdir::WalkDir;
+use yansi::Paint;
+
+/// The prefix of variables that reserve storage for future versions, like `__gap`
+const GAP_PREFIX: &str = "__gap";
+
+#[derive(Debug, Clone, Parser)]
+pub struct CompareLayoutArgs {
+    #[clap(help = "The name of the old contract.", value_name = "OLD")]
+    pub old: String,
+
+    #[clap(help = "The name of the new contract.", value_name = "NEW")]
+    pub new: String,
+
+    #[clap(
+        long,
+        help = "Read the old contract from this artifacts directory instead of compiling the project.",
+        long_help = "Read the old contract from this artifacts directory instead of compiling the project, e.g. the `out` directory of an older git revision. The artifacts must include the storage layout, see `extra_output`.",
+        value_hint = ValueHint::DirPath,
+        value_name = "PATH"
+    )]
+    pub old_artifacts: Option<PathBuf>,
+
+    #[clap(
+        long,
+        help = "Read the new contract from this artifacts directory instead of compiling the project.",
+        value_hint = ValueHint::DirPath,
+        value_name = "PATH"
+    )]
+    pub new_artifacts: Option<PathBuf>,
+
+    /// All build arguments are supported
+    #[clap(flatten)]
+    build: build::CoreBuildArgs,
+}
+
+impl Cmd for CompareLayoutArgs {
+    type Output = ();
+
+    fn run(self) -> Result<Self::Output> {
+        let CompareLayoutArgs { old, new, old_artifacts, new_artifacts, build } = self;
+
+        // only compile the project if a contract is not read from an artifacts directory
+        let outcome = if old_artifacts.is_none() || new_artifacts.is_none() {
+            let mut extra_output = build.compiler.extra_output.clone();
+            if !extra_output.contains(&ContractOutputSelection::StorageLayout) {
+                extra_output.push(ContractOutputSelection::StorageLayout);
+            }
+            let build = CoreBuildArgs {
+                compiler: CompilerArgs { extra_output, ..build.compiler },
+                ..build
+            };
+            Some(compile::suppress_compile(&build.project()?)?)
+        } else {
+            None
+        };
+
+        let old_layout = storage_layout(&old, old_artifacts.as_deref(), outcome.as_ref())?;
+        let new_layout = storage_layout(&new, new_artifacts.as_deref(), outcome.as_ref())?;
+
+        let comparison = compare_layouts(&old_layout, &new_layout)?;
+        for issue in &comparison.issues {
+            println!("{} {}", Paint::red("✗"), issue);
+        }
+        for added in &comparison.added {
+            println!("{} added `{}`", Paint::green("+"), added);
+        }
+
+        if comparison.is_compatible() {
+            println!("The storage layout of `{new}` is compatible with `{old}`");
+            Ok(())
+        } else {
+            eprintln!(
+                "{}",
+                Paint::red(format!("The storage layout of `{new}` is incompatible with `{old}`"))
+            );
+            std::process::exit(1)
+        }
+    }
+}
+
+/// Returns the storage layout of the contract, either from the artifacts directory or from the
+/// compiled project
+fn storage_layout(
+    contract: &str,
+    artifacts: Option<&Path>,
+    outcome: Option<&ProjectCompileOutput>,
+) -> Result<StorageLayout> {
+    let layout = match (artifacts, outcome) {
+        (Some(artifacts), _) => read_storage_layout(contract, artifacts)?,
+        (None, Some(outcome)) => outcome
+            .find(contract)
+            .ok_or_else(|| {
+                eyre::eyre!("Could not find artifact `{contract}` in the compiled artifacts")
+            })?
+            .storage_layout
+            .clone(),
+        (None, None) => None,
+    };
+    layout.ok_or_else(|| eyre::eyre!("No storage layout for `{contract}`"))
+}
+
+/// Reads the storage layout of the contract from its artifact in the directory
+///
+/// The contract is either a name or an identifier like `src/Token.sol:Token`.
+fn read_storage_layout(contract: &str, artifacts: &Path) -> Result<Option<StorageLayout>> {
+    let (file, name) = match contract.rsplit_once(':') {
+        Some((path, name)) => (Path::new(path).file_name(), name),
+        None => (None, contract),
+    };
+    let artifact_name = format!("{name}.json");
+
+    let candidates = WalkDir::new(artifacts)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.into_path())
+        .filter(|path| path.file_name().map_or(false, |f| f == artifact_name.as_str()))
+        .filter(|path| {
+            file.map_or(true, |file| path.parent().and_then(Path::file_name) == Some(file))
+        })
+        .collect::<Vec<_>>();
+
+    let path = match candidates.as_slice() {
+        [path] => path,
+        [] => eyre::bail!("Could not find artifact `{contract}` in {}", artifacts.display()),
+        paths => eyre::bail!(
+            "Multiple artifacts named `{contract}` in {}, use `<path>:{name}` instead:\n{}",
+            artifacts.display(),
+            paths.iter().map(|path| path.display().to_string()).collect::<Vec<_>>().join("\n")
+        ),
+    };
+
+    let artifact: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path)?)
+        .wrap_err_with(|| format!("Failed to read artifact {}", path.display()))?;
+    artifact
+        .get("storageLayout")
+        .filter(|layout| !layout.is_null())
+        .map(|layout| serde_json::from_value(layout.clone()))
+        .transpose()
+        .wrap_err_with(|| format!("Failed to read storage layout of {}", path.display()))
+}
+
+/// An incompatible change of the storage layout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutIssue {
+    /// A variable was removed
+    Removed { label: String, ty: String },
+    /// The type of a variable changed
+    Retyped { label: String, old: String, new: String },
+    /// The members of a struct, or of a struct nested in the variable, were reordered or changed
+    MembersChanged { label: String, ty: String },
+    /// A variable occupies fewer bytes than before
+    Shrunk { label: String, old: U256, new: U256 },
+    /// A variable moved to another slot or offset, e.g. because it was reordered
+    Moved { label: String, old: (U256, u64), new: (U256, u64) },
+    /// A new variable occupies storage of the old layout
+    Inserted { label: String, slot: U256, offset: u64 },
+    /// A gap no longer ends at the same slot
+    GapResized { label: String, old_end: U256, new_end: U256 },
+}
+
+impl fmt::Display for LayoutIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutIssue::Removed { label, ty } => write!(f, "removed `{label}` ({ty})"),
+            LayoutIssue::Retyped { label, old, new } => {
+                write!(f, "changed the type of `{label}` from {old} to {new}")
+            }
+            LayoutIssue::MembersChanged { label, ty } => {
+                write!(f, "changed the members of {ty} of `{label}`")
+            }
+            LayoutIssue::Shrunk { label, old, new } => {
+                write!(f, "shrunk `{label}` from {old} to {new} bytes")
+            }
+            LayoutIssue::Moved { label, old, new } => write!(
+                f,
+                "moved `{label}` from slot {} offset {} to slot {} offset {}",
+                old.0, old.1, new.0, new.1
+            ),
+            LayoutIssue::Inserted { label, slot, offset } => {
+                write!(f, "inserted `{label}` at used slot {slot} offset {offset}")
+            }
+            LayoutIssue::GapResized { label, old_end, new_end } => {
+                write!(f, "resized gap `{label}` to end at slot {new_end} instead of {old_end}")
+            }
+        }
+    }
+}
+
+/// The result of comparing an old storage layout with a new one
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LayoutComparison {
+    /// All incompatible changes
+    pub issues: Vec<LayoutIssue>,
+    /// Variables that were added in unused storage
+    pub added: Vec<String>,
+}
+
+impl LayoutComparison {
+    /// Whether the new layout can be used by an upgrade of a contract with the old layout
+    pub fn is_compatible(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// A state variable of a storage layout
+#[derive(Debug, Clone)]
+struct Variable {
+    label: String,
+    /// The number of preceding variables with the same label, as the gaps of multiple base
+    /// contracts are usually named alike
+    occurrence: usize,
+    slot: U256,
+    offset: u64,
+    ty: String,
+    /// How the value is laid out in storage, see [type_layout]
+    layout: String,
+    bytes: U256,
+}
+
+impl Variable {
+    /// Identifies the variable across layouts
+    fn key(&self) -> (&str, usize) {
+        (&self.label, self.occurrence)
+    }
+
+    fn is_gap(&self) -> bool {
+        self.label.starts_with(GAP_PREFIX)
+    }
+
+    /// The first slot after the variable
+    fn end(&self) -> U256 {
+        self.slot + (U256::from(self.offset) + self.bytes + 31) / 32
+    }
+
+    /// The position of the first byte of the variable in storage
+    fn start_byte(&self) -> U256 {
+        self.slot * 32 + self.offset
+    }
+
+    /// Whether the variables share any bytes of storage
+    fn overlaps(&self, other: &Variable) -> bool {
+        self.start_byte() < other.start_byte() + other.bytes &&
+            other.start_byte() < self.start_byte() + self.bytes
+    }
+}
+
+/// Returns the variables of the layout in storage order
+fn variables(layout: &StorageLayout) -> Result<Vec<Variable>> {
+    let types = serde_json::to_value(&layout.types)?;
+    let mut variables: Vec<Variable> = Vec::with_capacity(layout.storage.len());
+    for storage in &layout.storage {
+        let ty = layout
+            .types
+            .get(&storage.storage_type)
+            .ok_or_else(|| eyre::eyre!("Unknown storage type `{}`", storage.storage_type))?;
+        variables.push(Variable {
+            label: storage.label.clone(),
+            occurrence: variables.iter().filter(|var| var.label == storage.label).count(),
+            slot: U256::from_dec_str(&storage.slot)?,
+            offset: storage.offset as u64,
+            ty: ty.label.clone(),
+            layout: type_layout(&types, &storage.storage_type, &mut Vec::new())?,
+            bytes: U256::from_dec_str(&ty.number_of_bytes)?,
+        });
+    }
+    Ok(variables)
+}
+
+/// Describes how a value of the type is laid out in storage, two types with the same layout can
+/// read each other's values
+///
+/// Structs are described by the slot, offset and layout of their members rather than by their
+/// name, and contracts are stored like addresses.
+fn type_layout(types: &Value, id: &str, parents: &mut Vec<String>) -> Result<String> {
+    let ty = types.get(id).ok_or_else(|| eyre::eyre!("Unknown storage type `{id}`"))?;
+    let field = |name: &str| ty.get(name).and_then(Value::as_str);
+    let label = field("label").unwrap_or(id);
+    let nested = |name: &str, parents: &mut Vec<String>| {
+        let nested = field(name).ok_or_else(|| eyre::eyre!("No `{name}` of `{label}`"))?;
+        type_layout(types, nested, parents)
+    };
+
+    // a recursive struct can only refer to itself via a mapping or dynamic array
+    if parents.iter().any(|parent| parent == id) {
+        return Ok(label.to_string())
+    }
+    parents.push(id.to_string());
+
+    let layout = match field("encoding") {
+        Some("mapping") => {
+            format!("mapping({} => {})", nested("key", parents)?, nested("value", parents)?)
+        }
+        Some("dynamic_array") => format!("{}[]", nested("base", parents)?),
+        _ => {
+            if let Some(members) = ty.get("members").and_then(Value::as_array) {
+                let mut layouts = Vec::with_capacity(members.len());
+                for member in members {
+                    let member_field = |name: &str| {
+                        member
+                            .get(name)
+                            .map(|value| value.to_string())
+                            .ok_or_else(|| eyre::eyre!("No `{name}` of a member of `{label}`"))
+                    };
+                    let member_ty = member.get("type").and_then(Value::as_str).unwrap_or_default();
+                    layouts.push(format!(
+                        "{}:{}:{}",
+                        member_field("slot")?,
+                        member_field("offset")?,
+                        type_layout(types, member_ty, parents)?
+                    ));
+                }
+                format!("struct({})", layouts.join(", "))
+            } else if field("base").is_some() {
+                // keep the length of the static array
+                let len = label.rsplit_once('[').map_or("]", |(_, len)| len);
+                format!("{}[{len}", nested("base", parents)?)
+            } else if label.starts_with("contract ") {
+                "address".to_string()
+            } else {
+                label.to_string()
+            }
+        }
+    };
+
+    parents.pop();
+    Ok(layout)
+}
+
+/// Compares the storage layout of an upgrade with the layout of the old version of the contract
+pub fn compare_layouts(old: &StorageLayout, new: &StorageLayout) -> Result<LayoutComparison> {
+    let old = variables(old)?;
+    let new = variables(new)?;
+    let mut comparison = LayoutComparison::default();
+
+    for old_var in &old {
+        let new_var = match new.iter().find(|new_var| new_var.key() == old_var.key()) {
+            Some(new_var) => new_var,
+            // the storage of a removed gap may be used by new variables
+            None if old_var.is_gap() => continue,
+            None => {
+                comparison.issues.push(LayoutIssue::Removed {
+                    label: old_var.label.clone(),
+                    ty: old_var.ty.clone(),
+                });
+                continue
+            }
+        };
+
+        if old_var.is_gap() {
+            // a gap may shrink to make room for new variables, but must end at the same slot
+            if old_var.end() != new_var.end() {
+                comparison.issues.push(LayoutIssue::GapResized {
+                    label: old_var.label.clone(),
+                    old_end: old_var.end(),
+                    new_end: new_var.end(),
+                });
+            }
+            continue
+        }
+
+        if old_var.layout != new_var.layout && old_var.ty != new_var.ty {
+            comparison.issues.push(LayoutIssue::Retyped {
+                label: old_var.label.clone(),
+                old: old_var.ty.clone(),
+                new: new_var.ty.clone(),
+            });
+        } else if new_var.bytes < old_var.bytes {
+            comparison.issues.push(LayoutIssue::Shrunk {
+                label: old_var.label.clone(),
+                old: old_var.bytes,
+                new: new_var.bytes,
+            });
+        } else if old_var.layout != new_var.layout {
+            comparison.issues.push(LayoutIssue::MembersChanged {
+                label: old_var.label.clone(),
+                ty: old_var.ty.clone(),
+            });
+        }
+        if (old_var.slot, old_var.offset) != (new_var.slot, new_var.offset) {
+            comparison.issues.push(LayoutIssue::Moved {
+                label: old_var.label.clone(),
+                old: (old_var.slot, old_var.offset),
+                new: (new_var.slot, new_var.offset),
+            });
+        }
+    }
+
+    // new variables may only use storage that was unused or reserved by a gap
+    for new_var in &new {
+        if old.iter().any(|old_var| old_var.key() == new_var.key()) {
+            continue
+        }
+        if !old.iter().any(|old_var| !old_var.is_gap() && old_var.overlaps(new_var)) {
+            comparison.added.push(new_var.label.clone());
+        } else {
+            comparison.issues.push(LayoutIssue::Inserted {
+                label: new_var.label.clone(),
+                slot: new_var.slot,
+                offset: new_var.offset,
+            });
+        }
+    }
+
+    Ok(comparison)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a layout with the given `(label, slot, offset, type)` variables
+    fn layout(variables: &[(&str, u64, u64, &str)]) -> StorageLayout {
+        let storage = variables
+            .iter()
+            .enumerate()
+            .map(|(idx, (label, slot, offset, ty))| {
+                serde_json::json!({
+                    "astId": idx,
+                    "contract": "src/Token.sol:Token",
+                    "label": label,
+                    "offset": offset,
+                    "slot": slot.to_string(),
+                    "type": ty,
+                })
+            })
+            .collect::<Vec<_>>();
+        serde_json::from_value(serde_json::json!({
+            "storage": storage,
+            "types": {
+                "t_address": {"encoding": "inplace", "label": "address", "numberOfBytes": "20"},
+                "t_bool": {"encoding": "inplace", "label": "bool", "numberOfBytes": "1"},
+                "t_uint128": {"encoding": "inplace", "label": "uint128", "numberOfBytes": "16"},
+                "t_uint256": {"encoding": "inplace", "label": "uint256", "numberOfBytes": "32"},
+                "t_array(t_uint256)3_storage": {"base": "t_uint256", "encoding": "inplace", "label": "uint256[3]", "numberOfBytes": "96"},
+                "t_array(t_uint256)4_storage": {"base": "t_uint256", "encoding": "inplace", "label": "uint256[4]", "numberOfBytes": "128"},
+                "t_struct(Config)1_storage": {"encoding": "inplace", "label": "struct Token.Config", "numberOfBytes": "64"},
+                "t_struct(Config)2_storage": {"encoding": "inplace", "label": "struct Token.Config", "numberOfBytes": "32"},
+                "t_contract(Token)5": {"encoding": "inplace", "label": "contract Token", "numberOfBytes": "20"},
+                "t_struct(Config)3_storage": {"encoding": "inplace", "label": "struct Token.Config", "numberOfBytes": "64", "members": [
+                    {"astId": 10, "contract": "src/Token.sol:Token", "label": "fee", "offset": 0, "slot": "0", "type": "t_uint256"},
+                    {"astId": 11, "contract": "src/Token.sol:Token", "label": "admin", "offset": 0, "slot": "1", "type": "t_address"}
+                ]},
+                "t_struct(Config)4_storage": {"encoding": "inplace", "label": "struct Token.Config", "numberOfBytes": "64", "members": [
+                    {"astId": 12, "contract": "src/Token.sol:Token", "label": "admin", "offset": 0, "slot": "0", "type": "t_address"},
+                    {"astId": 13, "contract": "src/Token.sol:Token", "label": "fee", "offset": 0, "slot": "1", "type": "t_uint256"}
+                ]},
+                "t_struct(Settings)6_storage": {"encoding": "inplace", "label": "struct Token.Settings", "numberOfBytes": "64", "members": [
+                    {"astId": 14, "contract": "src/Token.sol:Token", "label": "fee", "offset": 0, "slot": "0", "type": "t_uint256"},
+                    {"astId": 15, "contract": "src/Token.sol:Token", "label": "admin", "offset": 0, "slot": "1", "type": "t_contract(Token)5"}
+                ]},
+                "t_mapping(t_address,t_struct(Config)3_storage)": {"encoding": "mapping", "key": "t_address", "label": "mapping(address => struct Token.Config)", "numberOfBytes": "32", "value": "t_struct(Config)3_storage"},
+                "t_mapping(t_address,t_struct(Config)4_storage)": {"encoding": "mapping", "key": "t_address", "label": "mapping(address => struct Token.Config)", "numberOfBytes": "32", "value": "t_struct(Config)4_storage"},
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn accepts_compatible_layouts() {
+        let old = layout(&[
+            ("owner", 0, 0, "t_address"),
+            ("supply", 1, 0, "t_uint256"),
+            ("__gap", 2, 0, "t_array(t_uint256)4_storage"),
+        ]);
+        // `paused` is packed after `owner`, `fee` uses a slot of the gap, `cap` is appended
+        let new = layout(&[
+            ("owner", 0, 0, "t_address"),
+            ("paused", 0, 20, "t_bool"),
+            ("supply", 1, 0, "t_uint256"),
+            ("fee", 2, 0, "t_uint256"),
+            ("__gap", 3, 0, "t_array(t_uint256)3_storage"),
+            ("cap", 6, 0, "t_uint256"),
+        ]);
+
+        let comparison = compare_layouts(&old, &new).unwrap();
+        assert!(comparison.is_compatible(), "{:?}", comparison.issues);
+        assert_eq!(comparison.added, vec!["paused", "fee", "cap"]);
+    }
+
+    #[test]
+    fn flags_incompatible_layouts() {
+        let old = layout(&[
+            ("owner", 0, 0, "t_address"),
+            ("supply", 1, 0, "t_uint256"),
+            ("limit", 2, 0, "t_uint256"),
+            ("admin", 3, 0, "t_address"),
+            ("__gap", 4, 0, "t_array(t_uint256)4_storage"),
+        ]);
+        // `supply` and `limit` are swapped, `admin` is removed and `fee` takes its slot, `owner`
+        // is retyped and the gap is not shrunk for `cap`
+        let new = layout(&[
+            ("owner", 0, 0, "t_uint128"),
+            ("limit", 1, 0, "t_uint256"),
+            ("supply", 2, 0, "t_uint256"),
+            ("fee", 3, 0, "t_uint256"),
+            ("cap", 4, 0, "t_uint256"),
+            ("__gap", 5, 0, "t_array(t_uint256)4_storage"),
+        ]);
+
+        let comparison = compare_layouts(&old, &new).unwrap();
+        assert!(!comparison.is_compatible());
+        assert_eq!(
+            comparison.issues,
+            vec![
+                LayoutIssue::Retyped {
+                    label: "owner".to_string(),
+                    old: "address".to_string(),
+                    new: "uint128".to_string()
+                },
+                LayoutIssue::Moved {
+                    label: "supply".to_string(),
+                    old: (1.into(), 0),
+                    new: (2.into(), 0)
+                },
+                LayoutIssue::Moved {
+                    label: "limit".to_string(),
+                    old: (2.into(), 0),
+                    new: (1.into(), 0)
+                },
+                LayoutIssue::Removed { label: "admin".to_string(), ty: "address".to_string() },
+                LayoutIssue::GapResized {
+                    label: "__gap".to_string(),
+                    old_end: 8.into(),
+                    new_end: 9.into()
+                },
+                LayoutIssue::Inserted { label: "fee".to_string(), slot: 3.into(), offset: 0 },
+            ]
+        );
+        // `cap` uses a slot of the old gap
+        assert_eq!(comparison.added, vec!["cap"]);
+    }
+
+    #[test]
+    fn flags_shrunk_variables() {
+        let old = layout(&[("config", 0, 0, "t_struct(Config)1_storage")]);
+        let new = layout(&[("config", 0, 0, "t_struct(Config)2_storage")]);
+
+        let comparison = compare_layouts(&old, &new).unwrap();
+        assert_eq!(
+            comparison.issues,
+            vec![LayoutIssue::Shrunk {
+                label: "config".to_string(),
+                old: 64.into(),
+                new: 32.into()
+            }]
+        );
+    }
+
+    #[test]
+    fn flags_reordered_struct_members() {
+        let old = layout(&[
+            ("config", 0, 0, "t_struct(Config)3_storage"),
+            ("configs", 2, 0, "t_mapping(t_address,t_struct(Config)3_storage)"),
+        ]);
+        let new = layout(&[
+            ("config", 0, 0, "t_struct(Config)4_storage"),
+            ("configs", 2, 0, "t_mapping(t_address,t_struct(Config)4_storage)"),
+        ]);
+
+        let comparison = compare_layouts(&old, &new).unwrap();
+        assert_eq!(
+            comparison.issues,
+            vec![
+                LayoutIssue::MembersChanged {
+                    label: "config".to_string(),
+                    ty: "struct Token.Config".to_string()
+                },
+                LayoutIssue::MembersChanged {
+                    label: "configs".to_string(),
+                    ty: "mapping(address => struct Token.Config)".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn accepts_contracts_as_addresses() {
+        // a renamed struct with a member retyped from `address` to a contract
+        let old =
+            layout(&[("owner", 0, 0, "t_address"), ("config", 1, 0, "t_struct(Config)3_storage")]);
+        let new = layout(&[
+            ("owner", 0, 0, "t_contract(Token)5"),
+            ("config", 1, 0, "t_struct(Settings)6_storage"),
+        ]);
+
+        let comparison = compare_layouts(&old, &new).unwrap();
+        assert!(comparison.is_compatible(), "{:?}", comparison.issues);
+    }
+}
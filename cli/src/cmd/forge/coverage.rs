@@ -0,0 +1,203 @@
+//! Coverage command
+use crate::{
+    cmd::{
+        forge::{build::CoreBuildArgs, test::Filter},
+        Cmd,
+    },
+    compile::ProjectCompiler,
+    utils::{self, FoundryPathExt},
+};
+use clap::{AppSettings, Parser, ValueHint};
+use ethers::{
+    prelude::artifacts::CompactContractBytecode,
+    solc::{sourcemap, Artifact},
+};
+use forge::{
+    coverage::{CoverageReport, HitMaps},
+    executor::opts::EvmOpts,
+    fuzz::invariant::InvariantConfig,
+    MultiContractRunnerBuilder,
+};
+use foundry_common::evm::EvmArgs;
+use foundry_config::{figment::Figment, Config};
+use std::{fs, path::PathBuf, str::FromStr};
+
+// Loads project's figment and merges the build cli arguments into it
+foundry_config::impl_figment_convert!(CoverageArgs, opts, evm_opts);
+
+/// Generate coverage reports for your tests.
+#[derive(Debug, Clone, Parser)]
+#[clap(global_setting = AppSettings::DeriveDisplayOrder)]
+pub struct CoverageArgs {
+    /// The report type to use for coverage.
+    ///
+    /// Valid values: summary, lcov. This flag can be used multiple times.
+    #[clap(long, default_value = "summary", value_name = "TYPE", multiple_occurrences = true)]
+    report: Vec<CoverageReportKind>,
+
+    /// The path of the LCOV report.
+    #[clap(
+        long,
+        default_value = "lcov.info",
+        value_hint = ValueHint::FilePath,
+        value_name = "PATH"
+    )]
+    report_file: PathBuf,
+
+    #[clap(flatten)]
+    filter: Filter,
+
+    #[clap(flatten, next_help_heading = "EVM OPTIONS")]
+    evm_opts: EvmArgs,
+
+    #[clap(flatten, next_help_heading = "BUILD OPTIONS")]
+    opts: CoreBuildArgs,
+}
+
+impl CoverageArgs {
+    /// Returns the currently configured [Config] and the extracted [EvmOpts] from that config
+    pub fn config_and_evm_opts(&self) -> eyre::Result<(Config, EvmOpts)> {
+        let figment: Figment = self.into();
+        let evm_opts = figment.extract()?;
+        let config = Config::from_provider(figment).sanitized();
+        Ok((config, evm_opts))
+    }
+}
+
+impl Cmd for CoverageArgs {
+    type Output = ();
+
+    fn run(self) -> eyre::Result<Self::Output> {
+        let (mut config, evm_opts) = self.config_and_evm_opts()?;
+
+        // The optimizer reorders and merges code, which makes the source maps a lot less accurate
+        config.optimizer = false;
+
+        // Compile all files without using the cache, so that we get the source files of all
+        // artifacts
+        let project = config.ephemeral_no_artifacts_project()?;
+        let output = ProjectCompiler::default().compile(&project)?;
+
+        // Run all tests with coverage collection enabled
+        let cfg = proptest::test_runner::Config {
+            failure_persistence: None,
+            cases: config.fuzz_runs,
+            max_local_rejects: config.fuzz_max_local_rejects,
+            max_global_rejects: config.fuzz_max_global_rejects,
+            ..Default::default()
+        };
+        let mut runner = MultiContractRunnerBuilder::default()
+            .fuzzer(proptest::test_runner::TestRunner::new(cfg))
+            .initial_balance(evm_opts.initial_balance)
+            .evm_spec(utils::evm_spec(&config.evm_version))
+            .sender(evm_opts.sender)
+            .with_fork(utils::get_fork(&evm_opts, &config.rpc_storage_caching))
+            .invariant_config(InvariantConfig {
+                depth: config.invariant_depth,
+                fail_on_revert: config.invariant_fail_on_revert,
+            })
+            .set_coverage(true)
+            .fuzz_configs(config.fuzz.clone())
+            .build(&project.paths.root, output.clone(), evm_opts)?;
+
+        let filter = self.filter.with_merged_config();
+        let results = runner.test(&filter, None, true)?;
+
+        let mut maps = HitMaps::default();
+        let mut failed = 0;
+        for suite in results.values() {
+            // Setup and invariant campaigns are shared by the tests of a suite and only counted
+            // once
+            if let Some(coverage) = suite.coverage.clone() {
+                maps.merge(coverage);
+            }
+            for result in suite.test_results.values() {
+                if !result.success {
+                    failed += 1;
+                }
+                if let Some(coverage) = result.coverage.clone() {
+                    maps.merge(coverage);
+                }
+            }
+        }
+        if failed > 0 {
+            eprintln!(
+                "Warning: {failed} test(s) failed, the coverage report may be incomplete.\n\
+                 Run `forge test` for more details."
+            );
+        }
+
+        // Build the report for all project sources, excluding tests and libraries
+        let (artifacts, sources) =
+            output.with_stripped_file_prefixes(&project.paths.root).into_artifacts_with_sources();
+
+        let mut report = CoverageReport::default();
+        for (id, path) in sources.into_ids() {
+            let file = project.root().join(&path);
+            if file.is_sol_test() ||
+                project.paths.libraries.iter().any(|lib| file.starts_with(lib))
+            {
+                continue
+            }
+            report.add_source(id, path, &fs::read_to_string(&file)?);
+        }
+
+        for (id, artifact) in artifacts {
+            let contract: CompactContractBytecode = artifact.into_contract_bytecode();
+
+            if let Some(bytecode) = contract.bytecode {
+                let source_map = bytecode.source_map.as_deref().map(sourcemap::parse);
+                if let (Some(code), Some(Ok(source_map))) = (bytecode.object.as_bytes(), source_map)
+                {
+                    report.add_bytecode(code, &source_map, &maps);
+                }
+            }
+
+            // Use the linked runtime code of the runner if there is one
+            if let Some(bytecode) = contract.deployed_bytecode.and_then(|code| code.bytecode) {
+                let code = runner
+                    .known_contracts
+                    .get(&id)
+                    .map(|(_, code)| code.clone())
+                    .or_else(|| bytecode.object.as_bytes().map(|code| code.to_vec()));
+                let source_map = bytecode.source_map.as_deref().map(sourcemap::parse);
+                if let (Some(code), Some(Ok(source_map))) = (code, source_map) {
+                    report.add_bytecode(&code, &source_map, &maps);
+                }
+            }
+        }
+
+        for kind in self.report {
+            match kind {
+                CoverageReportKind::Summary => println!("{}", report),
+                CoverageReportKind::Lcov => {
+                    report.write_lcov(fs::File::create(&self.report_file)?)?;
+                    println!("Wrote LCOV report to {}", self.report_file.display());
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The kinds of coverage reports
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum CoverageReportKind {
+    /// A table of the line and branch coverage of each file
+    Summary,
+    /// An LCOV tracefile
+    Lcov,
+}
+
+impl FromStr for CoverageReportKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "summary" => Ok(CoverageReportKind::Summary),
+            "lcov" => Ok(CoverageReportKind::Lcov),
+            _ => Err(format!("Unrecognized report type `{s}`")),
+        }
+    }
+}
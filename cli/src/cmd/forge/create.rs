@@ -4,21 +4,23 @@ use crate::{
     cmd::{forge::build::CoreBuildArgs, Cmd, RetryArgs},
     compile,
     opts::{forge::ContractInfo, EthereumOpts, WalletType},
-    utils::{parse_ether_value, parse_u256},
+    utils::{parse_ether_value, parse_u256, DEFAULT_CREATE2_DEPLOYER},
 };
+use cast::SimpleCast;
 use clap::{Parser, ValueHint};
 use ethers::{
     abi::{Abi, Constructor, Token},
     prelude::{artifacts::BytecodeObject, ContractFactory, Http, Middleware, Provider},
     solc::utils::RuntimeOrHandle,
-    types::{transaction::eip2718::TypedTransaction, Chain, U256},
+    types::{transaction::eip2718::TypedTransaction, Address, Chain, H256, U256},
+    utils::keccak256,
 };
 use eyre::{Context, Result};
 use foundry_config::Config;
 use foundry_utils::parse_tokens;
 use rustc_hex::ToHex;
 use serde_json::json;
-use std::{fs, path::PathBuf, sync::Arc};
+use std::{fs, path::PathBuf, str::FromStr, sync::Arc};
 
 pub const RETRY_VERIFY_ON_CREATE: RetryArgs = RetryArgs { retries: 15, delay: Some(3) };
 
@@ -100,6 +102,17 @@ Examples: 1ether, 10gwei, 0.01ether"#,
     )]
     value: Option<U256>,
 
+    #[clap(
+        long,
+        help_heading = "TRANSACTION OPTIONS",
+        help = "Deploy the contract with CREATE2 through the deterministic deployment proxy, using the given salt.",
+        long_help = r#"Deploy the contract with CREATE2 through the deterministic deployment proxy, using the given salt.
+
+The address of the contract only depends on the salt and the init code, so the contract is deployed to the same address on every chain the proxy is deployed to. See https://github.com/Arachnid/deterministic-deployment-proxy"#,
+        value_name = "SALT"
+    )]
+    salt: Option<H256>,
+
     #[clap(flatten, next_help_heading = "BUILD OPTIONS")]
     opts: CoreBuildArgs,
 
@@ -218,6 +231,29 @@ impl CreateArgs {
             self.legacy || Chain::try_from(chain).map(|x| Chain::is_legacy(&x)).unwrap_or_default();
         let mut deployer = if is_legacy { deployer.legacy() } else { deployer };
 
+        // the deterministic deployment proxy expects the salt followed by the init code
+        let create2_address = match self.salt {
+            Some(salt) => {
+                let create2_deployer = Address::from_str(DEFAULT_CREATE2_DEPLOYER)?;
+                if provider.get_code(create2_deployer, None).await?.as_ref().is_empty() {
+                    eyre::bail!(
+                        "The deterministic deployment proxy is not deployed at {:?} on chain {}",
+                        create2_deployer,
+                        chain
+                    )
+                }
+                let init_code = deployer.tx.data().cloned().unwrap_or_default();
+                deployer.tx.set_to(create2_deployer);
+                deployer.tx.set_data([salt.as_bytes(), init_code.as_ref()].concat().into());
+                Some(SimpleCast::compute_create2_address(
+                    create2_deployer,
+                    salt,
+                    H256(keccak256(init_code)),
+                ))
+            }
+            None => None,
+        };
+
         // fill tx first because if you target a lower gas than current base, eth_estimateGas
         // will fail and create will fail
         let mut tx = deployer.tx;
@@ -252,8 +288,27 @@ impl CreateArgs {
             deployer.tx.set_value(value);
         }
 
-        let (deployed_contract, receipt) = deployer.send_with_receipt().await?;
-        let address = deployed_contract.address();
+        let (address, receipt) = match create2_address {
+            Some(address) => {
+                let receipt = provider
+                    .send_transaction(deployer.tx, None)
+                    .await?
+                    .await?
+                    .ok_or_else(|| eyre::eyre!("Deployment transaction was dropped"))?;
+                // the proxy reverts if the deployment fails, e.g. if the address is already taken
+                if receipt.status != Some(1u64.into()) {
+                    eyre::bail!(
+                        "Deterministic deployment to {:?} failed, the contract may already be deployed",
+                        address
+                    )
+                }
+                (address, receipt)
+            }
+            None => {
+                let (deployed_contract, receipt) = deployer.send_with_receipt().await?;
+                (deployed_contract.address(), receipt)
+            }
+        };
         if self.json {
             let output = json!({
                 "deployer": deployer_address,
@@ -7,12 +7,13 @@ use std::{
 
 use clap::Parser;
 use console::{style, Style};
-use ethers::solc::ProjectPathsConfig;
+use foundry_config::{find_project_root_path, Config};
 
 use rayon::prelude::*;
 use similar::{ChangeTag, TextDiff};
+use solang_parser::pt::SourceUnit;
 
-use forge_fmt::{Formatter, FormatterConfig, Visitable};
+use forge_fmt::{solang_ext::AstEq, Formatter, Visitable};
 
 use crate::cmd::Cmd;
 
@@ -23,7 +24,10 @@ pub struct FmtArgs {
         conflicts_with = "root"
     )]
     path: Option<PathBuf>,
-    #[clap(help = "project's root path, default being the current working directory", long)]
+    #[clap(
+        help = "project's root path, default being the current working directory. All files in the `src` and `test` directories of the project are formatted",
+        long
+    )]
     root: Option<PathBuf>,
     #[clap(
         help = "run in 'check' mode. Exits with 0 if input is formatted correctly. Exits with 1 if formatting is required.",
@@ -63,33 +67,73 @@ impl std::fmt::Display for Line {
     }
 }
 
+/// Parses the formatted output and makes sure the formatter didn't change the semantics of the
+/// code
+fn ensure_equivalent(
+    input: &impl Display,
+    source_unit: &SourceUnit,
+    formatted: &str,
+) -> eyre::Result<()> {
+    let (formatted_source_unit, _comments) = solang_parser::parse(formatted, 0).map_err(|diags| {
+        eyre::eyre!(
+            "Failed to construct valid Solidity code for {}. Leaving source unchanged.\nDebug info: {:?}",
+            input,
+            diags
+        )
+    })?;
+
+    if !source_unit.ast_eq(&formatted_source_unit) {
+        eyre::bail!(
+            "Failed to construct equivalent Solidity code for {}. Leaving source unchanged.",
+            input
+        )
+    }
+
+    Ok(())
+}
+
 impl Cmd for FmtArgs {
     type Output = ();
 
     fn run(self) -> eyre::Result<Self::Output> {
-        let root = if let Some(path) = self.path {
-            path
-        } else {
-            let root = self.root.unwrap_or_else(|| {
-                std::env::current_dir().expect("failed to get current directory")
-            });
-            if !root.is_dir() {
-                return Err(eyre::eyre!("Root path should be a directory"))
+        let root = self.root.map(Ok).unwrap_or_else(find_project_root_path)?;
+        let config = Config::load_with_root(&root).sanitized();
+
+        let inputs = match self.path {
+            Some(path) if path == PathBuf::from("-") => {
+                let mut buf = String::new();
+                io::stdin().read_to_string(&mut buf)?;
+                vec![Input::Stdin(buf)]
+            }
+            Some(path) if path.is_dir() => {
+                ethers::solc::utils::source_files(path).into_iter().map(Input::Path).collect()
+            }
+            Some(path) if path.file_name().unwrap().to_string_lossy().ends_with(".sol") => {
+                vec![Input::Path(path)]
             }
+            Some(_) => vec![],
+            // read from stdin if something is piped in
+            None if !atty::is(atty::Stream::Stdin) => {
+                let mut buf = String::new();
+                io::stdin().read_to_string(&mut buf)?;
+                vec![Input::Stdin(buf)]
+            }
+            None => {
+                if !root.is_dir() {
+                    return Err(eyre::eyre!("Root path should be a directory"))
+                }
 
-            ProjectPathsConfig::find_source_dir(&root)
-        };
+                let mut paths = [&config.src, &config.test]
+                    .into_iter()
+                    .filter(|dir| dir.is_dir())
+                    .flat_map(ethers::solc::utils::source_files)
+                    .collect::<Vec<_>>();
+                // the test directory can be nested in the source directory
+                paths.sort();
+                paths.dedup();
 
-        let inputs = if root == PathBuf::from("-") || !atty::is(atty::Stream::Stdin) {
-            let mut buf = String::new();
-            io::stdin().read_to_string(&mut buf)?;
-            vec![Input::Stdin(buf)]
-        } else if root.is_dir() {
-            ethers::solc::utils::source_files(root).into_iter().map(Input::Path).collect()
-        } else if root.file_name().unwrap().to_string_lossy().ends_with(".sol") {
-            vec![Input::Path(root)]
-        } else {
-            vec![]
+                paths.into_iter().map(Input::Path).collect()
+            }
         };
 
         let diffs = inputs
@@ -109,18 +153,11 @@ impl Cmd for FmtArgs {
                         ))?;
 
                 let mut output = String::new();
-                let mut formatter =
-                    Formatter::new(&mut output, &source, FormatterConfig::default());
+                let mut formatter = Formatter::new(&mut output, &source, config.fmt.clone());
 
                 source_unit.visit(&mut formatter).unwrap();
 
-                solang_parser::parse(&output, 0).map_err(|diags| {
-                    eyre::eyre!(
-                            "Failed to construct valid Solidity code for {}. Leaving source unchanged.\nDebug info: {:?}",
-                            input,
-                            diags
-                        )
-                })?;
+                ensure_equivalent(input, &source_unit, &output)?;
 
                 if self.check || matches!(input, Input::Stdin(_)) {
                     if self.raw {
@@ -194,3 +231,25 @@ impl Cmd for FmtArgs {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_non_equivalent_output() {
+        let source = "contract A { uint256 a; }";
+        let (source_unit, _comments) = solang_parser::parse(source, 0).unwrap();
+
+        assert!(
+            ensure_equivalent(&"A.sol", &source_unit, "contract A {\n    uint256 a;\n}\n").is_ok()
+        );
+
+        let err = ensure_equivalent(&"A.sol", &source_unit, "contract A {\n    uint256 b;\n}\n")
+            .unwrap_err();
+        assert!(err.to_string().contains("Failed to construct equivalent Solidity code for A.sol"));
+
+        let err = ensure_equivalent(&"A.sol", &source_unit, "contract A {").unwrap_err();
+        assert!(err.to_string().contains("Failed to construct valid Solidity code for A.sol"));
+    }
+}
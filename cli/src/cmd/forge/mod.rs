@@ -40,7 +40,9 @@
 pub mod bind;
 pub mod build;
 pub mod cache;
+pub mod compare_layout;
 pub mod config;
+pub mod coverage;
 pub mod create;
 pub mod flatten;
 pub mod fmt;
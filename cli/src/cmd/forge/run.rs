@@ -1,17 +1,22 @@
 use crate::{
     cmd::{forge::build::CoreBuildArgs, Cmd},
-    compile, utils,
+    compile,
+    opts::{EthereumOpts, Wallet, WalletType},
+    utils,
 };
 use clap::{Parser, ValueHint};
 use ethers::{
     abi::{Abi, Function},
-    prelude::ArtifactId,
+    prelude::{ArtifactId, Http, Middleware, Provider},
     solc::{
         artifacts::{CompactContractBytecode, ContractBytecode, ContractBytecodeSome},
         utils::RuntimeOrHandle,
         Project,
     },
-    types::{Address, Bytes, Log, U256},
+    types::{
+        transaction::eip2718::TypedTransaction, Address, Bytes, Chain, Eip1559TransactionRequest,
+        Log, TransactionReceipt, TxHash, U256,
+    },
 };
 use forge::{
     debug::DebugArena,
@@ -26,7 +31,12 @@ use forge::{
 use foundry_common::evm::EvmArgs;
 use foundry_config::{figment::Figment, Config};
 use foundry_utils::{encode_args, format_token, IntoFunction, PostLinkInput};
-use std::{collections::BTreeMap, path::PathBuf};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fs,
+    path::{Path, PathBuf},
+};
 use ui::{TUIExitReason, Tui, Ui};
 use yansi::Paint;
 
@@ -58,6 +68,27 @@ pub struct RunArgs {
     #[clap(long)]
     pub debug: bool,
 
+    /// Broadcasts the transactions recorded with the `broadcast` cheatcodes.
+    ///
+    /// The transactions are signed with the configured wallet and sent to the RPC endpoint given
+    /// by --fork-url, one after another.
+    #[clap(long)]
+    pub broadcast: bool,
+
+    /// Resumes broadcasting the transactions of a previous run of the script that did not
+    /// complete.
+    #[clap(long, requires = "broadcast")]
+    pub resume: bool,
+
+    /// Send legacy transactions instead of EIP1559 transactions.
+    ///
+    /// This is automatically enabled for common networks without EIP1559.
+    #[clap(long)]
+    pub legacy: bool,
+
+    #[clap(flatten, next_help_heading = "WALLET OPTIONS")]
+    pub wallet: ScriptWallet,
+
     #[clap(flatten, next_help_heading = "BUILD OPTIONS")]
     pub opts: CoreBuildArgs,
 
@@ -65,14 +96,85 @@ pub struct RunArgs {
     pub evm_opts: EvmArgs,
 }
 
+/// The wallet used to sign broadcasted transactions.
+///
+/// These are the same options as the ones of [Wallet], without the short flags, which clash with
+/// the options of `forge run`.
+#[derive(Debug, Clone, Parser)]
+pub struct ScriptWallet {
+    /// Open an interactive prompt to enter your private key.
+    #[clap(long)]
+    pub interactive: bool,
+
+    /// Use the provided private key.
+    #[clap(long, value_name = "RAW_PRIVATE_KEY")]
+    pub private_key: Option<String>,
+
+    /// Use the mnemonic file at the specified path.
+    #[clap(long, value_name = "PATH")]
+    pub mnemonic_path: Option<String>,
+
+    /// Use the private key from the given mnemonic index. Used with --mnemonic-path.
+    #[clap(long, default_value = "0", value_name = "INDEX")]
+    pub mnemonic_index: u32,
+
+    /// Use the keystore in the given folder or file.
+    #[clap(env = "ETH_KEYSTORE", long = "keystore", value_name = "PATH")]
+    pub keystore_path: Option<String>,
+
+    /// The keystore password. Used with --keystore.
+    #[clap(long = "password", requires = "keystore-path", value_name = "PASSWORD")]
+    pub keystore_password: Option<String>,
+
+    /// Use a Ledger hardware wallet.
+    #[clap(long)]
+    pub ledger: bool,
+
+    /// Use a Trezor hardware wallet.
+    #[clap(long)]
+    pub trezor: bool,
+
+    /// The derivation path to use with hardware wallets.
+    #[clap(long, value_name = "PATH")]
+    pub hd_path: Option<String>,
+}
+
+impl From<ScriptWallet> for Wallet {
+    fn from(wallet: ScriptWallet) -> Self {
+        Wallet {
+            interactive: wallet.interactive,
+            private_key: wallet.private_key,
+            mnemonic_path: wallet.mnemonic_path,
+            mnemonic_index: wallet.mnemonic_index,
+            keystore_path: wallet.keystore_path,
+            keystore_password: wallet.keystore_password,
+            ledger: wallet.ledger,
+            trezor: wallet.trezor,
+            hd_path: wallet.hd_path,
+            from: None,
+        }
+    }
+}
+
 impl Cmd for RunArgs {
     type Output = ();
     fn run(self) -> eyre::Result<Self::Output> {
         let figment: Figment = From::from(&self);
-        let evm_opts = figment.extract::<EvmOpts>()?;
+        let mut evm_opts = figment.extract::<EvmOpts>()?;
         let verbosity = evm_opts.verbosity;
         let config = Config::from_provider(figment).sanitized();
 
+        let runtime = RuntimeOrHandle::new();
+
+        // When broadcasting, the script is run by the account that signs the transactions
+        let signer = if self.broadcast {
+            let (signer, chain) = runtime.block_on(self.signer(&evm_opts))?;
+            evm_opts.sender = signer_address(&signer);
+            Some((signer, chain))
+        } else {
+            None
+        };
+
         let BuildOutput {
             project,
             contract,
@@ -81,6 +183,10 @@ impl Cmd for RunArgs {
             predeploy_libraries,
         } = self.build(&config, &evm_opts)?;
 
+        if self.broadcast && !predeploy_libraries.is_empty() {
+            eyre::bail!("Broadcasting scripts that need to deploy libraries is not supported yet. Deploy the libraries first, then provide their addresses to link at compile time")
+        }
+
         let known_contracts = highlevel_known_contracts
             .iter()
             .map(|(id, c)| {
@@ -112,7 +218,6 @@ impl Cmd for RunArgs {
             }
         }
 
-        let runtime = RuntimeOrHandle::new();
         let env = runtime.block_on(evm_opts.evm_env());
         // the db backend that serves all the data
         let db = runtime
@@ -122,7 +227,8 @@ impl Cmd for RunArgs {
             .with_cheatcodes(evm_opts.ffi)
             .with_config(env)
             .with_spec(crate::utils::evm_spec(&config.evm_version))
-            .with_gas_limit(evm_opts.gas_limit());
+            .with_gas_limit(evm_opts.gas_limit())
+            .with_fs_allowed_paths(config.fs_allowed_paths.clone());
 
         if verbosity >= 3 {
             builder = builder.with_tracing();
@@ -141,7 +247,7 @@ impl Cmd for RunArgs {
                 hex::decode(calldata).unwrap().into(),
             ),
             _ => {
-                let func = IntoFunction::into(self.sig);
+                let func = IntoFunction::into(self.sig.clone());
                 (
                     abi.functions()
                         .find(|&abi_func| abi_func.short_signature() == func.short_signature())
@@ -151,9 +257,21 @@ impl Cmd for RunArgs {
             }
         };
 
+        // Resume broadcasting the transactions of a previous run instead of running the script
+        if self.resume {
+            let (signer, chain) = signer.expect("--resume requires --broadcast");
+            let path = self.sequence_path(&project, func, chain)?;
+            let mut sequence = ScriptSequence::load(&path)?;
+            return runtime.block_on(self.send_transactions(signer, &mut sequence, &path))
+        }
+
         let mut result = {
-            let mut runner =
-                Runner::new(builder.build(db), evm_opts.initial_balance, evm_opts.sender);
+            let mut runner = Runner::new(
+                builder.build(db),
+                evm_opts.initial_balance,
+                evm_opts.sender,
+                self.broadcast,
+            );
             let (address, mut result) =
                 runner.setup(&predeploy_libraries, bytecode, needs_setup)?;
 
@@ -165,7 +283,7 @@ impl Cmd for RunArgs {
                 traces,
                 debug: run_debug,
                 labeled_addresses,
-                ..
+                transactions,
             } = runner.run(address, call)?;
 
             result.success &= success;
@@ -176,6 +294,7 @@ impl Cmd for RunArgs {
             result.traces.extend(traces);
             result.debug = run_debug;
             result.labeled_addresses.extend(labeled_addresses);
+            result.transactions.extend(transactions);
 
             result
         };
@@ -286,6 +405,37 @@ impl Cmd for RunArgs {
                     println!("  {log}");
                 }
             }
+
+            if !result.transactions.is_empty() {
+                match signer {
+                    Some((signer, chain)) if result.success => {
+                        let sender = signer_address(&signer);
+                        if let Some(from) = result
+                            .transactions
+                            .iter()
+                            .filter_map(|tx| tx.from())
+                            .find(|from| **from != sender)
+                        {
+                            eyre::bail!("The script broadcasts transactions from {:?}, but only transactions from the configured wallet {:?} can be signed", from, sender)
+                        }
+
+                        let path = self.sequence_path(&project, func, chain)?;
+                        let mut sequence = ScriptSequence {
+                            transactions: result.transactions,
+                            ..Default::default()
+                        };
+                        sequence.save(&path)?;
+                        runtime.block_on(self.send_transactions(signer, &mut sequence, &path))?;
+                    }
+                    Some(_) => {
+                        eyre::bail!("The script failed, so none of its transactions were broadcasted")
+                    }
+                    None => println!(
+                        "{} transaction(s) were recorded. Run the script with --broadcast to send them.",
+                        result.transactions.len()
+                    ),
+                }
+            }
         }
         Ok(())
     }
@@ -308,6 +458,138 @@ pub struct BuildOutput {
 }
 
 impl RunArgs {
+    /// Returns the wallet that signs the broadcasted transactions, along with the id of the chain
+    /// they are sent to
+    async fn signer(&self, evm_opts: &EvmOpts) -> eyre::Result<(WalletType, u64)> {
+        let rpc_url = evm_opts.fork_url.clone().ok_or_else(|| {
+            eyre::eyre!(
+                "You need to provide an RPC endpoint with --fork-url to broadcast transactions"
+            )
+        })?;
+        let provider = Provider::<Http>::try_from(rpc_url.as_str())?;
+        let chain_id = provider.get_chainid().await?;
+
+        let opts = EthereumOpts {
+            rpc_url: Some(rpc_url),
+            flashbots: false,
+            etherscan_api_key: None,
+            chain: Chain::try_from(chain_id.as_u64()).unwrap_or(Chain::Mainnet),
+            wallet: self.wallet.clone().into(),
+        };
+        let signer = opts
+            .signer_with(chain_id, provider)
+            .await?
+            .ok_or_else(|| eyre::eyre!("could not find a wallet to sign the transactions"))?;
+
+        Ok((signer, chain_id.as_u64()))
+    }
+
+    /// Returns the path of the file the transactions of a run of `func` on `chain` are written to,
+    /// `broadcast/<script file>/<chain id>/<function>-latest.json`
+    fn sequence_path(
+        &self,
+        project: &Project,
+        func: &Function,
+        chain: u64,
+    ) -> eyre::Result<PathBuf> {
+        let file_name = self
+            .path
+            .file_name()
+            .ok_or_else(|| eyre::eyre!("invalid script path {}", self.path.display()))?;
+        Ok(project
+            .root()
+            .join("broadcast")
+            .join(file_name)
+            .join(chain.to_string())
+            .join(format!("{}-latest.json", func.name)))
+    }
+
+    async fn send_transactions(
+        &self,
+        signer: WalletType,
+        sequence: &mut ScriptSequence,
+        path: &Path,
+    ) -> eyre::Result<()> {
+        match signer {
+            WalletType::Ledger(signer) => self.send_with(signer, sequence, path).await,
+            WalletType::Local(signer) => self.send_with(signer, sequence, path).await,
+            WalletType::Trezor(signer) => self.send_with(signer, sequence, path).await,
+        }
+    }
+
+    /// Signs and sends the transactions of the sequence that have not been sent yet, one after
+    /// another, and writes the sequence to `path` after every step so that it can be resumed.
+    async fn send_with<M: Middleware + 'static>(
+        &self,
+        provider: M,
+        sequence: &mut ScriptSequence,
+        path: &Path,
+    ) -> eyre::Result<()> {
+        let chain = provider.get_chainid().await?.as_u64();
+        let is_legacy =
+            self.legacy || Chain::try_from(chain).map(|x| Chain::is_legacy(&x)).unwrap_or_default();
+
+        // A previous run may have been interrupted before it got the receipt of a transaction
+        for hash in std::mem::take(&mut sequence.pending) {
+            match provider.get_transaction_receipt(hash).await? {
+                Some(receipt) => sequence.add_receipt(receipt),
+                None => {
+                    sequence.pending.push(hash);
+                    sequence.save(path)?;
+                    eyre::bail!("Transaction {:?} is still pending. Try resuming later.", hash)
+                }
+            }
+        }
+        sequence.save(path)?;
+
+        while sequence.receipts.len() < sequence.transactions.len() {
+            let tx = sequence.transactions[sequence.receipts.len()].clone();
+            let from = *tx.from().expect("broadcasted transactions have a sender");
+
+            // The transactions were recorded with the nonces the sender had while running the
+            // script, which only hold if nobody else sends transactions from this account
+            let nonce = provider.get_transaction_count(from, None).await?;
+            if tx.nonce() != Some(&nonce) {
+                eyre::bail!(
+                    "The nonce of {:?} is {} onchain, but the next transaction has nonce {}. Were other transactions sent from this account in the meantime?",
+                    from,
+                    nonce,
+                    tx.nonce().copied().unwrap_or_default()
+                )
+            }
+
+            let mut tx = if is_legacy { tx } else { into_eip1559(tx) };
+            provider.fill_transaction(&mut tx, None).await?;
+
+            let pending = provider.send_transaction(tx, None).await?;
+            let hash = *pending;
+            println!("Sent transaction {:?}", hash);
+            sequence.pending.push(hash);
+            sequence.save(path)?;
+
+            let receipt = pending.await?.ok_or_else(|| {
+                eyre::eyre!("Transaction {:?} was dropped from the mempool", hash)
+            })?;
+            sequence.pending.clear();
+            let failed = receipt.status == Some(0u64.into());
+            sequence.add_receipt(receipt);
+            sequence.save(path)?;
+
+            if failed {
+                eyre::bail!("Transaction {:?} failed", hash)
+            }
+        }
+
+        println!(
+            "{}",
+            Paint::green(format!(
+                "All transactions were sent. Receipts were written to {}",
+                path.display()
+            ))
+        );
+        Ok(())
+    }
+
     /// Compiles the file with auto-detection and compiler params.
     pub fn build(&self, config: &Config, evm_opts: &EvmOpts) -> eyre::Result<BuildOutput> {
         let target_contract = dunce::canonicalize(&self.path)?;
@@ -394,6 +676,76 @@ impl RunArgs {
     }
 }
 
+/// The transactions of a script run, along with the receipts of the ones that were sent.
+///
+/// This is written to disk while broadcasting, so that an interrupted run can be resumed.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ScriptSequence {
+    /// The transactions recorded by the script, in the order they are sent
+    pub transactions: VecDeque<TypedTransaction>,
+    /// The receipts of the transactions that were mined
+    pub receipts: Vec<TransactionReceipt>,
+    /// The hashes of the transactions that were sent, but not mined yet
+    pub pending: Vec<TxHash>,
+    /// The addresses of the contracts deployed by the transactions
+    pub deployments: Vec<Address>,
+}
+
+impl ScriptSequence {
+    /// Loads the sequence of a previous run from `path`
+    pub fn load(path: &Path) -> eyre::Result<Self> {
+        let file = fs::File::open(path).map_err(|err| {
+            eyre::eyre!(
+                "could not open the transactions of a previous run at {}: {}",
+                path.display(),
+                err
+            )
+        })?;
+        Ok(serde_json::from_reader(file)?)
+    }
+
+    /// Writes the sequence to `path`
+    pub fn save(&self, path: &Path) -> eyre::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        serde_json::to_writer_pretty(fs::File::create(path)?, self)?;
+        Ok(())
+    }
+
+    /// Adds the receipt of the next transaction of the sequence
+    pub fn add_receipt(&mut self, receipt: TransactionReceipt) {
+        if let Some(address) = receipt.contract_address {
+            println!("Contract deployed at {:?}", address);
+            self.deployments.push(address);
+        }
+        self.receipts.push(receipt);
+    }
+}
+
+/// Converts a transaction recorded by the `broadcast` cheatcodes into an EIP1559 transaction
+fn into_eip1559(tx: TypedTransaction) -> TypedTransaction {
+    match tx {
+        TypedTransaction::Legacy(tx) => TypedTransaction::Eip1559(Eip1559TransactionRequest {
+            from: tx.from,
+            to: tx.to,
+            value: tx.value,
+            data: tx.data,
+            nonce: tx.nonce,
+            ..Default::default()
+        }),
+        tx => tx,
+    }
+}
+
+fn signer_address(signer: &WalletType) -> Address {
+    match signer {
+        WalletType::Ledger(signer) => signer.address(),
+        WalletType::Local(signer) => signer.address(),
+        WalletType::Trezor(signer) => signer.address(),
+    }
+}
+
 struct RunResult {
     pub success: bool,
     pub returned: bytes::Bytes,
@@ -402,17 +754,25 @@ struct RunResult {
     pub debug: Option<Vec<DebugArena>>,
     pub gas: u64,
     pub labeled_addresses: BTreeMap<Address, String>,
+    pub transactions: VecDeque<TypedTransaction>,
 }
 
 struct Runner<DB: DatabaseRef> {
     pub executor: Executor<DB>,
     pub initial_balance: U256,
     pub sender: Address,
+    /// Whether the transactions of the script are going to be broadcasted
+    pub broadcast: bool,
 }
 
-impl<DB: DatabaseRef> Runner<DB> {
-    pub fn new(executor: Executor<DB>, initial_balance: U256, sender: Address) -> Self {
-        Self { executor, initial_balance, sender }
+impl<DB: DatabaseRef + Clone> Runner<DB> {
+    pub fn new(
+        executor: Executor<DB>,
+        initial_balance: U256,
+        sender: Address,
+        broadcast: bool,
+    ) -> Self {
+        Self { executor, initial_balance, sender, broadcast }
     }
 
     pub fn setup(
@@ -421,12 +781,21 @@ impl<DB: DatabaseRef> Runner<DB> {
         code: Bytes,
         setup: bool,
     ) -> eyre::Result<(Address, RunResult)> {
-        // We max out their balance so that they can deploy and make calls.
-        self.executor.set_balance(self.sender, U256::MAX);
-        self.executor.set_balance(*CALLER, U256::MAX);
+        // When broadcasting, the balance and nonce of the sender have to match the ones it has
+        // onchain, so the script is deployed by a separate account
+        let deployer = if self.broadcast {
+            *CALLER
+        } else {
+            // We max out their balance so that they can deploy and make calls.
+            self.executor.set_balance(self.sender, U256::MAX);
+
+            // We set the nonce of the deployer accounts to 1 to get the same addresses as
+            // DappTools
+            self.executor.set_nonce(self.sender, 1);
 
-        // We set the nonce of the deployer accounts to 1 to get the same addresses as DappTools
-        self.executor.set_nonce(self.sender, 1);
+            self.sender
+        };
+        self.executor.set_balance(*CALLER, U256::MAX);
 
         // Deploy libraries
         let mut traces: Vec<(TraceKind, CallTraceArena)> = libraries
@@ -434,7 +803,7 @@ impl<DB: DatabaseRef> Runner<DB> {
             .filter_map(|code| {
                 let DeployResult { traces, .. } = self
                     .executor
-                    .deploy(self.sender, code.0.clone(), 0u32.into(), None)
+                    .deploy(deployer, code.0.clone(), 0u32.into(), None)
                     .expect("couldn't deploy library");
 
                 traces
@@ -449,7 +818,7 @@ impl<DB: DatabaseRef> Runner<DB> {
             traces: constructor_traces,
             debug: constructor_debug,
             ..
-        } = self.executor.deploy(self.sender, code.0, 0u32.into(), None).expect("couldn't deploy");
+        } = self.executor.deploy(deployer, code.0, 0u32.into(), None).expect("couldn't deploy");
         traces.extend(constructor_traces.map(|traces| (TraceKind::Deployment, traces)).into_iter());
         self.executor.set_balance(address, self.initial_balance);
 
@@ -463,8 +832,27 @@ impl<DB: DatabaseRef> Runner<DB> {
                     logs: setup_logs,
                     debug,
                     gas,
+                    transactions,
                     ..
-                }) |
+                }) => {
+                    traces
+                        .extend(setup_traces.map(|traces| (TraceKind::Setup, traces)).into_iter());
+                    logs.extend_from_slice(&setup_logs);
+
+                    (
+                        address,
+                        RunResult {
+                            returned: bytes::Bytes::new(),
+                            logs,
+                            traces,
+                            labeled_addresses: labels,
+                            success: !reverted,
+                            debug: vec![constructor_debug, debug].into_iter().collect(),
+                            gas,
+                            transactions: transactions.unwrap_or_default(),
+                        },
+                    )
+                }
                 Err(EvmError::Execution {
                     reverted,
                     traces: setup_traces,
@@ -488,6 +876,7 @@ impl<DB: DatabaseRef> Runner<DB> {
                             success: !reverted,
                             debug: vec![constructor_debug, debug].into_iter().collect(),
                             gas,
+                            transactions: Default::default(),
                         },
                     )
                 }
@@ -504,14 +893,25 @@ impl<DB: DatabaseRef> Runner<DB> {
                     debug: vec![constructor_debug].into_iter().collect(),
                     gas: 0,
                     labeled_addresses: Default::default(),
+                    transactions: Default::default(),
                 },
             )
         })
     }
 
     pub fn run(&mut self, address: Address, calldata: Bytes) -> eyre::Result<RunResult> {
-        let RawCallResult { reverted, gas, stipend, result, logs, traces, labels, debug, .. } =
-            self.executor.call_raw(self.sender, address, calldata.0, 0.into())?;
+        let RawCallResult {
+            reverted,
+            gas,
+            stipend,
+            result,
+            logs,
+            traces,
+            labels,
+            debug,
+            transactions,
+            ..
+        } = self.executor.call_raw(self.sender, address, calldata.0, 0.into())?;
         Ok(RunResult {
             success: !reverted,
             returned: result,
@@ -520,6 +920,7 @@ impl<DB: DatabaseRef> Runner<DB> {
             traces: traces.map(|traces| vec![(TraceKind::Execution, traces)]).unwrap_or_default(),
             debug: vec![debug].into_iter().collect(),
             labeled_addresses: labels,
+            transactions: transactions.unwrap_or_default(),
         })
     }
 }
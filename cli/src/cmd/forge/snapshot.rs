@@ -12,9 +12,10 @@ use eyre::Context;
 use forge::TestKindGas;
 use once_cell::sync::Lazy;
 use regex::Regex;
+use serde::Serialize;
 use std::{
     cmp::Ordering,
-    collections::HashMap,
+    collections::BTreeMap,
     fmt::Write,
     fs,
     io::{self, BufRead},
@@ -27,7 +28,7 @@ use yansi::Paint;
 /// A regex that matches a basic snapshot entry like
 /// `Test:testDeposit() (gas: 58804)`
 pub static RE_BASIC_SNAPSHOT_ENTRY: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"(?P<file>(.*?)):(?P<sig>(\w+)\s*\((.*?)\))\s*\(((gas:)?\s*(?P<gas>\d+)|(runs:\s*(?P<runs>\d+),\s*μ:\s*(?P<avg>\d+),\s*~:\s*(?P<med>\d+)))\)").unwrap()
+    Regex::new(r"(?P<file>(.*?)):(?P<sig>(\w+)\s*\((.*?)\))\s*\(((gas:)?\s*(?P<gas>\d+)|(runs:\s*(?P<runs>\d+),\s*μ:\s*(?P<avg>\d+),\s*~:\s*(?P<med>\d+))|(runs:\s*(?P<invruns>\d+),\s*calls:\s*(?P<calls>\d+),\s*reverts:\s*(?P<reverts>\d+)))\)").unwrap()
 });
 
 #[derive(Debug, Clone, Parser)]
@@ -64,6 +65,18 @@ pub struct SnapshotArgs {
     )]
     check: Option<Option<PathBuf>>,
 
+    /// The relative change of the gas usage of a test, in percent, that is tolerated by --check.
+    ///
+    /// Fuzz tests are compared on their median gas usage.
+    #[clap(long, default_value = "0", value_name = "PERCENT")]
+    tolerance: f64,
+
+    /// Write the comparison against the snapshot to the given file in JSON format.
+    ///
+    /// Only used together with --diff or --check.
+    #[clap(long, value_hint = ValueHint::FilePath, value_name = "PATH")]
+    diff_json: Option<PathBuf>,
+
     // Hidden because there is only one option
     #[clap(help = "How to format the output.", long, hide(true))]
     format: Option<Format>,
@@ -109,12 +122,18 @@ impl Cmd for SnapshotArgs {
 
         if let Some(path) = self.diff {
             let snap = path.as_ref().unwrap_or(&self.snap);
-            let snaps = read_snapshot(snap)?;
-            diff(tests, snaps)?;
+            let comparison = compare(tests, read_snapshot(snap)?);
+            if let Some(diff_json) = &self.diff_json {
+                write_diff_json(&comparison, self.tolerance, diff_json)?;
+            }
+            diff(comparison);
         } else if let Some(path) = self.check {
             let snap = path.as_ref().unwrap_or(&self.snap);
-            let snaps = read_snapshot(snap)?;
-            if check(tests, snaps) {
+            let comparison = compare(tests, read_snapshot(snap)?);
+            if let Some(diff_json) = &self.diff_json {
+                write_diff_json(&comparison, self.tolerance, diff_json)?;
+            }
+            if check(&comparison, self.tolerance) {
                 std::process::exit(0)
             } else {
                 std::process::exit(1)
@@ -198,7 +217,7 @@ impl SnapshotConfig {
 /// A general entry in a snapshot file
 ///
 /// Has the form `<signature>(gas:? 40181)`
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
 pub struct SnapshotEntry {
     pub contract_name: String,
     pub signature: String,
@@ -220,6 +239,20 @@ impl FromStr for SnapshotEntry {
                                 signature: sig.as_str().to_string(),
                                 gas_used: TestKindGas::Standard(gas.as_str().parse().unwrap()),
                             })
+                        } else if let Some(runs) = cap.name("invruns") {
+                            cap.name("calls")
+                                .and_then(|calls| {
+                                    cap.name("reverts").map(|reverts| (runs, calls, reverts))
+                                })
+                                .map(|(runs, calls, reverts)| SnapshotEntry {
+                                    contract_name: file.as_str().to_string(),
+                                    signature: sig.as_str().to_string(),
+                                    gas_used: TestKindGas::Invariant {
+                                        runs: runs.as_str().parse().unwrap(),
+                                        calls: calls.as_str().parse().unwrap(),
+                                        reverts: reverts.as_str().parse().unwrap(),
+                                    },
+                                })
                         } else {
                             cap.name("runs")
                                 .and_then(|runs| {
@@ -280,6 +313,7 @@ fn write_to_snapshot_file(
 /// A Snapshot entry diff
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct SnapshotDiff {
+    pub contract_name: String,
     pub signature: String,
     pub source_gas_used: TestKindGas,
     pub target_gas_used: TestKindGas,
@@ -289,85 +323,110 @@ impl SnapshotDiff {
     /// Returns the gas diff
     ///
     /// `> 0` if the source used more gas
-    /// `< 0` if the source used more gas
+    /// `< 0` if the target used more gas
     fn gas_change(&self) -> i128 {
         self.source_gas_used.gas() as i128 - self.target_gas_used.gas() as i128
     }
 
     /// Determines the percentage change
     fn gas_diff(&self) -> f64 {
-        self.gas_change() as f64 / self.target_gas_used.gas() as f64
+        match self.target_gas_used.gas() {
+            0 if self.gas_change() == 0 => 0.0,
+            0 => f64::INFINITY,
+            target => self.gas_change() as f64 / target as f64 * 100.0,
+        }
     }
+
+    /// Whether the gas usage changed by more than the given percentage
+    fn exceeds_tolerance(&self, tolerance: f64) -> bool {
+        self.gas_change() != 0 && self.gas_diff().abs() > tolerance
+    }
+}
+
+/// The tests compared with an existing snapshot
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotComparison {
+    /// The tests that have an entry in the snapshot
+    pub diffs: Vec<SnapshotDiff>,
+    /// The tests that have no entry in the snapshot
+    pub added: Vec<SnapshotEntry>,
+    /// The snapshot entries that have no matching test
+    pub removed: Vec<SnapshotEntry>,
 }
 
 /// Compares the set of tests with an existing snapshot
-///
-/// Returns true all tests match
-fn check(tests: Vec<Test>, snaps: Vec<SnapshotEntry>) -> bool {
-    let snaps = snaps
+fn compare(tests: Vec<Test>, snaps: Vec<SnapshotEntry>) -> SnapshotComparison {
+    let mut snaps = snaps
         .into_iter()
-        .map(|s| ((s.contract_name, s.signature), s.gas_used))
-        .collect::<HashMap<_, _>>();
-    let mut has_diff = false;
+        .map(|s| ((s.contract_name.clone(), s.signature.clone()), s))
+        .collect::<BTreeMap<_, _>>();
+    let mut comparison = SnapshotComparison::default();
     for test in tests {
-        if let Some(target_gas) =
-            snaps.get(&(test.contract_name().to_string(), test.signature.clone())).cloned()
-        {
-            let source_gas = test.result.kind.gas_used();
-            if source_gas.gas() != target_gas.gas() {
-                eprintln!(
-                    "Diff in \"{}::{}\": consumed \"{}\" gas, expected \"{}\" gas ",
-                    test.contract_name(),
-                    test.signature,
-                    source_gas,
-                    target_gas
-                );
-                has_diff = true;
-            }
-        } else {
-            eprintln!(
-                "No matching snapshot entry found for \"{}::{}\" in snapshot file",
-                test.contract_name(),
-                test.signature
-            );
-            has_diff = true;
+        let contract_name = test.contract_name().to_string();
+        let source_gas_used = test.result.kind.gas_used();
+        match snaps.remove(&(contract_name.clone(), test.signature.clone())) {
+            Some(snap) => comparison.diffs.push(SnapshotDiff {
+                contract_name,
+                signature: test.signature,
+                source_gas_used,
+                target_gas_used: snap.gas_used,
+            }),
+            None => comparison.added.push(SnapshotEntry {
+                contract_name,
+                signature: test.signature,
+                gas_used: source_gas_used,
+            }),
         }
     }
-    !has_diff
+    comparison.removed = snaps.into_values().collect();
+    comparison
 }
 
-/// Compare the set of tests with an existing snapshot
-fn diff(tests: Vec<Test>, snaps: Vec<SnapshotEntry>) -> eyre::Result<()> {
-    let snaps = snaps
-        .into_iter()
-        .map(|s| ((s.contract_name, s.signature), s.gas_used))
-        .collect::<HashMap<_, _>>();
-    let mut diffs = Vec::with_capacity(tests.len());
-    for test in tests.into_iter() {
-        let target_gas_used = snaps
-            .get(&(test.contract_name().to_string(), test.signature.clone()))
-            .cloned()
-            .ok_or_else(|| {
-                eyre::eyre!(
-                    "No matching snapshot entry found for \"{}\" in snapshot file",
-                    test.signature
-                )
-            })?;
-
-        diffs.push(SnapshotDiff {
-            source_gas_used: test.result.kind.gas_used(),
-            signature: test.signature,
-            target_gas_used,
-        });
+/// Checks the comparison of the tests with an existing snapshot
+///
+/// Returns true if no test changed by more than the tolerated percentage and all tests have an
+/// entry in the snapshot
+fn check(comparison: &SnapshotComparison, tolerance: f64) -> bool {
+    let mut has_diff = false;
+    for diff in comparison.diffs.iter().filter(|diff| diff.exceeds_tolerance(tolerance)) {
+        eprintln!(
+            "Diff in \"{}::{}\": consumed \"{}\" gas, expected \"{}\" gas ({})",
+            diff.contract_name,
+            diff.signature,
+            diff.source_gas_used,
+            diff.target_gas_used,
+            fmt_pct_change(diff.gas_diff())
+        );
+        has_diff = true;
+    }
+    for added in comparison.added.iter() {
+        eprintln!(
+            "No matching snapshot entry found for \"{}::{}\" in snapshot file",
+            added.contract_name, added.signature
+        );
+        has_diff = true;
     }
+    for removed in comparison.removed.iter() {
+        eprintln!(
+            "{} No matching test found for snapshot entry \"{}::{}\"",
+            Paint::yellow("Warning:").bold(),
+            removed.contract_name,
+            removed.signature
+        );
+    }
+    !has_diff
+}
+
+/// Prints the comparison of the tests with an existing snapshot
+fn diff(mut comparison: SnapshotComparison) {
     let mut overall_gas_change = 0i128;
     let mut overall_gas_diff = 0f64;
 
-    diffs.sort_by(|a, b| {
+    comparison.diffs.sort_by(|a, b| {
         a.gas_diff().abs().partial_cmp(&b.gas_diff().abs()).unwrap_or(Ordering::Equal)
     });
 
-    for diff in diffs {
+    for diff in comparison.diffs {
         let gas_change = diff.gas_change();
         overall_gas_change += gas_change;
         let gas_diff = diff.gas_diff();
@@ -385,7 +444,68 @@ fn diff(tests: Vec<Test>, snaps: Vec<SnapshotEntry>) -> eyre::Result<()> {
         fmt_change(overall_gas_change),
         fmt_pct_change(overall_gas_diff)
     );
-    Ok(())
+
+    if !comparison.added.is_empty() {
+        println!("\nAdded tests:");
+        for added in comparison.added {
+            println!("{}:{} {}", added.contract_name, added.signature, added.gas_used);
+        }
+    }
+    if !comparison.removed.is_empty() {
+        println!("\nRemoved tests:");
+        for removed in comparison.removed {
+            println!("{}:{} {}", removed.contract_name, removed.signature, removed.gas_used);
+        }
+    }
+}
+
+/// A test compared with its snapshot entry, as written to the JSON diff
+#[derive(Debug, Serialize)]
+struct JsonSnapshotDiff<'a> {
+    contract_name: &'a str,
+    signature: &'a str,
+    source_gas_used: &'a TestKindGas,
+    target_gas_used: &'a TestKindGas,
+    gas_change: i128,
+    gas_diff: f64,
+    exceeds_tolerance: bool,
+}
+
+/// The comparison with an existing snapshot, as written to the JSON diff
+#[derive(Debug, Serialize)]
+struct JsonSnapshotComparison<'a> {
+    tolerance: f64,
+    diffs: Vec<JsonSnapshotDiff<'a>>,
+    added: &'a [SnapshotEntry],
+    removed: &'a [SnapshotEntry],
+}
+
+/// Writes the comparison of the tests with an existing snapshot to a JSON file
+fn write_diff_json(
+    comparison: &SnapshotComparison,
+    tolerance: f64,
+    path: impl AsRef<Path>,
+) -> eyre::Result<()> {
+    let diffs = comparison
+        .diffs
+        .iter()
+        .map(|diff| JsonSnapshotDiff {
+            contract_name: &diff.contract_name,
+            signature: &diff.signature,
+            source_gas_used: &diff.source_gas_used,
+            target_gas_used: &diff.target_gas_used,
+            gas_change: diff.gas_change(),
+            gas_diff: diff.gas_diff(),
+            exceeds_tolerance: diff.exceeds_tolerance(tolerance),
+        })
+        .collect();
+    let json = JsonSnapshotComparison {
+        tolerance,
+        diffs,
+        added: &comparison.added,
+        removed: &comparison.removed,
+    };
+    Ok(fs::write(path, serde_json::to_string_pretty(&json)?)?)
 }
 
 fn fmt_pct_change(change: f64) -> String {
@@ -439,4 +559,41 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn can_parse_invariant_snapshot_entry() {
+        let s = "Test:invariantDeposit() (runs: 256, calls: 3840, reverts: 12)";
+        let entry = SnapshotEntry::from_str(s).unwrap();
+        assert_eq!(
+            entry,
+            SnapshotEntry {
+                contract_name: "Test".to_string(),
+                signature: "invariantDeposit()".to_string(),
+                gas_used: TestKindGas::Invariant { runs: 256, calls: 3840, reverts: 12 }
+            }
+        );
+    }
+
+    #[test]
+    fn can_check_tolerance() {
+        let diff = SnapshotDiff {
+            contract_name: "Test".to_string(),
+            signature: "deposit()".to_string(),
+            source_gas_used: TestKindGas::Fuzz { runs: 256, mean: 900, median: 1050 },
+            target_gas_used: TestKindGas::Fuzz { runs: 256, mean: 1000, median: 1000 },
+        };
+        assert_eq!(diff.gas_change(), 50);
+        assert_eq!(diff.gas_diff(), 5.0);
+        assert!(diff.exceeds_tolerance(0.0));
+        assert!(diff.exceeds_tolerance(4.9));
+        assert!(!diff.exceeds_tolerance(5.0));
+
+        let unchanged = SnapshotDiff {
+            target_gas_used: TestKindGas::Invariant { runs: 256, calls: 3840, reverts: 12 },
+            source_gas_used: TestKindGas::Invariant { runs: 256, calls: 3840, reverts: 0 },
+            ..diff
+        };
+        assert_eq!(unchanged.gas_diff(), 0.0);
+        assert!(!unchanged.exceeds_tolerance(0.0));
+    }
 }
@@ -8,12 +8,13 @@ use crate::{
     suggestions, utils,
     utils::FoundryPathExt,
 };
-use clap::{AppSettings, Parser};
-use ethers::solc::FileFilter;
+use clap::{AppSettings, Parser, ValueHint};
+use ethers::{solc::FileFilter, types::U256};
 use forge::{
     decode::decode_console_logs,
     executor::opts::EvmOpts,
-    gas_report::GasReport,
+    fuzz::{invariant::InvariantConfig, CounterExample},
+    gas_report::{GasReport, GasReportFormat},
     trace::{
         identifier::{EtherscanIdentifier, LocalTraceIdentifier},
         CallTraceDecoderBuilder, TraceKind,
@@ -22,6 +23,7 @@ use forge::{
 };
 use foundry_common::evm::EvmArgs;
 use foundry_config::{figment::Figment, Config};
+use proptest::test_runner::{RngAlgorithm, TestRng, TestRunner};
 use regex::Regex;
 use std::{
     collections::BTreeMap,
@@ -233,6 +235,19 @@ pub struct TestArgs {
     #[clap(long, env = "FORGE_GAS_REPORT")]
     gas_report: bool,
 
+    /// The format of the gas report.
+    ///
+    /// Valid values: table, json, markdown.
+    #[clap(long, default_value = "table", value_name = "FORMAT")]
+    gas_report_format: GasReportFormat,
+
+    /// Compare the gas report to a baseline report in JSON format.
+    ///
+    /// Prints the changes of the deployment cost and size, and the min, avg, median and max gas of
+    /// every function. `forge test` also accepts `--diff`.
+    #[clap(long, value_hint = ValueHint::FilePath, value_name = "PATH", requires = "gas-report")]
+    gas_report_diff: Option<PathBuf>,
+
     /// Exit with code 0 even if a test fails.
     #[clap(long, env = "FORGE_ALLOW_FAILURE")]
     allow_failure: bool,
@@ -241,6 +256,10 @@ pub struct TestArgs {
     #[clap(long, short, help_heading = "DISPLAY OPTIONS")]
     json: bool,
 
+    /// Set the seed of the fuzzer, to reproduce the inputs of a previous run.
+    #[clap(long, parse(try_from_str = utils::parse_u256), value_name = "SEED")]
+    fuzz_seed: Option<U256>,
+
     #[clap(flatten, next_help_heading = "EVM OPTIONS")]
     evm_opts: EvmArgs,
 
@@ -285,6 +304,10 @@ impl TestArgs {
         if let Some(etherscan_api_key) = &self.etherscan_api_key {
             config.etherscan_api_key = Some(etherscan_api_key.to_string());
         }
+
+        if let Some(fuzz_seed) = self.fuzz_seed {
+            config.fuzz_seed = Some(fuzz_seed);
+        }
         Ok((config, evm_opts))
     }
 
@@ -456,9 +479,19 @@ pub fn custom_run(args: TestArgs, include_fuzz_tests: bool) -> eyre::Result<Test
         max_global_rejects: config.fuzz_max_global_rejects,
         ..Default::default()
     };
-    let fuzzer = proptest::test_runner::TestRunner::new(cfg);
+    let fuzzer = match config.fuzz_seed {
+        Some(fuzz_seed) => {
+            let mut seed = [0u8; 32];
+            fuzz_seed.to_big_endian(&mut seed);
+            TestRunner::new_with_rng(cfg, TestRng::from_seed(RngAlgorithm::ChaCha, &seed))
+        }
+        None => TestRunner::new(cfg),
+    };
     let mut filter = args.filter();
 
+    // Load the baseline of the gas report first, so an invalid baseline fails early
+    let gas_report_baseline = args.gas_report_diff.as_deref().map(GasReport::read).transpose()?;
+
     // Set up the project
     let project = config.project()?;
     let compiler = ProjectCompiler::default();
@@ -482,6 +515,13 @@ pub fn custom_run(args: TestArgs, include_fuzz_tests: bool) -> eyre::Result<Test
         .evm_spec(evm_spec)
         .sender(evm_opts.sender)
         .with_fork(utils::get_fork(&evm_opts, &config.rpc_storage_caching))
+        .invariant_config(InvariantConfig {
+            depth: config.invariant_depth,
+            fail_on_revert: config.invariant_fail_on_revert,
+        })
+        .fs_allowed_paths(config.fs_allowed_paths.clone())
+        .fuzz_corpus_dir(config.cache_path.join("fuzz"))
+        .fuzz_configs(config.fuzz.clone())
         .build(project.paths.root, output, evm_opts)?;
 
     if args.debug.is_some() {
@@ -501,7 +541,7 @@ pub fn custom_run(args: TestArgs, include_fuzz_tests: bool) -> eyre::Result<Test
                     // Build debugger args if this is a fuzz test
                     let sig = match test_kind {
                         TestKind::Fuzz(cases) => {
-                            if let Some(counterexample) = counterexample {
+                            if let Some(CounterExample::Single(counterexample)) = counterexample {
                                 counterexample.calldata.to_string()
                             } else {
                                 cases.cases().first().expect("no fuzz cases run").calldata.to_string()
@@ -542,6 +582,8 @@ pub fn custom_run(args: TestArgs, include_fuzz_tests: bool) -> eyre::Result<Test
             args.allow_failure,
             include_fuzz_tests,
             args.gas_report,
+            args.gas_report_format,
+            gas_report_baseline,
         )
     }
 }
@@ -575,6 +617,8 @@ fn test(
     allow_failure: bool,
     include_fuzz_tests: bool,
     gas_reporting: bool,
+    gas_report_format: GasReportFormat,
+    gas_report_baseline: Option<GasReport>,
 ) -> eyre::Result<TestOutcome> {
     if runner.count_filtered_tests(&filter) == 0 {
         let filter_str = filter.to_string();
@@ -623,18 +667,25 @@ fn test(
 
         let mut results: BTreeMap<String, SuiteResult> = BTreeMap::new();
         let mut gas_report = GasReport::new(config.gas_reports);
+        // Only the report is printed if it is in JSON format, so it can be used as a baseline
+        let quiet = gas_reporting && gas_report_format == GasReportFormat::Json;
+        let verbosity = if quiet { 0 } else { verbosity };
         for (contract_name, suite_result) in rx {
             let mut tests = suite_result.test_results.clone();
-            println!();
+            if !quiet {
+                println!();
+            }
             for warning in suite_result.warnings.iter() {
                 eprintln!("{} {}", Paint::yellow("Warning:").bold(), warning);
             }
-            if !tests.is_empty() {
+            if !tests.is_empty() && !quiet {
                 let term = if tests.len() > 1 { "tests" } else { "test" };
                 println!("Running {} {} for {}", tests.len(), term, contract_name);
             }
             for (name, result) in &mut tests {
-                short_test_result(name, result);
+                if !quiet {
+                    short_test_result(name, result);
+                }
 
                 // We only display logs at level 2 and above
                 if verbosity >= 2 {
@@ -701,12 +752,19 @@ fn test(
                 [(contract_name.clone(), suite_result.clone())].into(),
                 allow_failure,
             );
-            println!("{}", block_outcome.summary());
+            if !quiet {
+                println!("{}", block_outcome.summary());
+            }
             results.insert(contract_name, suite_result);
         }
 
         if gas_reporting {
-            println!("{}", gas_report.finalize());
+            let gas_report = gas_report.finalize();
+            let rendered = match gas_report_baseline {
+                Some(baseline) => gas_report.diff(&baseline).render(gas_report_format)?,
+                None => gas_report.render(gas_report_format)?,
+            };
+            println!("{}", rendered);
         }
 
         // reattach the thread
@@ -99,6 +99,9 @@ fn main() -> eyre::Result<()> {
                 cmd.run()?;
             }
         }
+        Subcommands::Coverage(cmd) => {
+            cmd.run()?;
+        }
         Subcommands::Fmt(cmd) => {
             cmd.run()?;
         }
@@ -111,6 +114,9 @@ fn main() -> eyre::Result<()> {
         Subcommands::Inspect(cmd) => {
             cmd.run()?;
         }
+        Subcommands::CompareLayout(cmd) => {
+            cmd.run()?;
+        }
         Subcommands::UploadSelectors(args) => {
             utils::block_on(args.run())?;
         }
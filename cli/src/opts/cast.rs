@@ -1,6 +1,8 @@
 use super::{ClapChain, EthereumOpts, Wallet};
 use crate::{
-    cmd::cast::{find_block::FindBlockArgs, run::RunArgs},
+    cmd::cast::{
+        create2::Create2Args, find_block::FindBlockArgs, run::RunArgs, storage::StorageArgs,
+    },
     utils::{parse_ether_value, parse_u256},
 };
 use clap::{Parser, Subcommand, ValueHint};
@@ -286,14 +288,30 @@ Examples:
     },
     #[clap(name = "compute-address")]
     #[clap(alias = "ca")]
-    #[clap(about = "Compute the contract address from a given nonce and deployer address.")]
+    #[clap(
+        about = "Compute the contract address from a given nonce and deployer address, or from a salt and init code hash for CREATE2."
+    )]
     ComputeAddress {
         #[clap(long, env = "ETH_RPC_URL", value_name = "URL")]
         rpc_url: Option<String>,
         #[clap(help = "The deployer address.", value_name = "ADDRESS")]
         address: String,
-        #[clap(long, help = "The nonce of the deployer address.", parse(try_from_str = parse_u256), value_name = "NONCE")]
+        #[clap(long, help = "The nonce of the deployer address.", parse(try_from_str = parse_u256), conflicts_with = "salt", value_name = "NONCE")]
         nonce: Option<U256>,
+        #[clap(
+            long,
+            help = "The salt of a CREATE2 deployment.",
+            requires = "init-code-hash",
+            value_name = "SALT"
+        )]
+        salt: Option<H256>,
+        #[clap(
+            long,
+            help = "The keccak256 hash of the init code of a CREATE2 deployment.",
+            requires = "salt",
+            value_name = "HASH"
+        )]
+        init_code_hash: Option<H256>,
     },
     #[clap(name = "namehash")]
     #[clap(aliases = &["na", "nh"])]
@@ -659,25 +677,9 @@ Tries to decode the calldata using https://sig.eth.samczsun.com unless --offline
     #[clap(
         name = "storage",
         alias = "st",
-        about = "Get the raw value of a contract's storage slot."
+        about = "Get the raw value of a contract's storage slot, or the decoded value of a state variable."
     )]
-    Storage {
-        #[clap(help = "The contract address.", parse(try_from_str = parse_name_or_address), value_name = "ADDRESS")]
-        address: NameOrAddress,
-        #[clap(help = "The storage slot number (hex or decimal)", parse(try_from_str = parse_slot), value_name = "SLOT")]
-        slot: H256,
-        #[clap(short, long, env = "ETH_RPC_URL", value_name = "URL")]
-        rpc_url: Option<String>,
-        #[clap(
-            long,
-            short = 'B',
-            help = "The block height you want to query at.",
-            long_help = "The block height you want to query at. Can also be the tags earliest, latest, or pending.",
-            parse(try_from_str = parse_block_id),
-            value_name = "BLOCK"
-        )]
-        block: Option<BlockId>,
-    },
+    Storage(StorageArgs),
     #[clap(
         name = "proof",
         alias = "pr",
@@ -798,6 +800,12 @@ If an address is specified, then the ABI is fetched from Etherscan."#,
         about = "Get the block number closest to the provided timestamp."
     )]
     FindBlock(FindBlockArgs),
+    #[clap(
+        name = "create2",
+        alias = "c2",
+        about = "Mine a salt for a vanity contract address deployed with CREATE2."
+    )]
+    Create2(Create2Args),
     #[clap(alias = "com", about = "Generate shell completions script")]
     Completions {
         #[clap(arg_enum)]
@@ -896,7 +904,7 @@ pub fn parse_block_id(s: &str) -> eyre::Result<BlockId> {
     })
 }
 
-fn parse_slot(s: &str) -> eyre::Result<H256> {
+pub fn parse_slot(s: &str) -> eyre::Result<H256> {
     Ok(if s.starts_with("0x") {
         let padded = format!("{:0>64}", s.strip_prefix("0x").unwrap());
         H256::from_str(&padded)?
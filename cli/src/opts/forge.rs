@@ -7,7 +7,9 @@ use crate::cmd::forge::{
     bind::BindArgs,
     build::BuildArgs,
     cache::CacheArgs,
+    compare_layout,
     config,
+    coverage,
     create::CreateArgs,
     flatten,
     fmt::FmtArgs,
@@ -49,6 +51,8 @@ pub struct Opts {
 pub enum Subcommands {
     #[clap(alias = "t")]
     #[clap(about = "Run the project's tests.")]
+    // `forge snapshot` flattens the test args but has its own `--diff`
+    #[clap(mut_arg("gas-report-diff", |arg| arg.alias("diff")))]
     Test(test::TestArgs),
 
     #[clap(alias = "bi")]
@@ -135,6 +139,9 @@ pub enum Subcommands {
     #[clap(alias = "s", about = "Create a snapshot of each test's gas usage.")]
     Snapshot(snapshot::SnapshotArgs),
 
+    #[clap(about = "Generate coverage reports.")]
+    Coverage(coverage::CoverageArgs),
+
     #[clap(alias = "co", about = "Display the current config.")]
     Config(config::ConfigArgs),
 
@@ -147,6 +154,12 @@ pub enum Subcommands {
     #[clap(alias = "in", about = "Get specialized information about a smart contract")]
     Inspect(inspect::InspectArgs),
 
+    #[clap(
+        alias = "cl",
+        about = "Check that the storage layout of a new version of a contract is compatible with the old one."
+    )]
+    CompareLayout(compare_layout::CompareLayoutArgs),
+
     #[clap(
         alias = "up",
         about = "Uploads abi of given contract to https://sig.eth.samczsun.com function selector database"
@@ -30,6 +30,12 @@ pub(crate) const VERSION_MESSAGE: &str = concat!(
     ")"
 );
 
+/// The address of the deterministic deployment proxy, which deploys contracts with `CREATE2` using
+/// the first 32 bytes of the calldata as salt and the rest as init code.
+///
+/// It is deployed at the same address on most chains, see <https://github.com/Arachnid/deterministic-deployment-proxy>
+pub const DEFAULT_CREATE2_DEPLOYER: &str = "0x4e59b44847b379578588920ca78fbf26c0b4956c";
+
 /// Useful extensions to [`std::path::Path`].
 pub trait FoundryPathExt {
     /// Returns true if the [`Path`] ends with `.t.sol`
@@ -72,3 +72,43 @@ casttest!(upload_signatures, |_: TestProject, mut cmd: TestCommand| {
     assert!(output.contains("Function decimals(): 0x313ce567"), "{}", output);
     assert!(output.contains("Function allowance(address,address): 0xdd62ed3e"), "{}", output);
 });
+
+// tests that the `cast compute-address` command computes CREATE2 addresses without an rpc
+casttest!(computes_create2_address, |_: TestProject, mut cmd: TestCommand| {
+    // example 2 of EIP-1014, the init code hash is `keccak256(0x00)`
+    cmd.args([
+        "compute-address",
+        "0xdeadbeef00000000000000000000000000000000",
+        "--salt",
+        "0x000000000000000000000000feed000000000000000000000000000000000000",
+        "--init-code-hash",
+        "0xbc36789e7a1e281436464229828f817d6612f7b477d66591ff96a9e064bcc98a",
+    ]);
+    let output = cmd.stdout_lossy();
+
+    assert!(output.contains("0xd04116cdd17bebe565eb2422f2497e06cc1c9833"), "{}", output);
+});
+
+// tests that the `cast create2` command finds a salt for the requested address pattern
+casttest!(mines_create2_salt, |_: TestProject, mut cmd: TestCommand| {
+    cmd.args([
+        "create2",
+        "--starts-with",
+        "dd",
+        "--ends-with",
+        "0",
+        "--deployer",
+        "0x4e59b44847b379578588920ca78fbf26c0b4956c",
+        "--init-code",
+        "0x00",
+    ]);
+    let output = cmd.stdout_lossy();
+
+    let address = output
+        .lines()
+        .find_map(|line| line.strip_prefix("Address: "))
+        .unwrap_or_else(|| panic!("no address found: {}", output))
+        .to_lowercase();
+    assert!(address.starts_with("0xdd"), "{}", output);
+    assert!(address.ends_with('0'), "{}", output);
+});
@@ -483,6 +483,46 @@ result: uint256 255
     )));
 });
 
+// Tests that the run command records the transactions of the `broadcast` cheatcodes
+forgetest!(can_record_broadcasted_transactions, |prj: TestProject, mut cmd: TestCommand| {
+    let script = prj
+        .inner()
+        .add_source(
+            "Foo",
+            r#"
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity 0.8.10;
+interface Vm {
+    function startBroadcast() external;
+    function stopBroadcast() external;
+}
+contract Counter {
+    uint256 public count;
+    function increment() external {
+        count += 1;
+    }
+}
+contract Demo {
+    Vm constant vm = Vm(address(bytes20(uint160(uint256(keccak256("hevm cheat code"))))));
+    function run() external {
+        vm.startBroadcast();
+        Counter counter = new Counter();
+        counter.increment();
+        require(counter.count() == 1);
+        vm.stopBroadcast();
+        counter.increment();
+    }
+}"#,
+        )
+        .unwrap();
+    cmd.arg("run").arg(script).args(["--target-contract", "Demo"]);
+    let output = cmd.stdout_lossy();
+    assert!(output.contains(&Paint::green("Script ran successfully.").to_string()));
+    assert!(output.ends_with(
+        "2 transaction(s) were recorded. Run the script with --broadcast to send them.\n"
+    ));
+});
+
 // tests that the `inspect` command works correctly
 forgetest!(can_execute_inspect_command, |prj: TestProject, mut cmd: TestCommand| {
     // explicitly set to include the ipfs bytecode hash
@@ -564,6 +604,109 @@ contract BTest is DSTest {
     let _ = cmd.output();
 });
 
+// test that `forge snapshot --check` tolerates small changes and reports removed tests
+forgetest!(can_check_snapshot_with_tolerance, |prj: TestProject, mut cmd: TestCommand| {
+    prj.insert_ds_test();
+
+    prj.inner()
+        .add_source(
+            "ATest.t.sol",
+            r#"
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity 0.8.10;
+import "./test.sol";
+contract ATest is DSTest {
+    function testExample() public {
+        assertTrue(true);
+    }
+}
+   "#,
+        )
+        .unwrap();
+
+    cmd.arg("snapshot");
+    cmd.output();
+
+    // increase the recorded gas usage by 2% and add an entry without a test
+    let snap_path = prj.root().join(".gas-snapshot");
+    let snap = fs::read_to_string(&snap_path).unwrap();
+    let (entry, gas) = snap.trim().trim_end_matches(')').rsplit_once("(gas: ").unwrap();
+    let gas = gas.parse::<u64>().unwrap() * 102 / 100;
+    fs::write(&snap_path, format!("{entry}(gas: {gas})\nATest:testRemoved() (gas: 100)\n"))
+        .unwrap();
+
+    cmd.forge_fuse().args(["snapshot", "--check", "--tolerance", "1"]);
+    cmd.assert_err();
+
+    let diff_json = prj.root().join("snapshot-diff.json");
+    cmd.forge_fuse()
+        .args(["snapshot", "--check", "--tolerance", "3", "--diff-json"])
+        .arg(&diff_json);
+    cmd.output();
+
+    let diff: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&diff_json).unwrap()).unwrap();
+    assert_eq!(diff["diffs"][0]["signature"], "testExample()");
+    assert_eq!(diff["diffs"][0]["exceeds_tolerance"], false);
+    assert_eq!(diff["added"].as_array().unwrap().len(), 0);
+    assert_eq!(diff["removed"][0]["signature"], "testRemoved()");
+});
+
+// test that `forge coverage` writes an LCOV report for non-test sources
+forgetest!(can_write_lcov_coverage, |prj: TestProject, mut cmd: TestCommand| {
+    prj.insert_ds_test();
+
+    prj.inner()
+        .add_source(
+            "Counter.sol",
+            r#"
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity 0.8.10;
+contract Counter {
+    uint256 public count;
+
+    function increment() public {
+        count += 1;
+    }
+
+    function decrement() public {
+        require(count > 0, "underflow");
+        count -= 1;
+    }
+}
+   "#,
+        )
+        .unwrap();
+    prj.inner()
+        .add_source(
+            "CounterTest.t.sol",
+            r#"
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity 0.8.10;
+import "./test.sol";
+import "./Counter.sol";
+contract CounterTest is DSTest {
+    function testIncrement() public {
+        Counter counter = new Counter();
+        counter.increment();
+        assertEq(counter.count(), 1);
+    }
+}
+   "#,
+        )
+        .unwrap();
+
+    cmd.args(["coverage", "--report", "summary", "--report", "lcov"]);
+    let stdout = cmd.stdout_lossy();
+    assert!(stdout.contains("% Lines"));
+    assert!(stdout.contains("Wrote LCOV report"));
+
+    let lcov = std::fs::read_to_string(prj.root().join("lcov.info")).unwrap();
+    assert!(lcov.contains("Counter.sol"));
+    assert!(!lcov.contains("CounterTest.t.sol"));
+    assert!(lcov.contains("end_of_record"));
+});
+
 // test that `forge build` does not print `(with warnings)` if there arent any
 forgetest!(can_compile_without_warnings, |prj: TestProject, mut cmd: TestCommand| {
     let config = Config {
@@ -727,3 +870,46 @@ contract CTest is DSTest {
     let cache_after = fs::read_to_string(prj.cache_path()).unwrap();
     assert_eq!(cache, cache_after);
 });
+
+const UNFORMATTED: &str = r#"
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity 0.8.10;
+contract   Greeter {
+    function greet( ) public pure returns (string memory) { return "hello"; }
+}
+"#;
+
+// test that `forge fmt --check` fails and prints a diff for unformatted files without touching them
+forgetest!(can_check_unformatted, |prj: TestProject, mut cmd: TestCommand| {
+    let path = prj.create_file("src/Greeter.sol", UNFORMATTED);
+
+    cmd.args(["fmt", "--check"]).arg(&path);
+    let output = cmd.unchecked_output();
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Diff in"), "{}", stdout);
+    assert!(stdout.contains("contract   Greeter"), "{}", stdout);
+
+    assert_eq!(read_string(&path), UNFORMATTED);
+});
+
+// test that `forge fmt --check` succeeds for files that are already formatted
+forgetest!(can_check_formatted, |prj: TestProject, mut cmd: TestCommand| {
+    let path = prj.create_file("src/Greeter.sol", UNFORMATTED);
+
+    cmd.arg("fmt").arg(&path);
+    cmd.assert_empty_stdout();
+    let formatted = read_string(&path);
+    assert_ne!(formatted, UNFORMATTED);
+
+    cmd.forge_fuse().args(["fmt", "--check"]).arg(&path);
+    cmd.assert_empty_stdout();
+    assert_eq!(read_string(&path), formatted);
+});
+
+// test that `forge test --diff` is accepted as the gas report baseline
+forgetest!(can_diff_gas_report_via_alias, |_prj: TestProject, mut cmd: TestCommand| {
+    cmd.args(["test", "--gas-report", "--diff", "missing-baseline.json"]);
+    let stderr = cmd.stderr_lossy();
+    assert!(stderr.contains("Failed to read gas report missing-baseline.json"), "{}", stderr);
+});
@@ -12,9 +12,9 @@ use foundry_cli_test_utils::{
 };
 use foundry_config::{
     cache::{CachedChains, CachedEndpoints, StorageCachingConfig},
-    Config, OptimizerDetails, SolcReq,
+    Config, FormatterConfig, FuzzConfig, OptimizerDetails, SolcReq,
 };
-use std::{fs, path::PathBuf, str::FromStr};
+use std::{collections::BTreeMap, fs, path::PathBuf, str::FromStr};
 
 // import forge utils as mod
 #[allow(unused)]
@@ -61,7 +61,15 @@ forgetest!(can_extract_config_values, |prj: TestProject, mut cmd: TestCommand| {
         fuzz_runs: 1000,
         fuzz_max_local_rejects: 2000,
         fuzz_max_global_rejects: 100203,
+        fuzz_seed: Some(1000.into()),
+        fuzz: BTreeMap::from([(
+            "MyTest.testFoo".to_string(),
+            FuzzConfig { runs: Some(1000), ..Default::default() },
+        )]),
+        invariant_depth: 30,
+        invariant_fail_on_revert: true,
         ffi: true,
+        fs_allowed_paths: vec!["test-fixtures".into()],
         sender: "00a329c0648769A73afAc7F9381D08FB43dBEA72".parse().unwrap(),
         tx_origin: "00a329c0648769A73afAc7F9F81E08FB43dBEA72".parse().unwrap(),
         initial_balance: U256::from(0xffffffffffffffffffffffffu128),
@@ -93,6 +101,7 @@ forgetest!(can_extract_config_values, |prj: TestProject, mut cmd: TestCommand| {
         bytecode_hash: Default::default(),
         revert_strings: Some(RevertStrings::Strip),
         sparse_mode: true,
+        fmt: FormatterConfig { line_length: 100, ..Default::default() },
         __non_exhaustive: (),
     };
     prj.write_config(input.clone());
@@ -0,0 +1,100 @@
+//! Configuration specific to the `forge fmt` command and the `forge_fmt` package
+
+use serde::{Deserialize, Serialize};
+
+/// Contains the config and rule set
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FormatterConfig {
+    /// Maximum line length where formatter will try to wrap the line
+    pub line_length: usize,
+    /// Number of spaces per indentation level
+    pub tab_width: usize,
+    /// Print spaces between brackets
+    pub bracket_spacing: bool,
+    /// Style of uint/int256 types
+    pub int_types: IntTypes,
+    /// Style of quotation marks of string literals and import paths
+    pub quote_style: QuoteStyle,
+    /// Sort function attributes as visibility, mutability, virtual, override and modifiers
+    pub sort_func_attributes: bool,
+    /// Style of multiline function header in case it doesn't fit
+    pub multiline_func_header: MultilineFuncHeaderStyle,
+    /// Style of underscores in number literals
+    pub number_underscore: NumberUnderscore,
+    /// Sort import directives that are not separated by blank lines alphabetically
+    pub sort_imports: bool,
+}
+
+/// Style of uint/int256 types
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntTypes {
+    /// Print the explicit uint256 or int256
+    Long,
+    /// Print the implicit uint or int
+    Short,
+    /// Use the type defined in the source code
+    Preserve,
+}
+
+/// Style of quotation marks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteStyle {
+    /// Use double quotes where possible
+    Double,
+    /// Use single quotes where possible
+    Single,
+    /// Use quotation mark defined in the source code
+    Preserve,
+}
+
+impl QuoteStyle {
+    /// Returns the associated quotation mark character, if any
+    pub fn quote(self) -> Option<char> {
+        match self {
+            QuoteStyle::Double => Some('"'),
+            QuoteStyle::Single => Some('\''),
+            QuoteStyle::Preserve => None,
+        }
+    }
+}
+
+/// Style of multiline function header in case it doesn't fit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MultilineFuncHeaderStyle {
+    /// Write function attributes multiline first
+    AttributesFirst,
+    /// If the header doesn't fit, write both function parameters and attributes multiline
+    All,
+}
+
+/// Style of underscores in number literals
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NumberUnderscore {
+    /// Use the underscores defined in the source code
+    Preserve,
+    /// Remove all underscores
+    Remove,
+    /// Add an underscore every thousand, if the integer part is greater than 9999
+    Thousands,
+}
+
+impl Default for FormatterConfig {
+    fn default() -> Self {
+        FormatterConfig {
+            line_length: 80,
+            tab_width: 4,
+            bracket_spacing: false,
+            int_types: IntTypes::Long,
+            quote_style: QuoteStyle::Double,
+            sort_func_attributes: true,
+            multiline_func_header: MultilineFuncHeaderStyle::AttributesFirst,
+            number_underscore: NumberUnderscore::Preserve,
+            sort_imports: false,
+        }
+    }
+}
@@ -0,0 +1,45 @@
+//! Configuration of individual fuzz tests
+
+use serde::{Deserialize, Serialize};
+
+/// Fuzz settings of a single test contract or test function.
+///
+/// Every setting that is not set falls back to the less specific configuration, the global
+/// `fuzz_*` settings of the [Config](crate::Config) last.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FuzzConfig {
+    /// The number of test cases that must execute
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runs: Option<u32>,
+    /// The maximum number of local test case rejections
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_local_rejects: Option<u32>,
+    /// The maximum number of global test case rejections
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_global_rejects: Option<u32>,
+}
+
+impl FuzzConfig {
+    /// Sets the given `fuzz.<key>` setting, as used by `forge-config` comments, e.g. `runs = 1000`
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let value = value.trim().replace('_', "").parse::<u32>().map_err(|err| {
+            format!("invalid value `{}` for `fuzz.{}`: {}", value.trim(), key, err)
+        })?;
+        match key.trim() {
+            "runs" => self.runs = Some(value),
+            "max_local_rejects" => self.max_local_rejects = Some(value),
+            "max_global_rejects" => self.max_global_rejects = Some(value),
+            key => return Err(format!("unknown fuzz setting `fuzz.{}`", key)),
+        }
+        Ok(())
+    }
+
+    /// Returns the settings of `self`, with all unset ones taken from `other`
+    pub fn or(self, other: FuzzConfig) -> FuzzConfig {
+        FuzzConfig {
+            runs: self.runs.or(other.runs),
+            max_local_rejects: self.max_local_rejects.or(other.max_local_rejects),
+            max_global_rejects: self.max_global_rejects.or(other.max_global_rejects),
+        }
+    }
+}
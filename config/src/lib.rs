@@ -25,7 +25,7 @@ use semver::Version;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::{
     borrow::Cow,
-    collections::{hash_map::Entry, BTreeSet, HashMap},
+    collections::{hash_map::Entry, BTreeMap, BTreeSet, HashMap},
     fs,
     path::{Path, PathBuf},
     str::FromStr,
@@ -40,6 +40,11 @@ pub use crate::utils::*;
 
 pub mod cache;
 use cache::{Cache, ChainCache};
+
+pub mod fmt;
+pub use fmt::FormatterConfig;
+pub mod fuzz;
+pub use fuzz::FuzzConfig;
 mod chain;
 pub use chain::Chain;
 
@@ -168,6 +173,8 @@ pub struct Config {
     pub fuzz_runs: u32,
     /// Whether to allow ffi cheatcodes in test
     pub ffi: bool,
+    /// Paths the filesystem cheatcodes (`readFile`, `readLine`, `writeFile`) are allowed to access
+    pub fs_allowed_paths: Vec<PathBuf>,
     /// The address which will be executing all tests
     pub sender: Address,
     /// The tx.origin value during EVM execution
@@ -233,6 +240,16 @@ pub struct Config {
     /// by proptest, to be encountered during usage of `vm.assume`
     /// cheatcode.
     pub fuzz_max_global_rejects: u32,
+    /// The seed of the fuzzer's random number generator, makes fuzz test runs reproducible
+    pub fuzz_seed: Option<U256>,
+    /// Fuzz settings of individual test contracts and tests, keyed by the contract name or
+    /// `<path>:<contract name>`, optionally followed by `.<test name>`
+    pub fuzz: BTreeMap<String, FuzzConfig>,
+    /// The number of calls executed to attempt to break invariants in one run of an invariant
+    /// test
+    pub invariant_depth: u32,
+    /// Whether an invariant test fails if a call to a target contract reverts
+    pub invariant_fail_on_revert: bool,
     /// Print the names of the compiled contracts
     pub names: bool,
     /// Print the sizes of the compiled contracts
@@ -259,6 +276,8 @@ pub struct Config {
     /// included in solc's output selection, see also
     /// [OutputSelection](ethers_solc::artifacts::output_selection::OutputSelection)
     pub sparse_mode: bool,
+    /// Configuration for `forge fmt`
+    pub fmt: FormatterConfig,
     /// The root path where the config detection started from, `Config::with_root`
     #[doc(hidden)]
     //  We're skipping serialization here, so it won't be included in the [`Config::to_string()`]
@@ -414,6 +433,9 @@ impl Config {
 
         self.cache_path = p(&root, &self.cache_path);
 
+        self.fs_allowed_paths =
+            self.fs_allowed_paths.into_iter().map(|path| p(&root, &path)).collect();
+
         if let Some(ref mut model_checker) = self.model_checker {
             model_checker.contracts = std::mem::take(&mut model_checker.contracts)
                 .into_iter()
@@ -860,6 +882,10 @@ impl Config {
                 .replace("[model_checker]", &format!("[{}.model_checker]", self.profile));
         }
         s = s.replace("[rpc_storage_caching]", &format!("[{}.rpc_storage_caching]", self.profile));
+        s = s.replace("[fmt]", &format!("[{}.fmt]", self.profile));
+        s = s
+            .replace("[fuzz]", &format!("[{}.fuzz]", self.profile))
+            .replace("[fuzz.", &format!("[{}.fuzz.", self.profile));
 
         Ok(format!(
             r#"[{}]
@@ -1252,7 +1278,12 @@ impl Default for Config {
             fuzz_runs: 256,
             fuzz_max_local_rejects: 1024,
             fuzz_max_global_rejects: 65536,
+            fuzz_seed: None,
+            fuzz: Default::default(),
+            invariant_depth: 15,
+            invariant_fail_on_revert: false,
             ffi: false,
+            fs_allowed_paths: vec![],
             sender: "00a329c0648769A73afAc7F9381E08FB43dBEA72".parse().unwrap(),
             tx_origin: "00a329c0648769A73afAc7F9381E08FB43dBEA72".parse().unwrap(),
             initial_balance: U256::from(0xffffffffffffffffffffffffu128),
@@ -1280,6 +1311,7 @@ impl Default for Config {
             bytecode_hash: BytecodeHash::Ipfs,
             revert_strings: None,
             sparse_mode: false,
+            fmt: Default::default(),
         }
     }
 }
@@ -2283,6 +2315,7 @@ mod tests {
                 extra_output_files = []
                 ffi = false
                 force = false
+                fs_allowed_paths = []
                 fuzz_max_global_rejects = 65536
                 fuzz_max_local_rejects = 1024
                 fuzz_runs = 256
@@ -2291,6 +2324,8 @@ mod tests {
                 gas_reports = ['*']
                 ignored_error_codes = [1878]
                 initial_balance = '0xffffffffffffffffffffffff'
+                invariant_depth = 15
+                invariant_fail_on_revert = false
                 libraries = []
                 libs = ['lib']
                 memory_limit = 33554432
@@ -2327,6 +2362,88 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_fmt_config() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [default.fmt]
+                line_length = 100
+                bracket_spacing = true
+                int_types = "short"
+                quote_style = "single"
+                multiline_func_header = "all"
+                number_underscore = "thousands"
+                sort_imports = true
+            "#,
+            )?;
+
+            let config = Config::load_with_root(jail.directory());
+            assert_eq!(
+                config.fmt,
+                FormatterConfig {
+                    line_length: 100,
+                    bracket_spacing: true,
+                    int_types: fmt::IntTypes::Short,
+                    quote_style: fmt::QuoteStyle::Single,
+                    multiline_func_header: fmt::MultilineFuncHeaderStyle::All,
+                    number_underscore: fmt::NumberUnderscore::Thousands,
+                    sort_imports: true,
+                    ..Default::default()
+                }
+            );
+
+            let s = config.to_string_pretty().unwrap();
+            assert!(s.contains("[default.fmt]"));
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_fuzz_config() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [default]
+                fuzz_runs = 256
+
+                [default.fuzz.MyTest]
+                runs = 1000
+
+                [default.fuzz."test/MyTest.t.sol:MyTest.testExpensive"]
+                runs = 10
+                max_local_rejects = 5
+            "#,
+            )?;
+
+            let config = Config::load_with_root(jail.directory());
+            assert_eq!(config.fuzz_runs, 256);
+            assert_eq!(
+                config.fuzz,
+                BTreeMap::from([
+                    ("MyTest".to_string(), FuzzConfig { runs: Some(1000), ..Default::default() }),
+                    (
+                        "test/MyTest.t.sol:MyTest.testExpensive".to_string(),
+                        FuzzConfig {
+                            runs: Some(10),
+                            max_local_rejects: Some(5),
+                            ..Default::default()
+                        }
+                    ),
+                ])
+            );
+
+            let s = config.to_string_pretty().unwrap();
+            assert!(s.contains("[default.fuzz.MyTest]"));
+            assert!(s.contains(r#"[default.fuzz."test/MyTest.t.sol:MyTest.testExpensive"]"#));
+
+            Ok(())
+        });
+    }
+
     #[test]
     fn test_solc_req() {
         figment::Jail::expect_with(|jail| {
@@ -0,0 +1,94 @@
+use bytes::Bytes;
+use ethers::types::H256;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A collection of [HitMap]s, keyed by the hash of the bytecode they were recorded for.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct HitMaps(pub BTreeMap<H256, HitMap>);
+
+impl HitMaps {
+    /// Merges the hits of `other` into this collection
+    pub fn merge(&mut self, other: HitMaps) {
+        for (hash, map) in other.0 {
+            match self.0.get_mut(&hash) {
+                Some(existing) => existing.merge(map),
+                None => {
+                    self.0.insert(hash, map);
+                }
+            }
+        }
+    }
+
+    /// Merges two optional collections of hit maps
+    pub fn merge_opt(a: Option<HitMaps>, b: Option<HitMaps>) -> Option<HitMaps> {
+        match (a, b) {
+            (Some(mut a), Some(b)) => {
+                a.merge(b);
+                Some(a)
+            }
+            (a, None) => a,
+            (None, b) => b,
+        }
+    }
+
+    /// Returns all hit maps whose bytecode starts with (something very close to) `code`.
+    ///
+    /// The bytecode executed by the EVM is not necessarily byte-for-byte equal to the bytecode
+    /// produced by the compiler: immutables are filled in, and init code has the constructor
+    /// arguments appended to it. We therefore allow for a small number of differing bytes.
+    pub fn matching<'a>(&'a self, code: &'a [u8]) -> impl Iterator<Item = &'a HitMap> + 'a {
+        self.0.values().filter(move |map| {
+            if code.is_empty() || map.bytecode.len() < code.len() {
+                return false
+            }
+
+            let diff = code.iter().zip(map.bytecode.iter()).filter(|(a, b)| a != b).count();
+            diff as f64 / code.len() as f64 <= 0.1
+        })
+    }
+}
+
+/// Hit data for a single piece of bytecode.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct HitMap {
+    /// The bytecode the hits were recorded for
+    pub bytecode: Bytes,
+    /// The number of times each program counter was hit, `pc -> hits`
+    pub hits: BTreeMap<usize, u64>,
+    /// The number of times each `JUMPI` jumped and did not jump, `pc -> (taken, not taken)`
+    pub branches: BTreeMap<usize, (u64, u64)>,
+}
+
+impl HitMap {
+    pub fn new(bytecode: Bytes) -> Self {
+        Self { bytecode, ..Default::default() }
+    }
+
+    /// Increments the hit count of the given program counter
+    pub fn hit(&mut self, pc: usize) {
+        *self.hits.entry(pc).or_default() += 1;
+    }
+
+    /// Records whether the `JUMPI` at the given program counter jumped
+    pub fn branch(&mut self, pc: usize, taken: bool) {
+        let (jumped, fell_through) = self.branches.entry(pc).or_default();
+        if taken {
+            *jumped += 1;
+        } else {
+            *fell_through += 1;
+        }
+    }
+
+    /// Merges the hits of `other` into this map
+    pub fn merge(&mut self, other: HitMap) {
+        for (pc, hits) in other.hits {
+            *self.hits.entry(pc).or_default() += hits;
+        }
+        for (pc, (taken, not_taken)) in other.branches {
+            let branch = self.branches.entry(pc).or_default();
+            branch.0 += taken;
+            branch.1 += not_taken;
+        }
+    }
+}
@@ -35,6 +35,8 @@ ethers::contract::abigen!(
             expectRevert(bytes4)
             record()
             accesses(address)(bytes32[],bytes32[])
+            recordLogs()
+            getRecordedLogs()((bytes32[],bytes,address)[])
             expectEmit(bool,bool,bool,bool)
             expectEmit(bool,bool,bool,bool,address)
             mockCall(address,bytes,bytes)
@@ -48,6 +50,27 @@ ethers::contract::abigen!(
             setNonce(address,uint64)
             getNonce(address)
             chainId(uint256)
+            broadcast()
+            broadcast(address)
+            startBroadcast()
+            startBroadcast(address)
+            stopBroadcast()
+            createFork(string)(uint256)
+            createFork(string,uint256)(uint256)
+            selectFork(uint256)
+            rollFork(uint256)
+            rollFork(uint256,uint256)
+            readFile(string)(string)
+            readLine(string)(string)
+            writeFile(string,string)
+            envBool(string)(bool)
+            envUint(string)(uint256)
+            envAddress(string)(address)
+            envBytes32(string)(bytes32)
+            envString(string)(string)
+            parseJson(string,string)(bytes)
+            snapshot()(uint256)
+            revertTo(uint256)(bool)
     ]"#,
 );
 pub use hevm_mod::{HEVMCalls, HEVM_ABI};
@@ -1,4 +1,4 @@
-use ethers::prelude::Provider;
+use ethers::{abi::Abi, prelude::Provider};
 use revm::{
     db::{DatabaseRef, EmptyDB},
     Env, SpecId,
@@ -24,6 +24,10 @@ pub struct ExecutorBuilder {
     /// The configuration used to build an [InspectorStack].
     inspector_config: InspectorStackConfig,
     gas_limit: Option<U256>,
+    /// The custom errors used by the cheatcodes to decode reverts
+    errors: Option<Abi>,
+    /// The paths the filesystem cheatcodes are allowed to access
+    fs_allowed_paths: Vec<PathBuf>,
 }
 
 /// Represents a _fork_ of a live chain whose data is available only via the `url` endpoint.
@@ -152,6 +156,13 @@ impl ExecutorBuilder {
         self
     }
 
+    /// Enables coverage collection
+    #[must_use]
+    pub fn with_coverage(mut self) -> Self {
+        self.inspector_config.coverage = true;
+        self
+    }
+
     /// Sets the EVM spec to use
     #[must_use]
     pub fn with_spec(mut self, spec: SpecId) -> Self {
@@ -168,6 +179,20 @@ impl ExecutorBuilder {
         self
     }
 
+    /// Sets the custom errors used to decode reverts in cheatcodes, e.g. in `expectRevert`
+    #[must_use]
+    pub fn with_errors(mut self, errors: Abi) -> Self {
+        self.errors = Some(errors);
+        self
+    }
+
+    /// Sets the paths the filesystem cheatcodes are allowed to access
+    #[must_use]
+    pub fn with_fs_allowed_paths(mut self, paths: Vec<PathBuf>) -> Self {
+        self.fs_allowed_paths = paths;
+        self
+    }
+
     /// Configure the execution environment (gas limit, chain spec, ...)
     #[must_use]
     pub fn with_config(mut self, env: Env) -> Self {
@@ -178,7 +203,11 @@ impl ExecutorBuilder {
     }
 
     /// Builds the executor as configured.
-    pub fn build(self, db: impl Into<Backend>) -> Executor<Backend> {
+    pub fn build(mut self, db: impl Into<Backend>) -> Executor<Backend> {
+        if let Some(cheatcodes) = self.inspector_config.cheatcodes.as_mut() {
+            cheatcodes.errors = self.errors.map(Arc::new);
+            cheatcodes.set_fs_allowed_paths(self.fs_allowed_paths);
+        }
         let gas_limit = self.gas_limit.unwrap_or(self.env.block.gas_limit);
         Executor::new(db.into(), self.env, self.inspector_config, gas_limit)
     }
@@ -0,0 +1,380 @@
+//! The database the EVM runs on during a call
+use super::{
+    fork::{MultiFork, SharedBackend},
+    StateChangeset,
+};
+use bytes::Bytes;
+use ethers::types::{Address, H256, U256};
+use revm::{
+    db::{CacheDB, DatabaseCommit, DatabaseRef},
+    AccountInfo, BlockEnv, Database, Env, KECCAK_EMPTY,
+};
+use std::borrow::Cow;
+
+/// A [Database] that can switch the state the EVM runs on.
+///
+/// This is used by the cheatcodes that select forks and revert to snapshots, which must not modify
+/// the journaled state of the EVM directly.
+pub trait DatabaseExt: Database {
+    /// Takes a snapshot of the given journaled state on top of the database and returns its id
+    fn snapshot(&mut self, journaled: &StateChangeset, env: &Env) -> U256;
+
+    /// Restores the snapshot with the given id and returns whether it exists.
+    ///
+    /// The block environment, gas price and active fork are restored as well.
+    fn revert_to(&mut self, id: U256, env: &mut Env, depth: u64) -> bool;
+
+    /// Creates a new fork and returns its id
+    fn create_fork(&mut self, url: String, block: Option<u64>, env: &Env) -> eyre::Result<U256>;
+
+    /// Makes the fork with the given id the active one, the id `0` selects the initial state.
+    ///
+    /// The given journaled state of all non-persistent accounts is kept on the previously active
+    /// fork, and the block environment is switched to the one of the selected fork.
+    fn select_fork(
+        &mut self,
+        id: U256,
+        journaled: &StateChangeset,
+        env: &mut Env,
+        depth: u64,
+    ) -> eyre::Result<()>;
+
+    /// Moves the fork with the given id, or the active fork if none is given, to another block.
+    ///
+    /// Returns whether the rolled fork is the active one.
+    fn roll_fork(
+        &mut self,
+        id: Option<U256>,
+        block: U256,
+        env: &mut Env,
+        depth: u64,
+    ) -> eyre::Result<bool>;
+
+    /// Whether the state of the account is shared by all forks
+    fn is_persistent(&self, address: &Address) -> bool;
+
+    /// Marks the account as persistent, so its state is shared by all forks
+    fn add_persistent_account(&mut self, address: Address);
+
+    /// Undoes the switches made by calls deeper than `depth`.
+    ///
+    /// This is called when a call reverts, since the journaled changes of the call are reverted
+    /// as well.
+    fn revert_switches(&mut self, depth: u64, env: &mut Env);
+}
+
+impl<T: DatabaseExt> DatabaseExt for &mut T {
+    fn snapshot(&mut self, journaled: &StateChangeset, env: &Env) -> U256 {
+        (**self).snapshot(journaled, env)
+    }
+
+    fn revert_to(&mut self, id: U256, env: &mut Env, depth: u64) -> bool {
+        (**self).revert_to(id, env, depth)
+    }
+
+    fn create_fork(&mut self, url: String, block: Option<u64>, env: &Env) -> eyre::Result<U256> {
+        (**self).create_fork(url, block, env)
+    }
+
+    fn select_fork(
+        &mut self,
+        id: U256,
+        journaled: &StateChangeset,
+        env: &mut Env,
+        depth: u64,
+    ) -> eyre::Result<()> {
+        (**self).select_fork(id, journaled, env, depth)
+    }
+
+    fn roll_fork(
+        &mut self,
+        id: Option<U256>,
+        block: U256,
+        env: &mut Env,
+        depth: u64,
+    ) -> eyre::Result<bool> {
+        (**self).roll_fork(id, block, env, depth)
+    }
+
+    fn is_persistent(&self, address: &Address) -> bool {
+        (**self).is_persistent(address)
+    }
+
+    fn add_persistent_account(&mut self, address: Address) {
+        (**self).add_persistent_account(address)
+    }
+
+    fn revert_switches(&mut self, depth: u64, env: &mut Env) {
+        (**self).revert_switches(depth, env)
+    }
+}
+
+/// The state of the executor at a point in time, see [DatabaseExt::snapshot]
+#[derive(Clone)]
+pub struct Snapshot<DB: DatabaseRef> {
+    local: CacheDB<DB>,
+    forks: MultiFork,
+    block: BlockEnv,
+    gas_price: U256,
+    chain_id: U256,
+}
+
+/// The state before a switch, restored if the call that made the switch reverts
+struct Switch<DB: DatabaseRef> {
+    /// The depth of the call that made the switch
+    depth: u64,
+    state: Snapshot<DB>,
+}
+
+/// The parts of the executor state that were changed during a call
+pub struct ChangedState<DB: DatabaseRef> {
+    pub local: Option<CacheDB<DB>>,
+    pub forks: Option<MultiFork>,
+    pub snapshots: Option<Vec<Snapshot<DB>>>,
+}
+
+/// The state of the executor for the duration of a single call.
+///
+/// Persistent accounts, and all accounts if no fork is active, are read from the local state of
+/// the executor, all other accounts are read from the active fork. The state of the executor is
+/// only cloned once the call switches forks.
+pub struct ExecutorDb<'a, DB: DatabaseRef + Clone> {
+    local: Cow<'a, CacheDB<DB>>,
+    forks: Cow<'a, MultiFork>,
+    snapshots: Cow<'a, [Snapshot<DB>]>,
+    switches: Vec<Switch<DB>>,
+}
+
+impl<'a, DB: DatabaseRef + Clone> ExecutorDb<'a, DB> {
+    pub fn new(
+        local: &'a CacheDB<DB>,
+        forks: &'a MultiFork,
+        snapshots: &'a [Snapshot<DB>],
+    ) -> Self {
+        Self {
+            local: Cow::Borrowed(local),
+            forks: Cow::Borrowed(forks),
+            snapshots: Cow::Borrowed(snapshots),
+            switches: Vec::new(),
+        }
+    }
+
+    /// Returns the parts of the state that were changed during the call
+    pub fn into_changed(self) -> ChangedState<DB> {
+        fn owned<T: ToOwned + ?Sized>(value: Cow<'_, T>) -> Option<T::Owned> {
+            match value {
+                Cow::Owned(value) => Some(value),
+                Cow::Borrowed(_) => None,
+            }
+        }
+
+        ChangedState {
+            local: owned(self.local),
+            forks: owned(self.forks),
+            snapshots: owned(self.snapshots),
+        }
+    }
+
+    /// Returns the current state of the database and the environment
+    fn capture(&self, env: &Env) -> Snapshot<DB> {
+        Snapshot {
+            local: self.local.as_ref().clone(),
+            forks: self.forks.as_ref().clone(),
+            block: env.block.clone(),
+            gas_price: env.tx.gas_price,
+            chain_id: env.cfg.chain_id,
+        }
+    }
+
+    /// Replaces the state of the database and the environment with the captured state
+    fn restore(&mut self, state: Snapshot<DB>, env: &mut Env) {
+        self.local = Cow::Owned(state.local);
+        self.forks = Cow::Owned(state.forks);
+        env.block = state.block;
+        env.tx.gas_price = state.gas_price;
+        env.cfg.chain_id = state.chain_id;
+    }
+
+    /// Saves the current state, so the switch can be undone
+    fn switch(&self, depth: u64, env: &Env) -> Switch<DB> {
+        Switch { depth, state: self.capture(env) }
+    }
+
+    /// Returns the fork the account is read from, or `None` if it is read from the local state
+    fn fork_of(&self, address: &Address) -> Option<&CacheDB<SharedBackend>> {
+        self.forks.active().filter(|_| !self.forks.is_persistent(address)).map(|fork| &fork.db)
+    }
+}
+
+/// Commits the changes of persistent accounts, or all changes if no fork is active, to the local
+/// state and all other changes to the active fork
+pub fn commit_to<DB: DatabaseRef>(
+    local: &mut CacheDB<DB>,
+    forks: &mut MultiFork,
+    changeset: StateChangeset,
+) {
+    match forks.active {
+        Some(index) => {
+            let (persistent, changeset): (StateChangeset, StateChangeset) =
+                changeset.into_iter().partition(|(address, _)| forks.is_persistent(address));
+            local.commit(persistent);
+            forks.forks[index].db.commit(changeset);
+        }
+        None => local.commit(changeset),
+    }
+}
+
+/// Returns the account info with its code loaded.
+///
+/// The code of an account is looked up by its hash, so it must come from the same database as the
+/// account.
+fn basic<DB: DatabaseRef>(db: &DB, address: Address) -> AccountInfo {
+    let mut info = db.basic(address);
+    if info.code.is_none() && info.code_hash != KECCAK_EMPTY && !info.code_hash.is_zero() {
+        info.code = Some(db.code_by_hash(info.code_hash));
+    }
+    info
+}
+
+impl<'a, DB: DatabaseRef + Clone> Database for ExecutorDb<'a, DB> {
+    fn basic(&mut self, address: Address) -> AccountInfo {
+        match self.fork_of(&address) {
+            Some(fork) => basic(fork, address),
+            None => basic(self.local.as_ref(), address),
+        }
+    }
+
+    fn code_by_hash(&mut self, code_hash: H256) -> Bytes {
+        // The code is loaded together with the account, see [basic]
+        DatabaseRef::code_by_hash(self.local.as_ref(), code_hash)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> U256 {
+        match self.fork_of(&address) {
+            Some(fork) => DatabaseRef::storage(fork, address, index),
+            None => DatabaseRef::storage(self.local.as_ref(), address, index),
+        }
+    }
+
+    fn block_hash(&mut self, number: U256) -> H256 {
+        match self.forks.active() {
+            Some(fork) => DatabaseRef::block_hash(&fork.db, number),
+            None => DatabaseRef::block_hash(self.local.as_ref(), number),
+        }
+    }
+}
+
+impl<'a, DB: DatabaseRef + Clone> DatabaseExt for ExecutorDb<'a, DB> {
+    fn snapshot(&mut self, journaled: &StateChangeset, env: &Env) -> U256 {
+        let mut snapshot = self.capture(env);
+        commit_to(&mut snapshot.local, &mut snapshot.forks, journaled.clone());
+
+        let snapshots = self.snapshots.to_mut();
+        snapshots.push(snapshot);
+        (snapshots.len() - 1).into()
+    }
+
+    fn revert_to(&mut self, id: U256, env: &mut Env, depth: u64) -> bool {
+        let snapshot = match self.snapshots.get(id.min(usize::MAX.into()).as_usize()) {
+            Some(snapshot) => snapshot.clone(),
+            None => return false,
+        };
+
+        let switch = self.switch(depth, env);
+        self.switches.push(switch);
+        self.restore(snapshot, env);
+        true
+    }
+
+    fn create_fork(&mut self, url: String, block: Option<u64>, env: &Env) -> eyre::Result<U256> {
+        self.forks.to_mut().create(url, block, env)
+    }
+
+    fn select_fork(
+        &mut self,
+        id: U256,
+        journaled: &StateChangeset,
+        env: &mut Env,
+        depth: u64,
+    ) -> eyre::Result<()> {
+        let target = self.forks.index(id)?;
+        let switch = self.switch(depth, env);
+        self.switches.push(switch);
+
+        // The journaled state of the accounts belongs to the state that was active so far, it
+        // is restored once that state is selected again
+        let changeset: StateChangeset = journaled
+            .iter()
+            .filter(|(address, _)| !self.forks.is_persistent(address))
+            .map(|(address, account)| (*address, account.clone()))
+            .collect();
+        let forks = self.forks.to_mut();
+        match forks.active {
+            Some(index) => forks.forks[index].db.commit(changeset),
+            None => {
+                self.local.to_mut().commit(changeset);
+                forks.initial_env = Some(env.clone());
+            }
+        }
+
+        let selected = match target {
+            Some(index) => Some(forks.forks[index].env.clone()),
+            None => forks.initial_env.take(),
+        };
+        if let Some(selected) = selected {
+            env.block = BlockEnv { gas_limit: env.block.gas_limit, ..selected.block };
+            env.cfg.chain_id = selected.cfg.chain_id;
+        }
+        forks.active = target;
+
+        Ok(())
+    }
+
+    fn roll_fork(
+        &mut self,
+        id: Option<U256>,
+        block: U256,
+        env: &mut Env,
+        depth: u64,
+    ) -> eyre::Result<bool> {
+        let index = match id {
+            Some(id) => self
+                .forks
+                .index(id)?
+                .ok_or_else(|| eyre::eyre!("The initial state can't be rolled"))?,
+            None => self.forks.active.ok_or_else(|| eyre::eyre!("No fork is active"))?,
+        };
+        let block =
+            u64::try_from(block).map_err(|_| eyre::eyre!("Block number {} is too large", block))?;
+
+        let switch = self.switch(depth, env);
+        let forks = self.forks.to_mut();
+        forks.roll(index, block, env)?;
+        self.switches.push(switch);
+
+        let active = forks.active == Some(index);
+        if active {
+            let fork = &forks.forks[index];
+            env.block = BlockEnv { gas_limit: env.block.gas_limit, ..fork.env.block.clone() };
+            env.cfg.chain_id = fork.env.cfg.chain_id;
+        }
+        Ok(active)
+    }
+
+    fn is_persistent(&self, address: &Address) -> bool {
+        self.forks.is_persistent(address)
+    }
+
+    fn add_persistent_account(&mut self, address: Address) {
+        if !self.forks.is_persistent(&address) {
+            self.forks.to_mut().persistent.insert(address);
+        }
+    }
+
+    fn revert_switches(&mut self, depth: u64, env: &mut Env) {
+        while matches!(self.switches.last(), Some(switch) if switch.depth > depth) {
+            let switch = self.switches.pop().expect("checked above");
+            self.restore(switch.state, env);
+        }
+    }
+}
@@ -6,3 +6,6 @@ pub use init::environment;
 
 mod cache;
 pub use cache::{BlockchainDb, BlockchainDbMeta, JsonBlockCacheDB, MemDb};
+
+mod multi;
+pub use multi::{CreatedFork, MultiFork};
@@ -0,0 +1,138 @@
+//! Support for creating and switching between several forks at runtime
+use super::{environment, BlockchainDb, BlockchainDbMeta, SharedBackend};
+use crate::executor::{CHEATCODE_ADDRESS, HARDHAT_CONSOLE_ADDRESS};
+use ethers::{
+    providers::{Http, Provider},
+    types::{Address, U256},
+};
+use futures::Future;
+use revm::{db::CacheDB, Env};
+use std::{collections::BTreeSet, sync::Arc};
+
+/// A fork created at runtime, e.g. with the `createFork` cheatcode
+#[derive(Clone, Debug)]
+pub struct CreatedFork {
+    /// The URL of the endpoint the fork fetches its state from
+    pub url: String,
+    /// The environment of the forked block
+    pub env: Env,
+    /// The state of the fork.
+    ///
+    /// Accounts that were changed while the fork was active are cached here, everything else is
+    /// fetched from the forked block.
+    pub db: CacheDB<SharedBackend>,
+}
+
+impl CreatedFork {
+    /// Creates a fork of the chain behind `url` at the given block, or the latest block if none
+    /// is given.
+    ///
+    /// The backend of the fork runs on its own thread, so this can be called from synchronous code
+    /// like the cheatcode handler.
+    pub fn spawn(url: String, block: Option<u64>, env: &Env) -> eyre::Result<Self> {
+        let provider = Arc::new(Provider::<Http>::try_from(url.as_str())?);
+
+        let env = {
+            let provider = Arc::clone(&provider);
+            let memory_limit = env.cfg.memory_limit;
+            let origin = env.tx.caller;
+            block_on(async move {
+                environment(&*provider, memory_limit, None, None, block, origin).await
+            })?
+        };
+
+        let meta = BlockchainDbMeta::new(env.clone(), url.clone());
+        let db = BlockchainDb::new(meta, None);
+        let pin_block = env.block.number.as_u64();
+        let backend = SharedBackend::spawn_backend_thread(provider, db, Some(pin_block.into()));
+
+        Ok(Self { url, env, db: CacheDB::new(backend) })
+    }
+
+    /// Returns the block number the fork is pinned to
+    pub fn block_number(&self) -> U256 {
+        self.env.block.number
+    }
+}
+
+/// Holds all forks created at runtime and keeps track of the active one.
+///
+/// The id `0` refers to the state the executor was created with, forks created at runtime start
+/// at id `1`. Accounts marked as persistent (e.g. the test contract) always live in the initial
+/// state, all other accounts are read from the active fork.
+#[derive(Clone, Debug, Default)]
+pub struct MultiFork {
+    /// All created forks, the fork with id `n` is at index `n - 1`
+    pub forks: Vec<CreatedFork>,
+    /// The index of the active fork, `None` if the initial state is active
+    pub active: Option<usize>,
+    /// Accounts whose state is shared by all forks
+    pub persistent: BTreeSet<Address>,
+    /// The environment of the initial state, saved while a fork is active
+    pub initial_env: Option<Env>,
+}
+
+impl MultiFork {
+    /// Creates a new fork and returns its id
+    pub fn create(&mut self, url: String, block: Option<u64>, env: &Env) -> eyre::Result<U256> {
+        self.forks.push(CreatedFork::spawn(url, block, env)?);
+        Ok(self.forks.len().into())
+    }
+
+    /// Returns the index of the fork with the given id, or `None` for the initial state
+    pub fn index(&self, id: U256) -> eyre::Result<Option<usize>> {
+        if id.is_zero() {
+            return Ok(None)
+        }
+        if id > U256::from(self.forks.len()) {
+            eyre::bail!("Fork {} does not exist", id)
+        }
+        Ok(Some(id.as_usize() - 1))
+    }
+
+    /// Returns the active fork, if any
+    pub fn active(&self) -> Option<&CreatedFork> {
+        self.active.and_then(|index| self.forks.get(index))
+    }
+
+    /// Returns the id of the active fork
+    pub fn active_id(&self) -> U256 {
+        self.active.map(|index| index + 1).unwrap_or_default().into()
+    }
+
+    /// Moves the fork at the given index to another block.
+    ///
+    /// All local changes made to the fork are discarded.
+    pub fn roll(&mut self, index: usize, block: u64, env: &Env) -> eyre::Result<()> {
+        let url = self.forks[index].url.clone();
+        self.forks[index] = CreatedFork::spawn(url, Some(block), env)?;
+        Ok(())
+    }
+
+    /// Whether the state of the account is shared by all forks
+    pub fn is_persistent(&self, address: &Address) -> bool {
+        self.persistent.contains(address) ||
+            *address == CHEATCODE_ADDRESS ||
+            *address == HARDHAT_CONSOLE_ADDRESS
+    }
+}
+
+/// Runs a future to completion on a separate thread with its own runtime.
+///
+/// Cheatcodes are executed synchronously, possibly on a thread that is already driving a runtime,
+/// where blocking on a future directly would panic.
+fn block_on<F>(future: F) -> F::Output
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    std::thread::spawn(move || {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_io()
+            .build()
+            .expect("failed to create tokio runtime")
+            .block_on(future)
+    })
+    .join()
+    .expect("fork setup thread panicked")
+}
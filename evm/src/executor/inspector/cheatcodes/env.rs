@@ -4,7 +4,7 @@ use super::Cheatcodes;
 use crate::abi::HEVMCalls;
 use bytes::Bytes;
 use ethers::{
-    abi::{self, AbiEncode, Token, Tokenize},
+    abi::{self, AbiEncode, Token, Tokenizable, Tokenize},
     types::{Address, H256, U256},
     utils::keccak256,
 };
@@ -37,6 +37,13 @@ fn prank(
 ) -> Result<Bytes, Bytes> {
     let prank = Prank { prank_caller, prank_origin, new_caller, new_origin, depth, single_call };
 
+    if state.broadcast.is_some() {
+        return Err("You cannot `prank` for a broadcasted transaction. \
+                    Pass the desired tx.origin into the broadcast cheatcode call"
+            .to_string()
+            .encode()
+            .into())
+    }
     if state.prank.is_some() {
         return Err("You have an active prank already.".to_string().encode().into())
     }
@@ -45,12 +52,62 @@ fn prank(
     Ok(Bytes::new())
 }
 
+#[derive(Clone, Debug, Default)]
+pub struct Broadcast {
+    /// Address of the contract that initiated the broadcast
+    pub original_caller: Address,
+    /// Address of `tx.origin` when the broadcast was initiated
+    pub original_origin: Address,
+    /// The address that signs the broadcasted transactions
+    pub new_origin: Address,
+    /// The depth at which the broadcast was called
+    pub depth: u64,
+    /// Whether or not the broadcast stops by itself after the next call
+    pub single_call: bool,
+}
+
+fn broadcast(
+    state: &mut Cheatcodes,
+    original_caller: Address,
+    original_origin: Address,
+    new_origin: Address,
+    depth: u64,
+    single_call: bool,
+) -> Result<Bytes, Bytes> {
+    let broadcast = Broadcast { original_caller, original_origin, new_origin, depth, single_call };
+
+    if state.prank.is_some() {
+        return Err("You have an active prank. Broadcasting and pranks are not compatible. \
+                    Disable one or the other"
+            .to_string()
+            .encode()
+            .into())
+    }
+    if state.broadcast.is_some() {
+        return Err("You have an active broadcast already.".to_string().encode().into())
+    }
+
+    state.broadcast = Some(broadcast);
+    Ok(Bytes::new())
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct RecordAccess {
     pub reads: BTreeMap<Address, Vec<U256>>,
     pub writes: BTreeMap<Address, Vec<U256>>,
 }
 
+/// A log emitted while recording logs with `recordLogs`
+#[derive(Clone, Debug)]
+pub struct RecordedLog {
+    /// The topics of the log
+    pub topics: Vec<H256>,
+    /// The data of the log
+    pub data: Bytes,
+    /// The address of the contract that emitted the log
+    pub emitter: Address,
+}
+
 fn start_record(state: &mut Cheatcodes) {
     state.accesses = Some(Default::default());
 }
@@ -67,6 +124,27 @@ fn accesses(state: &mut Cheatcodes, address: Address) -> Bytes {
     }
 }
 
+fn start_record_logs(state: &mut Cheatcodes) {
+    state.recorded_logs = Some(Default::default());
+}
+
+/// Returns all logs recorded since the last call to `recordLogs` or `getRecordedLogs` as an array
+/// of `(bytes32[] topics, bytes data, address emitter)` tuples
+fn get_recorded_logs(state: &mut Cheatcodes) -> Bytes {
+    let logs = state.recorded_logs.as_mut().map(std::mem::take).unwrap_or_default();
+    let logs = logs
+        .into_iter()
+        .map(|log| {
+            Token::Tuple(vec![
+                Token::Array(log.topics.into_iter().map(|topic| topic.into_token()).collect()),
+                Token::Bytes(log.data.to_vec()),
+                Token::Address(log.emitter),
+            ])
+        })
+        .collect();
+    abi::encode(&[Token::Array(logs)]).into()
+}
+
 pub fn apply<DB: Database>(
     state: &mut Cheatcodes,
     data: &mut EVMData<'_, DB>,
@@ -160,6 +238,11 @@ pub fn apply<DB: Database>(
             Ok(Bytes::new())
         }
         HEVMCalls::Accesses(inner) => Ok(accesses(state, inner.0)),
+        HEVMCalls::RecordLogs(_) => {
+            start_record_logs(state);
+            Ok(Bytes::new())
+        }
+        HEVMCalls::GetRecordedLogs(_) => Ok(get_recorded_logs(state)),
         HEVMCalls::SetNonce(inner) => {
             // TODO:  this is probably not a good long-term solution since it might mess up the gas
             // calculations
@@ -188,6 +271,32 @@ pub fn apply<DB: Database>(
             data.env.cfg.chain_id = inner.0;
             Ok(Bytes::new())
         }
+        HEVMCalls::Broadcast0(_) => broadcast(
+            state,
+            caller,
+            data.env.tx.caller,
+            data.env.tx.caller,
+            data.subroutine.depth(),
+            true,
+        ),
+        HEVMCalls::Broadcast1(inner) => {
+            broadcast(state, caller, data.env.tx.caller, inner.0, data.subroutine.depth(), true)
+        }
+        HEVMCalls::StartBroadcast0(_) => broadcast(
+            state,
+            caller,
+            data.env.tx.caller,
+            data.env.tx.caller,
+            data.subroutine.depth(),
+            false,
+        ),
+        HEVMCalls::StartBroadcast1(inner) => {
+            broadcast(state, caller, data.env.tx.caller, inner.0, data.subroutine.depth(), false)
+        }
+        HEVMCalls::StopBroadcast(_) => {
+            state.broadcast = None;
+            Ok(Bytes::new())
+        }
         _ => return None,
     })
 }
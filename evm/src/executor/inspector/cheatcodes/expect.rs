@@ -4,7 +4,7 @@ use super::Cheatcodes;
 use crate::abi::HEVMCalls;
 use bytes::Bytes;
 use ethers::{
-    abi::{AbiEncode, RawLog},
+    abi::{Abi, AbiEncode, RawLog},
     types::{Address, H160, U256},
 };
 use revm::{return_ok, Database, EVMData, Return};
@@ -41,11 +41,26 @@ fn expect_revert(state: &mut Cheatcodes, reason: Bytes, depth: u64) -> Result<By
     }
 }
 
+/// Formats revert data for an error message.
+///
+/// The data is decoded as a revert string, a `Panic(uint256)` code or a custom error of the given
+/// `errors`, falling back to a plain string and finally to its hex representation.
+fn format_revert_data(data: &[u8], errors: Option<&Abi>) -> String {
+    if let Ok(decoded) = foundry_utils::decode_revert(data, errors) {
+        return decoded
+    }
+    match std::str::from_utf8(data) {
+        Ok(s) if !s.chars().any(char::is_control) => format!("'{}'", s),
+        _ => format!("0x{}", hex::encode(data)),
+    }
+}
+
 pub fn handle_expect_revert(
     is_create: bool,
     expected_revert: &Bytes,
     status: Return,
     retdata: Bytes,
+    errors: Option<&Abi>,
 ) -> Result<(Option<Address>, Bytes), Bytes> {
     if matches!(status, return_ok!()) {
         return Err("Call did not revert as expected".to_string().encode().into())
@@ -68,13 +83,11 @@ pub fn handle_expect_revert(
 
             (
                 format!(
-                    "Error != expected error: '{}' != '{}'",
+                    "Error != expected error: '{}' != {}",
                     String::from_utf8(decoded_data.to_vec())
                         .ok()
                         .unwrap_or_else(|| hex::encode(&decoded_data)),
-                    String::from_utf8(expected_revert.to_vec())
-                        .ok()
-                        .unwrap_or_else(|| hex::encode(&expected_revert))
+                    format_revert_data(expected_revert, errors)
                 )
                 .encode()
                 .into(),
@@ -83,9 +96,9 @@ pub fn handle_expect_revert(
         }
         _ => (
             format!(
-                "Error != expected error: 0x{} != 0x{}",
-                hex::encode(&retdata),
-                hex::encode(&expected_revert)
+                "Error != expected error: {} != {}",
+                format_revert_data(&retdata, errors),
+                format_revert_data(expected_revert, errors)
             )
             .encode()
             .into(),
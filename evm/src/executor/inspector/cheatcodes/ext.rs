@@ -1,11 +1,20 @@
+use super::Cheatcodes;
 use crate::abi::HEVMCalls;
 use bytes::Bytes;
 use ethers::{
     abi::{self, AbiEncode, Token},
     prelude::{artifacts::CompactContractBytecode, ProjectPathsConfig},
+    types::{Address, H256, U256},
 };
 use serde::Deserialize;
-use std::{fs::File, io::Read, path::Path, process::Command};
+use serde_json::Value;
+use std::{
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+    process::Command,
+    str::FromStr,
+};
 
 fn ffi(args: &[String]) -> Result<Bytes, Bytes> {
     let output = Command::new(&args[0])
@@ -76,16 +85,236 @@ fn get_code(path: &str) -> Result<Bytes, Bytes> {
     }
 }
 
-pub fn apply(ffi_enabled: bool, call: &HEVMCalls) -> Option<Result<Bytes, Bytes>> {
+/// Resolves `path` and makes sure that it's located in one of the allowed paths.
+///
+/// An existing path is resolved entirely, so a symlink can't point outside of the allowed paths.
+/// For a file that does not exist yet only the parent directory is resolved.
+fn ensure_path_allowed(state: &Cheatcodes, path: &str) -> Result<PathBuf, Bytes> {
+    let path = Path::new(path);
+    let resolved = match (path.parent(), path.file_name()) {
+        (Some(parent), Some(file_name)) if std::fs::symlink_metadata(path).is_err() => {
+            let parent = if parent.as_os_str().is_empty() { Path::new(".") } else { parent };
+            parent.canonicalize().map(|parent| parent.join(file_name))
+        }
+        _ => path.canonicalize(),
+    }
+    .map_err(|err| format!("Failed to resolve {}: {}", path.display(), err).encode())?;
+
+    if state.fs_allowed_paths.iter().any(|allowed| resolved.starts_with(allowed)) {
+        Ok(resolved)
+    } else {
+        Err(format!(
+            "Access to {} is not allowed, add it to `fs_allowed_paths` in foundry.toml",
+            path.display()
+        )
+        .encode()
+        .into())
+    }
+}
+
+fn read_file(state: &Cheatcodes, path: &str) -> Result<Bytes, Bytes> {
+    let path = ensure_path_allowed(state, path)?;
+    let data = std::fs::read_to_string(path).map_err(|err| err.to_string().encode())?;
+
+    Ok(abi::encode(&[Token::String(data)]).into())
+}
+
+/// Reads the next line of the file, returns an empty string once the end of the file is reached
+fn read_line(state: &mut Cheatcodes, path: &str) -> Result<Bytes, Bytes> {
+    let path = ensure_path_allowed(state, path)?;
+    let data = std::fs::read_to_string(&path).map_err(|err| err.to_string().encode())?;
+
+    let index = state.read_lines.entry(path).or_default();
+    let line = data.lines().nth(*index).unwrap_or_default().to_string();
+    *index += 1;
+
+    Ok(abi::encode(&[Token::String(line)]).into())
+}
+
+fn write_file(state: &mut Cheatcodes, path: &str, data: &str) -> Result<Bytes, Bytes> {
+    let path = ensure_path_allowed(state, path)?;
+    std::fs::write(&path, data).map_err(|err| err.to_string().encode())?;
+
+    // Start reading lines of the new content from the beginning
+    state.read_lines.remove(&path);
+
+    Ok(Bytes::new())
+}
+
+/// Reads the environment variable `key` and parses it with `parse`
+fn env<T>(key: &str, parse: impl FnOnce(&str) -> Option<T>) -> Result<T, Bytes> {
+    let value = std::env::var(key)
+        .map_err(|err| format!("Failed to get environment variable `{}`: {}", key, err).encode())?;
+    parse(value.trim()).ok_or_else(|| {
+        format!("Failed to parse environment variable `{}` with value `{}`", key, value)
+            .encode()
+            .into()
+    })
+}
+
+fn env_token(call: &HEVMCalls) -> Option<Result<Token, Bytes>> {
+    Some(match call {
+        HEVMCalls::EnvBool(inner) => {
+            env(&inner.0, |value| value.to_lowercase().parse().ok()).map(Token::Bool)
+        }
+        HEVMCalls::EnvUint(inner) => env(&inner.0, |value| match value.strip_prefix("0x") {
+            Some(hex) => U256::from_str_radix(hex, 16).ok(),
+            None => U256::from_dec_str(value).ok(),
+        })
+        .map(Token::Uint),
+        HEVMCalls::EnvAddress(inner) => {
+            env(&inner.0, |value| Address::from_str(value).ok()).map(Token::Address)
+        }
+        HEVMCalls::EnvBytes32(inner) => env(&inner.0, |value| H256::from_str(value).ok())
+            .map(|value| Token::FixedBytes(value.as_bytes().to_vec())),
+        HEVMCalls::EnvString(inner) => {
+            env(&inner.0, |value| Some(value.to_string())).map(Token::String)
+        }
+        _ => return None,
+    })
+}
+
+/// Converts a JSON value into the token it's ABI-encoded as.
+///
+/// Objects are encoded as tuples of their values, ordered alphabetically by key. Hex strings are
+/// encoded as addresses, `bytes32` or `bytes` depending on their length and decimal strings as
+/// `uint256`, so numbers that don't fit into JSON numbers can be used as well.
+fn json_to_token(value: &Value) -> Result<Token, String> {
+    Ok(match value {
+        Value::Null => return Err("Null values can't be encoded".to_string()),
+        Value::Bool(b) => Token::Bool(*b),
+        Value::Number(number) => {
+            if let Some(n) = number.as_u64() {
+                Token::Uint(n.into())
+            } else if let Some(n) = number.as_i64() {
+                Token::Int(ethers::types::I256::from(n).into_raw())
+            } else {
+                return Err(format!(
+                    "Number {} can't be represented exactly, use a string instead",
+                    number
+                ))
+            }
+        }
+        Value::String(s) => match s.strip_prefix("0x").map(hex::decode) {
+            Some(Ok(bytes)) if bytes.len() == 20 => Token::Address(Address::from_slice(&bytes)),
+            Some(Ok(bytes)) if bytes.len() == 32 => Token::FixedBytes(bytes),
+            Some(Ok(bytes)) => Token::Bytes(bytes),
+            _ => match U256::from_dec_str(s) {
+                Ok(n) => Token::Uint(n),
+                Err(_) => Token::String(s.clone()),
+            },
+        },
+        Value::Array(values) => {
+            Token::Array(values.iter().map(json_to_token).collect::<Result<_, _>>()?)
+        }
+        Value::Object(map) => {
+            let mut entries = map.iter().collect::<Vec<_>>();
+            entries.sort_by_key(|(key, _)| *key);
+            Token::Tuple(
+                entries
+                    .into_iter()
+                    .map(|(_, value)| json_to_token(value))
+                    .collect::<Result<_, _>>()?,
+            )
+        }
+    })
+}
+
+/// Selects the value at `key` in the JSON `value`.
+///
+/// The key is a path of object keys and array indices, e.g. `.a.b[0]`. An empty key or `.`
+/// selects the whole value.
+fn select_json<'a>(value: &'a Value, key: &str) -> Result<&'a Value, String> {
+    let key = key.strip_prefix('$').unwrap_or(key);
+    key.split('.').filter(|part| !part.is_empty()).try_fold(value, |value, part| {
+        let (name, indices) = part.split_once('[').map_or((part, ""), |(name, rest)| (name, rest));
+        let mut value = if name.is_empty() {
+            value
+        } else {
+            value.get(name).ok_or_else(|| format!("Key `{}` not found", name))?
+        };
+        for index in indices.split('[').filter(|index| !index.is_empty()) {
+            let index = index
+                .strip_suffix(']')
+                .and_then(|index| index.parse::<usize>().ok())
+                .ok_or_else(|| format!("Invalid index in `{}`", part))?;
+            value = value.get(index).ok_or_else(|| format!("Index {} out of bounds", index))?;
+        }
+        Ok(value)
+    })
+}
+
+fn parse_json(json: &str, key: &str) -> Result<Bytes, Bytes> {
+    let value: Value = serde_json::from_str(json).map_err(|err| err.to_string().encode())?;
+    let token = select_json(&value, key)
+        .and_then(json_to_token)
+        .map_err(|err| format!("Failed to parse JSON at `{}`: {}", key, err).encode())?;
+    let encoded = abi::encode(&[token]);
+
+    Ok(abi::encode(&[Token::Bytes(encoded)]).into())
+}
+
+pub fn apply(state: &mut Cheatcodes, call: &HEVMCalls) -> Option<Result<Bytes, Bytes>> {
+    if let Some(token) = env_token(call) {
+        return Some(token.map(|token| abi::encode(&[token]).into()))
+    }
+
     Some(match call {
         HEVMCalls::Ffi(inner) => {
-            if !ffi_enabled {
+            if !state.ffi {
                 Err("FFI disabled: run again with `--ffi` if you want to allow tests to call external scripts.".to_string().encode().into())
             } else {
                 ffi(&inner.0)
             }
         }
         HEVMCalls::GetCode(inner) => get_code(&inner.0),
+        HEVMCalls::ReadFile(inner) => read_file(state, &inner.0),
+        HEVMCalls::ReadLine(inner) => read_line(state, &inner.0),
+        HEVMCalls::WriteFile(inner) => write_file(state, &inner.0, &inner.1),
+        HEVMCalls::ParseJson(inner) => parse_json(&inner.0, &inner.1),
         _ => return None,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cheats(allowed: PathBuf) -> Cheatcodes {
+        let mut cheats = Cheatcodes::default();
+        cheats.set_fs_allowed_paths(vec![allowed]);
+        cheats
+    }
+
+    #[test]
+    fn allows_paths_in_non_canonical_allowed_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("allowed")).unwrap();
+        std::fs::create_dir_all(dir.path().join("other")).unwrap();
+        let cheats = cheats(dir.path().join("other/../allowed"));
+
+        let file = dir.path().join("allowed/file.txt");
+        assert!(ensure_path_allowed(&cheats, file.to_str().unwrap()).is_ok());
+
+        let file = dir.path().join("other/file.txt");
+        assert!(ensure_path_allowed(&cheats, file.to_str().unwrap()).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_symlinks_out_of_allowed_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("allowed")).unwrap();
+        std::fs::write(dir.path().join("secret.txt"), "secret").unwrap();
+        let cheats = cheats(dir.path().join("allowed"));
+
+        let link = dir.path().join("allowed/link.txt");
+        std::os::unix::fs::symlink(dir.path().join("secret.txt"), &link).unwrap();
+        assert!(ensure_path_allowed(&cheats, link.to_str().unwrap()).is_err());
+
+        // a dangling symlink can't be used to create a file outside of the allowed paths either
+        let link = dir.path().join("allowed/dangling.txt");
+        std::os::unix::fs::symlink(dir.path().join("new.txt"), &link).unwrap();
+        assert!(ensure_path_allowed(&cheats, link.to_str().unwrap()).is_err());
+    }
+}
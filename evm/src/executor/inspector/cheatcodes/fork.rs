@@ -0,0 +1,109 @@
+use crate::{abi::HEVMCalls, executor::db::DatabaseExt};
+use bytes::Bytes;
+use ethers::{
+    abi::AbiEncode,
+    types::{Address, U256},
+    utils::keccak256,
+};
+use revm::{EVMData, KECCAK_EMPTY};
+
+/// Updates the journaled accounts to their state in the database after the database switched to
+/// another state.
+///
+/// The changes are made through the journal, so they are undone if the current call reverts.
+/// Persistent accounts are skipped if `skip_persistent` is true.
+pub fn reload_journaled_accounts<DB: DatabaseExt>(
+    data: &mut EVMData<'_, DB>,
+    skip_persistent: bool,
+) {
+    let addresses: Vec<Address> = data.subroutine.state().keys().copied().collect();
+    for address in addresses {
+        if skip_persistent && data.db.is_persistent(&address) {
+            continue
+        }
+
+        let info = data.db.basic(address);
+        let account = data.subroutine.account(address).clone();
+
+        if info.balance > account.info.balance {
+            data.subroutine.balance_add(address, info.balance - account.info.balance);
+        } else if info.balance < account.info.balance {
+            data.subroutine.balance_sub(address, account.info.balance - info.balance);
+        }
+
+        let code = info.code.unwrap_or_default();
+        let hash = if code.is_empty() { KECCAK_EMPTY } else { keccak256(&code).into() };
+        if hash != account.info.code_hash {
+            data.subroutine.set_code(address, code, hash);
+        }
+
+        // Only the storage slots that were accessed are journaled, all others are read from the
+        // database
+        for slot in account.storage.keys() {
+            let value = data.db.storage(address, *slot);
+            if data.subroutine.sload(address, *slot, data.db).0 != value {
+                data.subroutine.sstore(address, *slot, value, data.db);
+            }
+        }
+
+        data.subroutine.state().get_mut(&address).unwrap().info.nonce = info.nonce;
+    }
+}
+
+/// Makes the fork with the given id the active one and loads the state of the journaled accounts
+/// from it
+fn select<DB: DatabaseExt>(
+    data: &mut EVMData<'_, DB>,
+    caller: Address,
+    id: U256,
+) -> Result<Bytes, Bytes> {
+    // The test contract and the sender keep their state across forks
+    data.db.add_persistent_account(caller);
+    data.db.add_persistent_account(data.env.tx.caller);
+
+    let depth = data.subroutine.depth();
+    data.db
+        .select_fork(id, data.subroutine.state(), data.env, depth)
+        .map_err(|err| err.to_string().encode())?;
+    reload_journaled_accounts(data, true);
+    Ok(Bytes::new())
+}
+
+/// Moves the fork with the given id, or the active fork, to another block, discarding its local
+/// state
+fn roll<DB: DatabaseExt>(
+    data: &mut EVMData<'_, DB>,
+    id: Option<U256>,
+    block: U256,
+) -> Result<Bytes, Bytes> {
+    let depth = data.subroutine.depth();
+    let active =
+        data.db.roll_fork(id, block, data.env, depth).map_err(|err| err.to_string().encode())?;
+    if active {
+        reload_journaled_accounts(data, true);
+    }
+    Ok(Bytes::new())
+}
+
+pub fn apply<DB: DatabaseExt>(
+    data: &mut EVMData<'_, DB>,
+    caller: Address,
+    call: &HEVMCalls,
+) -> Option<Result<Bytes, Bytes>> {
+    Some(match call {
+        HEVMCalls::CreateFork0(inner) => data
+            .db
+            .create_fork(inner.0.clone(), None, data.env)
+            .map(|id| id.encode().into())
+            .map_err(|err| err.to_string().encode().into()),
+        HEVMCalls::CreateFork1(inner) => u64::try_from(inner.1)
+            .map_err(|_| eyre::eyre!("Block number {} is too large", inner.1))
+            .and_then(|block| data.db.create_fork(inner.0.clone(), Some(block), data.env))
+            .map(|id| id.encode().into())
+            .map_err(|err| err.to_string().encode().into()),
+        HEVMCalls::SelectFork(inner) => select(data, caller, inner.0),
+        HEVMCalls::RollFork0(inner) => roll(data, None, inner.0),
+        HEVMCalls::RollFork1(inner) => roll(data, Some(inner.0), inner.1),
+        _ => return None,
+    })
+}
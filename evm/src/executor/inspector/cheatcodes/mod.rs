@@ -1,31 +1,42 @@
 /// Cheatcodes related to the execution environment.
 mod env;
-pub use env::{Prank, RecordAccess};
+pub use env::{Broadcast, Prank, RecordAccess, RecordedLog};
 /// Assertion helpers (such as `expectEmit`)
 mod expect;
 pub use expect::{ExpectedCallData, ExpectedEmit, ExpectedRevert, MockCallDataContext};
 /// Cheatcodes that interact with the external environment (FFI etc.)
 mod ext;
+/// Cheatcodes that create and switch between forks
+mod fork;
 /// Cheatcodes that configure the fuzzer
 mod fuzz;
+/// Cheatcodes that snapshot and restore the state of the EVM
+mod snapshot;
 /// Utility cheatcodes (`sign` etc.)
 mod util;
 
 use self::expect::{handle_expect_emit, handle_expect_revert};
 use crate::{
     abi::HEVMCalls,
-    executor::{CHEATCODE_ADDRESS, HARDHAT_CONSOLE_ADDRESS},
+    executor::{db::DatabaseExt, CHEATCODE_ADDRESS, HARDHAT_CONSOLE_ADDRESS},
 };
 use bytes::Bytes;
 use ethers::{
-    abi::{AbiDecode, AbiEncode, RawLog},
-    types::{Address, H256, U256},
+    abi::{Abi, AbiDecode, AbiEncode, RawLog},
+    types::{
+        transaction::eip2718::TypedTransaction, Address, NameOrAddress, TransactionRequest, H256,
+        U256,
+    },
 };
 use revm::{
-    opcode, BlockEnv, CallInputs, CreateInputs, Database, EVMData, Gas, Inspector, Interpreter,
-    Return,
+    opcode, return_ok, BlockEnv, CallInputs, CreateInputs, CreateScheme, EVMData, Gas, Inspector,
+    Interpreter, Return,
+};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    path::PathBuf,
+    sync::Arc,
 };
-use std::collections::BTreeMap;
 
 /// An inspector that handles calls to various cheatcodes, each with their own behavior.
 ///
@@ -36,6 +47,12 @@ pub struct Cheatcodes {
     /// Whether FFI is enabled or not
     pub ffi: bool,
 
+    /// Paths the filesystem cheatcodes are allowed to access
+    pub fs_allowed_paths: Vec<PathBuf>,
+
+    /// The index of the next line to read with `readLine` for each file
+    pub read_lines: BTreeMap<PathBuf, usize>,
+
     /// The block environment
     ///
     /// Used in the cheatcode handler to overwrite the block environment separately from the
@@ -60,6 +77,9 @@ pub struct Cheatcodes {
     /// Recorded storage reads and writes
     pub accesses: Option<RecordAccess>,
 
+    /// Logs emitted since `recordLogs` was called
+    pub recorded_logs: Option<Vec<RecordedLog>>,
+
     /// Mocked calls
     pub mocked_calls: BTreeMap<Address, BTreeMap<MockCallDataContext, Bytes>>,
 
@@ -68,6 +88,15 @@ pub struct Cheatcodes {
 
     /// Expected emits
     pub expected_emits: Vec<ExpectedEmit>,
+
+    /// Current broadcasting information
+    pub broadcast: Option<Broadcast>,
+
+    /// Transactions recorded while broadcasting, in the order they were made
+    pub broadcastable_transactions: VecDeque<TypedTransaction>,
+
+    /// Custom errors of the known contracts, used to decode unexpected reverts
+    pub errors: Option<Arc<Abi>>,
 }
 
 impl Cheatcodes {
@@ -75,7 +104,16 @@ impl Cheatcodes {
         Self { ffi, block: Some(block), gas_price: Some(gas_price), ..Default::default() }
     }
 
-    fn apply_cheatcode<DB: Database>(
+    /// Sets the paths the filesystem cheatcodes are allowed to access.
+    ///
+    /// The paths are canonicalized, so they can be compared to the resolved paths the cheatcodes
+    /// are called with. Paths that don't exist are kept as they are.
+    pub fn set_fs_allowed_paths(&mut self, paths: Vec<PathBuf>) {
+        self.fs_allowed_paths =
+            paths.into_iter().map(|path| path.canonicalize().unwrap_or(path)).collect();
+    }
+
+    fn apply_cheatcode<DB: DatabaseExt>(
         &mut self,
         data: &mut EVMData<'_, DB>,
         caller: Address,
@@ -89,20 +127,22 @@ impl Cheatcodes {
             .or_else(|| util::apply(self, data, &decoded))
             .or_else(|| expect::apply(self, data, &decoded))
             .or_else(|| fuzz::apply(data, &decoded))
-            .or_else(|| ext::apply(self.ffi, &decoded))
+            .or_else(|| fork::apply(data, caller, &decoded))
+            .or_else(|| ext::apply(self, &decoded))
+            .or_else(|| snapshot::apply(data, &decoded))
             .ok_or_else(|| "Cheatcode was unhandled. This is a bug.".to_string().encode())?
     }
 }
 
 impl<DB> Inspector<DB> for Cheatcodes
 where
-    DB: Database,
+    DB: DatabaseExt,
 {
     fn call(
         &mut self,
         data: &mut EVMData<'_, DB>,
         call: &mut CallInputs,
-        _: bool,
+        is_static: bool,
     ) -> (Return, Gas, Bytes) {
         if call.contract == CHEATCODE_ADDRESS {
             match self.apply_cheatcode(data, call.context.caller, call) {
@@ -156,6 +196,40 @@ where
                 }
             }
 
+            // Record the call as a transaction if we are broadcasting. Calls that can not modify
+            // state do not need to be broadcasted
+            if let Some(broadcast) = &self.broadcast {
+                if !is_static &&
+                    data.subroutine.depth() == broadcast.depth &&
+                    call.context.caller == broadcast.original_caller
+                {
+                    // The transaction is sent by an EOA, so we set both `msg.sender` and
+                    // `tx.origin`
+                    call.context.caller = broadcast.new_origin;
+                    call.transfer.source = broadcast.new_origin;
+                    data.env.tx.caller = broadcast.new_origin;
+
+                    data.subroutine.load_account(broadcast.new_origin, data.db);
+
+                    // we can safely unwrap because `load_account` inserts the account
+                    let account = data.subroutine.state().get_mut(&broadcast.new_origin).unwrap();
+                    self.broadcastable_transactions.push_back(TypedTransaction::Legacy(
+                        TransactionRequest {
+                            from: Some(broadcast.new_origin),
+                            to: Some(NameOrAddress::Address(call.contract)),
+                            value: Some(call.transfer.value),
+                            data: Some(call.input.clone().into()),
+                            nonce: Some(account.info.nonce.into()),
+                            ..Default::default()
+                        },
+                    ));
+
+                    // The broadcast call becomes a transaction that increments the nonce of the
+                    // sender, but executing it as a call doesn't, so we increment it manually
+                    account.info.nonce += 1;
+                }
+            }
+
             (Return::Continue, Gas::new(call.gas_limit), Bytes::new())
         } else {
             (Return::Continue, Gas::new(call.gas_limit), Bytes::new())
@@ -223,6 +297,15 @@ where
                 address,
             );
         }
+
+        // Record the log if `recordLogs` has been called
+        if let Some(recorded_logs) = &mut self.recorded_logs {
+            recorded_logs.push(RecordedLog {
+                topics: topics.to_vec(),
+                data: data.clone(),
+                emitter: *address,
+            });
+        }
     }
 
     fn call_end(
@@ -232,12 +315,28 @@ where
         remaining_gas: Gas,
         status: Return,
         retdata: Bytes,
-        _: bool,
+        is_static: bool,
     ) -> (Return, Gas, Bytes) {
         if call.contract == CHEATCODE_ADDRESS || call.contract == HARDHAT_CONSOLE_ADDRESS {
             return (status, remaining_gas, retdata)
         }
 
+        // The journaled changes of a reverted call are undone, so are the forks it selected and
+        // the snapshots it reverted to
+        if !matches!(status, return_ok!()) {
+            data.db.revert_switches(data.subroutine.depth(), data.env);
+        }
+
+        // Clean up broadcasts
+        if let Some(broadcast) = &self.broadcast {
+            if !is_static && data.subroutine.depth() == broadcast.depth {
+                data.env.tx.caller = broadcast.original_origin;
+                if broadcast.single_call {
+                    std::mem::take(&mut self.broadcast);
+                }
+            }
+        }
+
         // Clean up pranks
         if let Some(prank) = &self.prank {
             if data.subroutine.depth() == prank.depth {
@@ -252,7 +351,13 @@ where
         if let Some(expected_revert) = &self.expected_revert {
             if data.subroutine.depth() <= expected_revert.depth {
                 let expected_revert = std::mem::take(&mut self.expected_revert).unwrap();
-                return match handle_expect_revert(false, &expected_revert.reason, status, retdata) {
+                return match handle_expect_revert(
+                    false,
+                    &expected_revert.reason,
+                    status,
+                    retdata,
+                    self.errors.as_deref(),
+                ) {
                     Err(retdata) => (Return::Revert, remaining_gas, retdata),
                     Ok((_, retdata)) => (Return::Return, remaining_gas, retdata),
                 }
@@ -332,6 +437,42 @@ where
             }
         }
 
+        // Record the deployment as a transaction if we are broadcasting
+        if let Some(broadcast) = &self.broadcast {
+            if data.subroutine.depth() == broadcast.depth &&
+                call.caller == broadcast.original_caller
+            {
+                // An EOA can only deploy contracts using `CREATE`
+                if let CreateScheme::Create2 { .. } = call.scheme {
+                    return (
+                        Return::Revert,
+                        None,
+                        Gas::new(call.gas_limit),
+                        "CREATE2 is not supported for broadcasted transactions"
+                            .to_string()
+                            .encode()
+                            .into(),
+                    )
+                }
+
+                call.caller = broadcast.new_origin;
+                data.env.tx.caller = broadcast.new_origin;
+
+                data.subroutine.load_account(broadcast.new_origin, data.db);
+                let nonce = data.subroutine.account(broadcast.new_origin).info.nonce;
+                self.broadcastable_transactions.push_back(TypedTransaction::Legacy(
+                    TransactionRequest {
+                        from: Some(broadcast.new_origin),
+                        to: None,
+                        value: Some(call.value),
+                        data: Some(call.init_code.clone().into()),
+                        nonce: Some(nonce.into()),
+                        ..Default::default()
+                    },
+                ));
+            }
+        }
+
         (Return::Continue, None, Gas::new(call.gas_limit), Bytes::new())
     }
 
@@ -344,6 +485,22 @@ where
         remaining_gas: Gas,
         retdata: Bytes,
     ) -> (Return, Option<Address>, Gas, Bytes) {
+        // The journaled changes of a reverted create are undone, so are the forks it selected and
+        // the snapshots it reverted to
+        if !matches!(status, return_ok!()) {
+            data.db.revert_switches(data.subroutine.depth(), data.env);
+        }
+
+        // Clean up broadcasts
+        if let Some(broadcast) = &self.broadcast {
+            if data.subroutine.depth() == broadcast.depth {
+                data.env.tx.caller = broadcast.original_origin;
+                if broadcast.single_call {
+                    std::mem::take(&mut self.broadcast);
+                }
+            }
+        }
+
         // Clean up pranks
         if let Some(prank) = &self.prank {
             if data.subroutine.depth() == prank.depth {
@@ -358,7 +515,13 @@ where
         if let Some(expected_revert) = &self.expected_revert {
             if data.subroutine.depth() <= expected_revert.depth {
                 let expected_revert = std::mem::take(&mut self.expected_revert).unwrap();
-                return match handle_expect_revert(true, &expected_revert.reason, status, retdata) {
+                return match handle_expect_revert(
+                    true,
+                    &expected_revert.reason,
+                    status,
+                    retdata,
+                    self.errors.as_deref(),
+                ) {
                     Err(retdata) => (Return::Revert, None, remaining_gas, retdata),
                     Ok((address, retdata)) => (Return::Return, address, remaining_gas, retdata),
                 }
@@ -0,0 +1,30 @@
+use super::fork::reload_journaled_accounts;
+use crate::{abi::HEVMCalls, executor::db::DatabaseExt};
+use bytes::Bytes;
+use ethers::{abi::AbiEncode, types::U256};
+use revm::EVMData;
+
+/// Restores the snapshot with the given id and returns whether it exists
+fn revert_to<DB: DatabaseExt>(data: &mut EVMData<'_, DB>, id: U256) -> Bytes {
+    let depth = data.subroutine.depth();
+    if !data.db.revert_to(id, data.env, depth) {
+        return false.encode().into()
+    }
+
+    // Persistent accounts are restored as well
+    reload_journaled_accounts(data, false);
+    true.encode().into()
+}
+
+pub fn apply<DB: DatabaseExt>(
+    data: &mut EVMData<'_, DB>,
+    call: &HEVMCalls,
+) -> Option<Result<Bytes, Bytes>> {
+    Some(match call {
+        HEVMCalls::Snapshot(_) => {
+            Ok(data.db.snapshot(data.subroutine.state(), data.env).encode().into())
+        }
+        HEVMCalls::RevertTo(inner) => Ok(revert_to(data, inner.0)),
+        _ => return None,
+    })
+}
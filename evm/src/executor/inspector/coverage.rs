@@ -0,0 +1,66 @@
+use crate::coverage::{HitMap, HitMaps};
+use ethers::{types::H256, utils::keccak256};
+use revm::{opcode, Database, EVMData, Inspector, Interpreter, Return};
+use std::collections::BTreeMap;
+
+/// An inspector that records the program counters hit during execution, keyed by the hash of the
+/// executed bytecode.
+#[derive(Default, Debug)]
+pub struct CoverageCollector {
+    /// The collected hit maps
+    pub maps: HitMaps,
+    /// A mapping of the location of the code of a running interpreter to the hash of that code.
+    ///
+    /// Hashing the code on every step would be prohibitively slow, so we hash it once when the
+    /// interpreter is initialized. The location of the code is stable for the lifetime of the
+    /// interpreter, and a new interpreter always overwrites any stale entry before its first step.
+    code_hashes: BTreeMap<usize, H256>,
+}
+
+impl<DB> Inspector<DB> for CoverageCollector
+where
+    DB: Database,
+{
+    fn initialize_interp(
+        &mut self,
+        interpreter: &mut Interpreter,
+        _: &mut EVMData<'_, DB>,
+        _: bool,
+    ) -> Return {
+        let code = &interpreter.contract.code;
+        let hash = H256::from(keccak256(code));
+        self.code_hashes.insert(code.as_ptr() as usize, hash);
+        self.maps.0.entry(hash).or_insert_with(|| HitMap::new(code.clone()));
+
+        Return::Continue
+    }
+
+    fn step(
+        &mut self,
+        interpreter: &mut Interpreter,
+        _: &mut EVMData<'_, DB>,
+        _is_static: bool,
+    ) -> Return {
+        let hash = match self.code_hashes.get(&(interpreter.contract.code.as_ptr() as usize)) {
+            Some(hash) => hash,
+            None => return Return::Continue,
+        };
+        let map = match self.maps.0.get_mut(hash) {
+            Some(map) => map,
+            None => return Return::Continue,
+        };
+
+        let pc = interpreter.program_counter();
+        map.hit(pc);
+
+        // Record which way the branch went; the condition is the second item on the stack
+        if interpreter.contract.code[pc] == opcode::JUMPI {
+            let stack = interpreter.stack().data();
+            if stack.len() >= 2 {
+                map.branch(pc, !stack[stack.len() - 2].is_zero());
+            }
+        }
+
+        Return::Continue
+    }
+}
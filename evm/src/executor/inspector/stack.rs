@@ -1,9 +1,11 @@
-use super::{Cheatcodes, Debugger, LogCollector, Tracer};
-use crate::{debug::DebugArena, trace::CallTraceArena};
+use super::{Cheatcodes, CoverageCollector, Debugger, LogCollector, Tracer};
+use crate::{
+    coverage::HitMaps, debug::DebugArena, executor::db::DatabaseExt, trace::CallTraceArena,
+};
 use bytes::Bytes;
-use ethers::types::{Address, Log, H256};
-use revm::{db::Database, CallInputs, CreateInputs, EVMData, Gas, Inspector, Interpreter, Return};
-use std::collections::BTreeMap;
+use ethers::types::{transaction::eip2718::TypedTransaction, Address, Log, H256};
+use revm::{CallInputs, CreateInputs, EVMData, Gas, Inspector, Interpreter, Return};
+use std::collections::{BTreeMap, VecDeque};
 
 /// Helper macro to call the same method on multiple inspectors without resorting to dynamic
 /// dispatch
@@ -22,6 +24,8 @@ pub struct InspectorData {
     pub labels: BTreeMap<Address, String>,
     pub traces: Option<CallTraceArena>,
     pub debug: Option<DebugArena>,
+    pub coverage: Option<HitMaps>,
+    pub transactions: Option<VecDeque<TypedTransaction>>,
     pub cheatcodes: Option<Cheatcodes>,
 }
 
@@ -35,10 +39,11 @@ pub struct InspectorStack {
     pub logs: Option<LogCollector>,
     pub cheatcodes: Option<Cheatcodes>,
     pub debugger: Option<Debugger>,
+    pub coverage: Option<CoverageCollector>,
 }
 
 impl InspectorStack {
-    pub fn collect_inspector_states(self) -> InspectorData {
+    pub fn collect_inspector_states(mut self) -> InspectorData {
         InspectorData {
             logs: self.logs.map(|logs| logs.logs).unwrap_or_default(),
             labels: self
@@ -48,6 +53,11 @@ impl InspectorStack {
                 .unwrap_or_default(),
             traces: self.tracer.map(|tracer| tracer.traces),
             debug: self.debugger.map(|debugger| debugger.arena),
+            coverage: self.coverage.map(|coverage| coverage.maps),
+            transactions: self
+                .cheatcodes
+                .as_mut()
+                .map(|cheatcodes| std::mem::take(&mut cheatcodes.broadcastable_transactions)),
             cheatcodes: self.cheatcodes,
         }
     }
@@ -55,7 +65,7 @@ impl InspectorStack {
 
 impl<DB> Inspector<DB> for InspectorStack
 where
-    DB: Database,
+    DB: DatabaseExt,
 {
     fn initialize_interp(
         &mut self,
@@ -65,7 +75,13 @@ where
     ) -> Return {
         call_inspectors!(
             inspector,
-            [&mut self.debugger, &mut self.tracer, &mut self.logs, &mut self.cheatcodes],
+            [
+                &mut self.debugger,
+                &mut self.coverage,
+                &mut self.tracer,
+                &mut self.logs,
+                &mut self.cheatcodes
+            ],
             {
                 let status = inspector.initialize_interp(interpreter, data, is_static);
 
@@ -87,7 +103,13 @@ where
     ) -> Return {
         call_inspectors!(
             inspector,
-            [&mut self.debugger, &mut self.tracer, &mut self.logs, &mut self.cheatcodes],
+            [
+                &mut self.debugger,
+                &mut self.coverage,
+                &mut self.tracer,
+                &mut self.logs,
+                &mut self.cheatcodes
+            ],
             {
                 let status = inspector.step(interpreter, data, is_static);
 
@@ -29,6 +29,7 @@ impl Tracer {
         value: U256,
         kind: CallKind,
         caller: Address,
+        gas_limit: u64,
     ) {
         self.trace_stack.push(self.traces.push_trace(
             0,
@@ -40,6 +41,7 @@ impl Tracer {
                 value,
                 status: Return::Continue,
                 caller,
+                gas_limit,
                 ..Default::default()
             },
         ));
@@ -84,6 +86,7 @@ where
             call.transfer.value,
             call.context.scheme.into(),
             call.context.caller,
+            call.gas_limit,
         );
 
         (Return::Continue, Gas::new(call.gas_limit), Bytes::new())
@@ -130,6 +133,7 @@ where
             call.value,
             CallKind::Create,
             call.caller,
+            call.gas_limit,
         );
 
         (Return::Continue, None, Gas::new(call.gas_limit), Bytes::new())
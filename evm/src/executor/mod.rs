@@ -14,6 +14,9 @@ pub mod inspector;
 /// Forking provider
 pub mod fork;
 
+/// The database the EVM runs on
+pub mod db;
+
 /// Executor builder
 pub mod builder;
 pub use builder::{ExecutorBuilder, Fork};
@@ -26,13 +29,17 @@ pub use revm::db::DatabaseRef;
 
 pub use revm::Env;
 
-use self::inspector::{InspectorData, InspectorStackConfig};
-use crate::{debug::DebugArena, trace::CallTraceArena, CALLER};
+use self::{
+    db::{ChangedState, ExecutorDb, Snapshot},
+    fork::MultiFork,
+    inspector::{InspectorData, InspectorStackConfig},
+};
+use crate::{coverage::HitMaps, debug::DebugArena, trace::CallTraceArena, CALLER};
 use bytes::Bytes;
 use ethers::{
     abi::{Abi, Detokenize, Tokenize},
     prelude::{decode_function_data, encode_function_data, Address, U256},
-    types::Log,
+    types::{transaction::eip2718::TypedTransaction, Log},
 };
 use eyre::Result;
 use foundry_utils::IntoFunction;
@@ -41,7 +48,7 @@ use revm::{
     db::{CacheDB, DatabaseCommit, EmptyDB},
     return_ok, Account, BlockEnv, CreateScheme, Return, TransactOut, TransactTo, TxEnv, EVM,
 };
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, VecDeque};
 
 /// A mapping of addresses to their changed state.
 pub type StateChangeset = HashMap<Address, Account>;
@@ -60,6 +67,7 @@ pub enum EvmError {
         debug: Option<DebugArena>,
         labels: BTreeMap<Address, String>,
         state_changeset: Option<StateChangeset>,
+        coverage: Option<HitMaps>,
     },
     /// Error which occurred during ABI encoding/decoding
     #[error(transparent)]
@@ -82,6 +90,8 @@ pub struct DeployResult {
     pub traces: Option<CallTraceArena>,
     /// The debug nodes of the call
     pub debug: Option<DebugArena>,
+    /// The coverage info collected during the deployment
+    pub coverage: Option<HitMaps>,
 }
 
 /// The result of a call.
@@ -103,6 +113,10 @@ pub struct CallResult<D: Detokenize> {
     pub traces: Option<CallTraceArena>,
     /// The debug nodes of the call
     pub debug: Option<DebugArena>,
+    /// The coverage info collected during the call
+    pub coverage: Option<HitMaps>,
+    /// The transactions recorded with the `broadcast` cheatcodes during the call
+    pub transactions: Option<VecDeque<TypedTransaction>>,
     /// The changeset of the state.
     ///
     /// This is only present if the changed state was not committed to the database (i.e. if you
@@ -131,6 +145,10 @@ pub struct RawCallResult {
     pub traces: Option<CallTraceArena>,
     /// The debug nodes of the call
     pub debug: Option<DebugArena>,
+    /// The coverage info collected during the call
+    pub coverage: Option<HitMaps>,
+    /// The transactions recorded with the `broadcast` cheatcodes during the call
+    pub transactions: Option<VecDeque<TypedTransaction>>,
     /// The changeset of the state.
     ///
     /// This is only present if the changed state was not committed to the database (i.e. if you
@@ -150,11 +168,14 @@ impl Default for RawCallResult {
             labels: BTreeMap::new(),
             traces: None,
             debug: None,
+            coverage: None,
+            transactions: None,
             state_changeset: None,
         }
     }
 }
 
+#[derive(Clone)]
 pub struct Executor<DB: DatabaseRef> {
     // Note: We do not store an EVM here, since we are really
     // only interested in the database. REVM's `EVM` is a thin
@@ -165,6 +186,10 @@ pub struct Executor<DB: DatabaseRef> {
     // take `&mut self` when we are not committing to the database, since
     // we need to set `evm.env`.
     pub db: CacheDB<DB>,
+    /// The forks created with the `createFork` cheatcode
+    pub forks: MultiFork,
+    /// The snapshots taken with the `snapshot` cheatcode, indexed by their id
+    snapshots: Vec<Snapshot<DB>>,
     env: Env,
     inspector_config: InspectorStackConfig,
     /// The gas limit for calls and deployments. This is different from the gas limit imposed by
@@ -175,7 +200,7 @@ pub struct Executor<DB: DatabaseRef> {
 
 impl<DB> Executor<DB>
 where
-    DB: DatabaseRef,
+    DB: DatabaseRef + Clone,
 {
     pub fn new(
         inner_db: DB,
@@ -192,7 +217,14 @@ where
             revm::AccountInfo { code: Some(Bytes::from_static(&[1])), ..Default::default() },
         );
 
-        Executor { db, env, inspector_config, gas_limit }
+        Executor {
+            db,
+            forks: MultiFork::default(),
+            snapshots: Vec::new(),
+            env,
+            inspector_config,
+            gas_limit,
+        }
     }
 
     /// Set the balance of an account.
@@ -228,11 +260,34 @@ where
         self
     }
 
+    pub fn set_coverage(&mut self, coverage: bool) -> &mut Self {
+        self.inspector_config.coverage = coverage;
+        self
+    }
+
     pub fn set_gas_limit(&mut self, gas_limit: U256) -> &mut Self {
         self.gas_limit = gas_limit;
         self
     }
 
+    /// Commits the state changeset to the local state or the active fork, see [db::commit_to]
+    pub fn commit(&mut self, changeset: StateChangeset) {
+        db::commit_to(&mut self.db, &mut self.forks, changeset)
+    }
+
+    /// Keeps the state that was switched to during a committing call
+    fn apply_changed(&mut self, changed: ChangedState<DB>) {
+        if let Some(db) = changed.local {
+            self.db = db;
+        }
+        if let Some(forks) = changed.forks {
+            self.forks = forks;
+        }
+        if let Some(snapshots) = changed.snapshots {
+            self.snapshots = snapshots;
+        }
+    }
+
     /// Calls the `setUp()` function on a contract.
     pub fn setup(&mut self, address: Address) -> std::result::Result<CallResult<()>, EvmError> {
         self.call_committing::<(), _, _>(*CALLER, address, "setUp()", (), 0.into(), None)
@@ -262,6 +317,8 @@ where
             labels,
             traces,
             debug,
+            coverage,
+            transactions,
             state_changeset,
         } = self.call_raw_committing(from, to, calldata, value)?;
         match status {
@@ -276,6 +333,8 @@ where
                     labels,
                     traces,
                     debug,
+                    coverage,
+                    transactions,
                     state_changeset,
                 })
             }
@@ -292,6 +351,7 @@ where
                     debug,
                     labels,
                     state_changeset,
+                    coverage,
                 })
             }
         }
@@ -312,21 +372,27 @@ where
         // Build VM
         let mut evm = EVM::new();
         evm.env = self.build_env(from, TransactTo::Call(to), calldata, value);
-        evm.database(&mut self.db);
+        let mut db = ExecutorDb::new(&self.db, &self.forks, &self.snapshots);
+        evm.database(&mut db);
 
         // Run the call
         let mut inspector = self.inspector_config.stack();
-        let (status, out, gas, _) = evm.inspect_commit(&mut inspector);
+        let (status, out, gas, state_changeset, _) = evm.inspect(&mut inspector);
         let result = match out {
             TransactOut::Call(data) => data,
             _ => Bytes::default(),
         };
 
-        let InspectorData { logs, labels, traces, debug, cheatcodes } =
+        let InspectorData { logs, labels, traces, debug, coverage, transactions, cheatcodes } =
             inspector.collect_inspector_states();
 
         // Persist the changed block environment
-        self.inspector_config.block = evm.env.block.clone();
+        let block = evm.env.block.clone();
+        drop(evm);
+        let changed = db.into_changed();
+        self.apply_changed(changed);
+        self.commit(state_changeset);
+        self.inspector_config.block = block;
 
         // Persist cheatcode state
         self.inspector_config.cheatcodes = cheatcodes;
@@ -341,6 +407,8 @@ where
             labels,
             traces,
             debug,
+            coverage,
+            transactions,
             state_changeset: None,
         })
     }
@@ -369,6 +437,8 @@ where
             labels,
             traces,
             debug,
+            coverage,
+            transactions,
             state_changeset,
         } = self.call_raw(from, to, calldata, value)?;
         match status {
@@ -383,6 +453,8 @@ where
                     labels,
                     traces,
                     debug,
+                    coverage,
+                    transactions,
                     state_changeset,
                 })
             }
@@ -399,6 +471,7 @@ where
                     debug,
                     labels,
                     state_changeset,
+                    coverage,
                 })
             }
         }
@@ -419,17 +492,18 @@ where
         // Build VM
         let mut evm = EVM::new();
         evm.env = self.build_env(from, TransactTo::Call(to), calldata, value);
-        evm.database(&self.db);
+        let mut db = ExecutorDb::new(&self.db, &self.forks, &self.snapshots);
+        evm.database(&mut db);
 
         // Run the call
         let mut inspector = self.inspector_config.stack();
-        let (status, out, gas, state_changeset, _) = evm.inspect_ref(&mut inspector);
+        let (status, out, gas, state_changeset, _) = evm.inspect(&mut inspector);
         let result = match out {
             TransactOut::Call(data) => data,
             _ => Bytes::default(),
         };
 
-        let InspectorData { logs, labels, traces, debug, .. } =
+        let InspectorData { logs, labels, traces, debug, coverage, transactions, .. } =
             inspector.collect_inspector_states();
         Ok(RawCallResult {
             status,
@@ -441,6 +515,8 @@ where
             labels,
             traces,
             debug,
+            coverage,
+            transactions,
             state_changeset: Some(state_changeset),
         })
     }
@@ -455,12 +531,18 @@ where
     ) -> std::result::Result<DeployResult, EvmError> {
         let mut evm = EVM::new();
         evm.env = self.build_env(from, TransactTo::Create(CreateScheme::Create), code, value);
-        evm.database(&mut self.db);
+        let mut db = ExecutorDb::new(&self.db, &self.forks, &self.snapshots);
+        evm.database(&mut db);
 
         let mut inspector = self.inspector_config.stack();
-        let (status, out, gas, _) = evm.inspect_commit(&mut inspector);
-        let InspectorData { logs, labels, traces, debug, cheatcodes, .. } =
+        let (status, out, gas, state_changeset, _) = evm.inspect(&mut inspector);
+        let InspectorData { logs, labels, traces, debug, coverage, cheatcodes, .. } =
             inspector.collect_inspector_states();
+        let block = evm.env.block.clone();
+        drop(evm);
+        let changed = db.into_changed();
+        self.apply_changed(changed);
+        self.commit(state_changeset);
 
         let result = match out {
             TransactOut::Create(ref data, _) => data.to_owned(),
@@ -482,6 +564,7 @@ where
                         debug,
                         labels,
                         state_changeset: None,
+                        coverage,
                     });
                 }
             }
@@ -498,17 +581,18 @@ where
                     debug,
                     labels,
                     state_changeset: None,
+                    coverage,
                 })
             }
         };
 
         // Persist the changed block environment
-        self.inspector_config.block = evm.env.block.clone();
+        self.inspector_config.block = block;
 
         // Persist cheatcode state
         self.inspector_config.cheatcodes = cheatcodes;
 
-        Ok(DeployResult { address, gas, logs, traces, debug })
+        Ok(DeployResult { address, gas, logs, traces, debug, coverage })
     }
 
     /// Check if a call to a test contract was successful.
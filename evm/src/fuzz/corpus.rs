@@ -0,0 +1,103 @@
+use ethers::{abi::Function, types::Bytes};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The inputs that made a fuzz test fail in previous runs.
+///
+/// The corpus of a test is stored as a JSON array of calldata at
+/// `<corpus dir>/<source>/<Contract>/<signature>.json`, so the failing inputs can be replayed
+/// before new inputs are generated.
+#[derive(Clone, Debug, Default)]
+pub struct FuzzCorpus {
+    /// The file the corpus is stored in
+    path: PathBuf,
+    /// The calldata of all failing inputs
+    inputs: Vec<Bytes>,
+}
+
+impl FuzzCorpus {
+    /// Loads the corpus of the fuzz test `func` in the contract with the artifact identifier
+    /// `<source>:<Contract>` from the corpus directory.
+    ///
+    /// The corpus is empty if the file does not exist yet or is invalid.
+    pub fn load(dir: impl AsRef<Path>, identifier: &str, func: &Function) -> Self {
+        let contract_dir = match identifier.rsplit_once(':') {
+            Some((source, name)) => dir.as_ref().join(source).join(name),
+            None => dir.as_ref().join(identifier),
+        };
+        let path = contract_dir.join(format!("{}.json", func.signature()));
+        let inputs = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|err| {
+                tracing::warn!(?err, ?path, "invalid fuzz corpus");
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        Self { path, inputs }
+    }
+
+    /// Returns the inputs that are valid calldata for the function.
+    ///
+    /// Inputs that don't decode, e.g. of a previous version of the function with different
+    /// parameter types, are skipped.
+    pub fn inputs(&self, func: &Function) -> Vec<Bytes> {
+        let selector = func.short_signature();
+        self.inputs
+            .iter()
+            .filter(|calldata| {
+                let calldata = calldata.as_ref();
+                calldata.len() >= 4 &&
+                    calldata[..4] == selector &&
+                    func.decode_input(&calldata[4..]).is_ok()
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Adds a failing input to the corpus and writes it to disk
+    pub fn add(&mut self, calldata: Bytes) -> io::Result<()> {
+        if self.inputs.contains(&calldata) {
+            return Ok(())
+        }
+        self.inputs.push(calldata);
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(&self.inputs)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::abi::{AbiParser, Token};
+
+    #[test]
+    fn can_persist_corpus() {
+        let dir = tempfile::tempdir().unwrap();
+        let func = AbiParser::default().parse_function("testFuzz(uint256)").unwrap();
+        let other = AbiParser::default().parse_function("testFuzz(address)").unwrap();
+
+        let id = "test/Fuzz.t.sol:FuzzTest";
+
+        let mut corpus = FuzzCorpus::load(dir.path(), id, &func);
+        assert!(corpus.inputs(&func).is_empty());
+
+        let calldata: Bytes = func.encode_input(&[Token::Uint(1000.into())]).unwrap().into();
+        corpus.add(calldata.clone()).unwrap();
+        corpus.add(calldata.clone()).unwrap();
+        assert!(dir.path().join("test/Fuzz.t.sol/FuzzTest/testFuzz(uint256).json").exists());
+
+        let corpus = FuzzCorpus::load(dir.path(), id, &func);
+        assert_eq!(corpus.inputs(&func), vec![calldata]);
+
+        // overloads and contracts with the same name in other files have their own corpus
+        assert!(FuzzCorpus::load(dir.path(), id, &other).inputs(&other).is_empty());
+        assert!(FuzzCorpus::load(dir.path(), "src/Fuzz.t.sol:FuzzTest", &func)
+            .inputs(&func)
+            .is_empty());
+    }
+}
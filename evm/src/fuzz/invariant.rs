@@ -0,0 +1,356 @@
+use super::{
+    strategies::{
+        build_initial_state, collect_state_from_call, fuzz_calldata, fuzz_calldata_from_state,
+        EvmFuzzState,
+    },
+    BaseCounterExample, CounterExample,
+};
+use crate::{
+    coverage::HitMaps,
+    executor::{Executor, RawCallResult},
+};
+use ethers::{
+    abi::{Abi, Function, ParamType, StateMutability, Token},
+    types::{Address, Bytes},
+};
+use proptest::{
+    strategy::{BoxedStrategy, Strategy, Union},
+    test_runner::{TestCaseError, TestError, TestRunner},
+};
+use revm::db::DatabaseRef;
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeMap,
+};
+
+/// Contracts identified as targets during an invariant campaign.
+///
+/// `address -> (contract name, abi)`
+pub type TargetedContracts = BTreeMap<Address, (String, Abi)>;
+
+/// A single call made during an invariant run: `(target address, calldata)`
+pub type BasicTxDetails = (Address, Bytes);
+
+/// Configuration for invariant campaigns
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct InvariantConfig {
+    /// The number of calls executed to attempt to break invariants in one run
+    pub depth: u32,
+    /// Fails the invariant test if a call to a target contract reverts
+    pub fail_on_revert: bool,
+}
+
+impl Default for InvariantConfig {
+    fn default() -> Self {
+        InvariantConfig { depth: 15, fail_on_revert: false }
+    }
+}
+
+/// Wrapper around an [`Executor`] which runs invariant campaigns.
+///
+/// Every run starts from the state the executor is in (i.e. after `setUp`), executes a random
+/// sequence of calls against the targeted contracts and checks all invariants after each call.
+/// Failing sequences are shrunk by [`proptest`] before they are reported.
+pub struct InvariantExecutor<'a, DB: DatabaseRef + Clone> {
+    /// The VM, in the state every run starts from
+    executor: &'a Executor<DB>,
+    /// The fuzzer
+    runner: TestRunner,
+    /// The account that makes all calls
+    sender: Address,
+    /// The invariant campaign configuration
+    config: InvariantConfig,
+}
+
+impl<'a, DB> InvariantExecutor<'a, DB>
+where
+    DB: DatabaseRef + Clone,
+{
+    /// Instantiates an invariant executor given a testrunner
+    pub fn new(
+        executor: &'a Executor<DB>,
+        runner: TestRunner,
+        sender: Address,
+        config: InvariantConfig,
+    ) -> Self {
+        Self { executor, runner, sender, config }
+    }
+
+    /// Runs an invariant campaign against the `targets`, checking every function in `invariants`
+    /// of the test contract at `test_address` after each call.
+    pub fn invariant_fuzz(
+        &self,
+        invariants: &[&Function],
+        test_address: Address,
+        targets: &TargetedContracts,
+        errors: Option<&Abi>,
+    ) -> eyre::Result<InvariantFuzzTestResult> {
+        // The invariants have to hold before the first call is made
+        let broken =
+            assert_invariants(self.executor, self.sender, test_address, invariants, errors);
+        if !broken.is_empty() {
+            return Ok(InvariantFuzzTestResult {
+                invariants: broken
+                    .into_iter()
+                    .map(|(name, reason)| {
+                        (name, InvariantFuzzError { reason, call_sequence: vec![] })
+                    })
+                    .collect(),
+                runs: 0,
+                calls: 0,
+                reverts: 0,
+                coverage: None,
+            })
+        }
+
+        // Stores fuzz state for use with [fuzz_calldata_from_state]
+        let state: EvmFuzzState = build_initial_state(&self.executor.db);
+        let strat = invariant_strat(targets, self.config.depth as usize, state.clone())?;
+
+        let runs = Cell::new(0usize);
+        let calls = Cell::new(0usize);
+        let reverts = Cell::new(0usize);
+        let coverage: RefCell<Option<HitMaps>> = RefCell::default();
+
+        // Stores the last failing sequence together with the invariants it broke
+        let failure: RefCell<Option<(Vec<BasicTxDetails>, BTreeMap<String, Option<String>>)>> =
+            RefCell::new(None);
+
+        tracing::debug!(targets = targets.len(), depth = self.config.depth, "invariant fuzzing");
+        let run_result = self.runner.clone().run(&strat, |sequence| {
+            runs.set(runs.get() + 1);
+
+            // Every run starts from the post-setup state
+            let mut executor = self.executor.clone();
+
+            for (idx, (address, calldata)) in sequence.iter().enumerate() {
+                let RawCallResult {
+                    reverted, result, logs, state_changeset, coverage: call_coverage, ..
+                } = executor
+                    .call_raw(self.sender, *address, calldata.0.clone(), 0.into())
+                    .expect("could not make raw evm call");
+                calls.set(calls.get() + 1);
+                if call_coverage.is_some() {
+                    let mut coverage = coverage.borrow_mut();
+                    *coverage = HitMaps::merge_opt(coverage.take(), call_coverage);
+                }
+
+                let state_changeset = state_changeset.expect("we should have a state changeset");
+                collect_state_from_call(&logs, &state_changeset, state.clone());
+
+                if reverted {
+                    reverts.set(reverts.get() + 1);
+                    if self.config.fail_on_revert {
+                        let reason = foundry_utils::decode_revert(result.as_ref(), errors)
+                            .unwrap_or_default();
+                        let broken = invariants
+                            .iter()
+                            .map(|func| (func.name.clone(), Some(reason.clone())))
+                            .collect();
+                        *failure.borrow_mut() = Some((sequence[..=idx].to_vec(), broken));
+                        return Err(TestCaseError::fail(reason))
+                    }
+                    continue
+                }
+
+                executor.commit(state_changeset);
+
+                let broken =
+                    assert_invariants(&executor, self.sender, test_address, invariants, errors);
+                if !broken.is_empty() {
+                    let reason = broken.values().flatten().next().cloned().unwrap_or_default();
+                    *failure.borrow_mut() = Some((sequence[..=idx].to_vec(), broken));
+                    return Err(TestCaseError::fail(reason))
+                }
+            }
+
+            Ok(())
+        });
+
+        let mut result = InvariantFuzzTestResult {
+            invariants: BTreeMap::new(),
+            runs: runs.get(),
+            calls: calls.get(),
+            reverts: reverts.get(),
+            coverage: coverage.into_inner(),
+        };
+
+        match run_result {
+            Err(TestError::Abort(reason)) => {
+                for func in invariants {
+                    result.invariants.insert(
+                        func.name.clone(),
+                        InvariantFuzzError {
+                            reason: Some(reason.to_string()),
+                            call_sequence: vec![],
+                        },
+                    );
+                }
+            }
+            Err(TestError::Fail(_, _)) => {
+                if let Some((sequence, broken)) = failure.into_inner() {
+                    let call_sequence = sequence
+                        .iter()
+                        .map(|(address, calldata)| to_counterexample(*address, calldata, targets))
+                        .collect::<Vec<_>>();
+                    for (name, reason) in broken {
+                        result.invariants.insert(
+                            name,
+                            InvariantFuzzError { reason, call_sequence: call_sequence.clone() },
+                        );
+                    }
+                }
+            }
+            Ok(_) => (),
+        }
+
+        Ok(result)
+    }
+}
+
+/// The outcome of an invariant campaign
+#[derive(Debug)]
+pub struct InvariantFuzzTestResult {
+    /// The invariants that were broken, `invariant name -> error`
+    pub invariants: BTreeMap<String, InvariantFuzzError>,
+    /// The number of runs that were executed
+    pub runs: usize,
+    /// The number of calls that were made to target contracts
+    pub calls: usize,
+    /// The number of calls to target contracts that reverted
+    pub reverts: usize,
+    /// The coverage info collected during the campaign
+    pub coverage: Option<HitMaps>,
+}
+
+/// A broken invariant
+#[derive(Debug, Clone)]
+pub struct InvariantFuzzError {
+    /// The revert reason of the invariant, if any
+    pub reason: Option<String>,
+    /// The (shrunk) sequence of calls that broke the invariant
+    pub call_sequence: Vec<BaseCounterExample>,
+}
+
+impl InvariantFuzzError {
+    /// Returns the call sequence as a counterexample, if the invariant was broken by a call
+    pub fn counterexample(&self) -> Option<CounterExample> {
+        if self.call_sequence.is_empty() {
+            None
+        } else {
+            Some(CounterExample::Sequence(self.call_sequence.clone()))
+        }
+    }
+}
+
+/// Returns `true` if the function can change the state of the contract, and therefore should be
+/// called during an invariant campaign.
+pub fn is_mutable(func: &Function) -> bool {
+    !matches!(func.state_mutability, StateMutability::Pure | StateMutability::View)
+}
+
+/// Builds the strategy which generates sequences of calls (up to `depth` long) to the mutable
+/// functions of all targeted contracts.
+fn invariant_strat(
+    targets: &TargetedContracts,
+    depth: usize,
+    state: EvmFuzzState,
+) -> eyre::Result<BoxedStrategy<Vec<BasicTxDetails>>> {
+    let calls = targets
+        .iter()
+        .flat_map(|(address, (_, abi))| {
+            let address = *address;
+            let state = state.clone();
+            abi.functions().filter(|func| is_mutable(func)).map(move |func| {
+                // TODO: Make the weights configurable, see [super::FuzzedExecutor::fuzz]
+                Union::new_weighted(vec![
+                    (60, fuzz_calldata(func.clone())),
+                    (40, fuzz_calldata_from_state(func.clone(), state.clone())),
+                ])
+                .prop_map(move |calldata| (address, calldata))
+                .boxed()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if calls.is_empty() {
+        eyre::bail!("No contracts with mutable functions to fuzz.")
+    }
+
+    Ok(proptest::collection::vec(Union::new(calls), 1..=depth.max(1)).boxed())
+}
+
+/// Calls every invariant on the test contract and returns the ones that were broken, together
+/// with their revert reasons.
+///
+/// An invariant is broken if it reverts, if it fails a DSTest assertion or if it returns `false`.
+fn assert_invariants<DB: DatabaseRef + Clone>(
+    executor: &Executor<DB>,
+    sender: Address,
+    test_address: Address,
+    invariants: &[&Function],
+    errors: Option<&Abi>,
+) -> BTreeMap<String, Option<String>> {
+    let mut broken = BTreeMap::new();
+    for func in invariants {
+        let calldata = func.encode_input(&[]).expect("invariants do not take arguments");
+        let call = match executor.call_raw(sender, test_address, calldata.into(), 0.into()) {
+            Ok(call) => call,
+            Err(err) => {
+                broken.insert(func.name.clone(), Some(err.to_string()));
+                continue
+            }
+        };
+
+        let mut success = executor.is_success(
+            test_address,
+            call.reverted,
+            call.state_changeset.clone().expect("we should have a state changeset"),
+            false,
+        );
+
+        // Invariants may also return a boolean instead of using assertions
+        if success && func.outputs.len() == 1 && func.outputs[0].kind == ParamType::Bool {
+            success = matches!(
+                func.decode_output(call.result.as_ref()).as_deref(),
+                Ok([Token::Bool(true)])
+            );
+        }
+
+        if !success {
+            let reason = foundry_utils::decode_revert(call.result.as_ref(), errors)
+                .ok()
+                .filter(|reason| !reason.is_empty());
+            broken.insert(func.name.clone(), reason);
+        }
+    }
+    broken
+}
+
+/// Converts a call made during an invariant run into a counterexample
+fn to_counterexample(
+    address: Address,
+    calldata: &Bytes,
+    targets: &TargetedContracts,
+) -> BaseCounterExample {
+    let (contract_name, func) = targets
+        .get(&address)
+        .map(|(name, abi)| {
+            let func = abi.functions().find(|func| {
+                calldata.len() >= 4 && func.short_signature() == calldata.as_ref()[..4]
+            });
+            (Some(name.clone()), func)
+        })
+        .unwrap_or((None, None));
+
+    BaseCounterExample {
+        address: Some(address),
+        calldata: calldata.clone(),
+        signature: func.map(|func| func.signature()),
+        contract_name,
+        args: func
+            .and_then(|func| func.decode_input(&calldata.as_ref()[4..]).ok())
+            .unwrap_or_default(),
+    }
+}
@@ -1,8 +1,15 @@
 mod strategies;
 
+/// Invariant (stateful) fuzzing
+pub mod invariant;
+
+/// Persistent corpus of failing fuzz inputs
+pub mod corpus;
+
 pub use proptest::test_runner::{Config as FuzzConfig, Reason};
 
 use crate::{
+    coverage::HitMaps,
     executor::{Executor, RawCallResult},
     trace::CallTraceArena,
 };
@@ -34,15 +41,24 @@ pub struct FuzzedExecutor<'a, DB: DatabaseRef> {
     runner: TestRunner,
     /// The account that calls tests
     sender: Address,
+    /// Inputs that are run before any new inputs are generated
+    corpus: Vec<Bytes>,
 }
 
 impl<'a, DB> FuzzedExecutor<'a, DB>
 where
-    DB: DatabaseRef,
+    DB: DatabaseRef + Clone,
 {
     /// Instantiates a fuzzed executor given a testrunner
     pub fn new(executor: &'a Executor<DB>, runner: TestRunner, sender: Address) -> Self {
-        Self { executor, runner, sender }
+        Self { executor, runner, sender, corpus: Vec::new() }
+    }
+
+    /// Sets the inputs that are replayed before any new inputs are generated, e.g. the
+    /// counterexamples of previous runs
+    pub fn with_corpus(mut self, corpus: Vec<Bytes>) -> Self {
+        self.corpus = corpus;
+        self
     }
 
     /// Fuzzes the provided function, assuming it is available at the contract at `address`
@@ -63,6 +79,9 @@ where
         // Stores the result and calldata of the last failed call, if any.
         let counterexample: RefCell<(Bytes, RawCallResult)> = RefCell::new(Default::default());
 
+        // Stores the coverage info collected across all fuzz cases
+        let coverage: RefCell<Option<HitMaps>> = RefCell::default();
+
         // Stores fuzz state for use with [fuzz_calldata_from_state]
         let state: EvmFuzzState = build_initial_state(&self.executor.db);
 
@@ -73,11 +92,15 @@ where
             (40, fuzz_calldata_from_state(func.clone(), state.clone())),
         ]);
         tracing::debug!(func = ?func.name, should_fail, "fuzzing");
-        let run_result = self.runner.clone().run(&strat, |calldata| {
-            let call = self
+        let test = |calldata: Bytes| {
+            let mut call = self
                 .executor
                 .call_raw(self.sender, address, calldata.0.clone(), 0.into())
                 .expect("could not make raw evm call");
+            if call.coverage.is_some() {
+                let mut coverage = coverage.borrow_mut();
+                *coverage = HitMaps::merge_opt(coverage.take(), call.coverage.take());
+            }
             let state_changeset =
                 call.state_changeset.as_ref().expect("we should have a state changeset");
 
@@ -97,17 +120,19 @@ where
             );
 
             if success {
-                cases.borrow_mut().push(FuzzCase {
-                    calldata,
-                    gas: call.gas,
-                    stipend: call.stipend,
-                });
+                // Cases that are run while shrinking a counterexample are not recorded
+                if counterexample.borrow().0.is_empty() {
+                    cases.borrow_mut().push(FuzzCase {
+                        calldata,
+                        gas: call.gas,
+                        stipend: call.stipend,
+                    });
+                }
                 Ok(())
             } else {
-                // We cannot use the calldata returned by the test runner in `TestError::Fail`,
-                // since that input represents the last run case, which may not correspond with our
-                // failure - when a fuzz case fails, proptest will try to run at least one more
-                // case to find a minimal failure case.
+                // Once a fuzz case fails, proptest shrinks the inputs and reruns the test for
+                // every simplified input until the failure can't be minimized any further, so the
+                // last failed call is the minimal counterexample.
                 *counterexample.borrow_mut() = (calldata, call);
                 Err(TestCaseError::fail(
                     match foundry_utils::decode_revert(
@@ -119,7 +144,18 @@ where
                     },
                 ))
             }
+        };
+
+        // Replay the corpus first, the test fails without generating new inputs if any of them
+        // still fails
+        let replay_result = self.corpus.iter().find_map(|calldata| match test(calldata.clone()) {
+            Err(TestCaseError::Fail(reason)) => Some(TestError::Fail(reason, calldata.clone())),
+            _ => None,
         });
+        let run_result = match replay_result {
+            Some(err) => Err(err),
+            None => self.runner.clone().run(&strat, test),
+        };
 
         let (calldata, call) = counterexample.into_inner();
         let mut result = FuzzTestResult {
@@ -130,6 +166,7 @@ where
             logs: call.logs,
             traces: call.traces,
             labeled_addresses: call.labels,
+            coverage: coverage.into_inner(),
         };
 
         match run_result {
@@ -143,7 +180,13 @@ where
                 let args = func
                     .decode_input(&calldata.as_ref()[4..])
                     .expect("could not decode fuzzer inputs");
-                result.counterexample = Some(CounterExample { calldata, args });
+                result.counterexample = Some(CounterExample::Single(BaseCounterExample {
+                    address: None,
+                    calldata,
+                    signature: None,
+                    contract_name: None,
+                    args,
+                }));
             }
             _ => (),
         }
@@ -153,17 +196,59 @@ where
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct CounterExample {
+pub enum CounterExample {
+    /// Call used as input for a fuzz test
+    Single(BaseCounterExample),
+    /// Sequence of calls used as input for an invariant test
+    Sequence(Vec<BaseCounterExample>),
+}
+
+impl fmt::Display for CounterExample {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CounterExample::Single(counterexample) => counterexample.fmt(f),
+            CounterExample::Sequence(sequence) => {
+                writeln!(f, "[Sequence]")?;
+                for call in sequence {
+                    writeln!(f, "\t\t{call}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BaseCounterExample {
+    /// The address the call was made to, only set for calls made during invariant tests
+    pub address: Option<Address>,
     pub calldata: Bytes,
+    /// The signature of the called function, only set for calls made during invariant tests
+    pub signature: Option<String>,
+    /// The name of the called contract, only set for calls made during invariant tests
+    pub contract_name: Option<String>,
 
     #[serde(skip)]
     pub args: Vec<Token>,
 }
 
-impl fmt::Display for CounterExample {
+impl fmt::Display for BaseCounterExample {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let args = foundry_utils::format_tokens(&self.args).collect::<Vec<_>>().join(", ");
-        write!(f, "calldata=0x{}, args=[{}]", hex::encode(&self.calldata), args)
+
+        if let Some(address) = &self.address {
+            write!(f, "addr=")?;
+            match &self.contract_name {
+                Some(name) => write!(f, "[{name}]{address:?} ")?,
+                None => write!(f, "{address:?} ")?,
+            }
+        }
+
+        write!(f, "calldata=0x{}, ", hex::encode(&self.calldata))?;
+        if let Some(signature) = &self.signature {
+            write!(f, "{signature}, ")?;
+        }
+        write!(f, "args=[{}]", args)
     }
 }
 
@@ -194,6 +279,9 @@ pub struct FuzzTestResult {
 
     /// Labeled addresses
     pub labeled_addresses: BTreeMap<Address, String>,
+
+    /// The coverage info collected across all fuzz cases
+    pub coverage: Option<HitMaps>,
 }
 
 /// Container type for all successful test cases
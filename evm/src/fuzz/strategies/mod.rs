@@ -1,5 +1,5 @@
 mod uint;
-pub use uint::UintStrategy;
+pub use uint::{UintStrategy, UintValueTree};
 
 mod param;
 pub use param::{fuzz_param, fuzz_param_from_state};
@@ -4,7 +4,7 @@ use ethers::{
 };
 use proptest::prelude::*;
 
-use super::state::EvmFuzzState;
+use super::state::{EvmFuzzState, StateValueStrategy};
 
 /// The max length of arrays we fuzz for is 256.
 pub const MAX_ARRAY_LEN: usize = 256;
@@ -30,14 +30,7 @@ pub fn fuzz_param(param: &ParamType) -> impl Strategy<Value = Token> {
                 .prop_map(move |x| I256::from_raw(U256::from(&x)).into_token())
                 .boxed(),
             y @ 1..=31 => any::<[u8; 32]>()
-                .prop_map(move |x| {
-                    // Generate a uintN in the correct range, then shift it to the range of intN
-                    // by subtracting 2^(N-1)
-                    let uint = U256::from(&x) % U256::from(2).pow(U256::from(y * 8));
-                    let max_int_plus1 = U256::from(2).pow(U256::from(y * 8 - 1));
-                    let num = I256::from_raw(uint.overflowing_sub(max_int_plus1).0);
-                    num.into_token()
-                })
+                .prop_map(move |x| int_from_uint(U256::from(&x), y * 8).into_token())
                 .boxed(),
             _ => panic!("unsupported solidity type int{n}"),
         },
@@ -74,14 +67,12 @@ pub fn fuzz_param(param: &ParamType) -> impl Strategy<Value = Token> {
 ///
 /// Works with ABI Encoder v2 tuples.
 pub fn fuzz_param_from_state(param: &ParamType, state: EvmFuzzState) -> BoxedStrategy<Token> {
-    // These are to comply with lifetime requirements
-    let state_len = state.borrow().len();
-    let s = state.clone();
-
     // Select a value from the state
-    let value = any::<prop::sample::Index>()
-        .prop_map(move |index| index.index(state_len))
-        .prop_map(move |index| *s.borrow().iter().nth(index).unwrap());
+    let value = StateValueStrategy::new(state.clone()).prop_map(|value| {
+        let mut bytes = [0u8; 32];
+        value.to_big_endian(&mut bytes);
+        bytes
+    });
 
     // Convert the value based on the parameter type
     match param {
@@ -94,14 +85,7 @@ pub fn fuzz_param_from_state(param: &ParamType, state: EvmFuzzState) -> BoxedStr
                 value.prop_map(move |value| I256::from_raw(U256::from(value)).into_token()).boxed()
             }
             y @ 1..=31 => value
-                .prop_map(move |value| {
-                    // Generate a uintN in the correct range, then shift it to the range of intN
-                    // by subtracting 2^(N-1)
-                    let uint = U256::from(value) % U256::from(2usize).pow(U256::from(y * 8));
-                    let max_int_plus1 = U256::from(2usize).pow(U256::from(y * 8 - 1));
-                    let num = I256::from_raw(uint.overflowing_sub(max_int_plus1).0);
-                    num.into_token()
-                })
+                .prop_map(move |value| int_from_uint(U256::from(value), y * 8).into_token())
                 .boxed(),
             _ => panic!("unsupported solidity type int{n}"),
         },
@@ -144,10 +128,29 @@ pub fn fuzz_param_from_state(param: &ParamType, state: EvmFuzzState) -> BoxedStr
     }
 }
 
+/// Wraps a uint to `bits` bits and interprets the result as a two's complement intN.
+///
+/// Unlike shifting the uintN to the range of intN, this maps zero to zero, so values shrunk towards
+/// zero are also shrunk towards zero as intN.
+fn int_from_uint(uint: U256, bits: usize) -> I256 {
+    let uint = uint % U256::from(2usize).pow(U256::from(bits));
+    let max_int_plus1 = U256::from(2usize).pow(U256::from(bits - 1));
+    if uint < max_int_plus1 {
+        I256::from_raw(uint)
+    } else {
+        // negative values are sign extended to 256 bits
+        I256::from_raw(uint.overflowing_sub(U256::from(2usize).pow(U256::from(bits))).0)
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::int_from_uint;
     use crate::fuzz::strategies::{build_initial_state, fuzz_calldata, fuzz_calldata_from_state};
-    use ethers::abi::AbiParser;
+    use ethers::{
+        abi::AbiParser,
+        types::{I256, U256},
+    };
     use revm::db::{CacheDB, EmptyDB};
 
     #[test]
@@ -168,4 +171,14 @@ mod tests {
 
         let _ = runner.run(&strat, |_| Ok(()));
     }
+
+    #[test]
+    fn int_from_uint_is_twos_complement() {
+        assert_eq!(int_from_uint(U256::zero(), 8), I256::zero());
+        assert_eq!(int_from_uint(U256::from(127), 8), I256::from(127));
+        assert_eq!(int_from_uint(U256::from(128), 8), I256::from(-128));
+        assert_eq!(int_from_uint(U256::from(255), 8), I256::from(-1));
+        assert_eq!(int_from_uint(U256::from(256), 8), I256::zero());
+        assert_eq!(int_from_uint(U256::MAX, 64), I256::from(-1));
+    }
 }
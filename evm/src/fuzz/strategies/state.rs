@@ -1,11 +1,16 @@
-use super::fuzz_param_from_state;
+use super::{fuzz_param_from_state, UintValueTree};
 use crate::{executor::StateChangeset, utils};
 use bytes::Bytes;
 use ethers::{
     abi::Function,
+    core::rand::Rng,
     types::{Address, Log, H256, U256},
 };
-use proptest::prelude::{BoxedStrategy, Strategy};
+use proptest::{
+    prelude::{BoxedStrategy, Strategy},
+    strategy::NewTree,
+    test_runner::TestRunner,
+};
 use revm::{
     db::{CacheDB, DatabaseRef},
     opcode, spec_opcode_gas, SpecId,
@@ -17,6 +22,34 @@ use std::{cell::RefCell, collections::HashSet, io::Write, rc::Rc};
 /// Wrapped in a shareable container.
 pub type EvmFuzzState = Rc<RefCell<HashSet<[u8; 32]>>>;
 
+/// A strategy that selects a random value from the [EvmFuzzState].
+///
+/// The value is selected when the value tree is created and then shrunk towards zero like a
+/// `uint256`. Selecting it lazily instead would yield a different value on every shrinking step,
+/// since the state grows with every call.
+#[derive(Debug)]
+pub struct StateValueStrategy {
+    state: EvmFuzzState,
+}
+
+impl StateValueStrategy {
+    pub fn new(state: EvmFuzzState) -> Self {
+        Self { state }
+    }
+}
+
+impl Strategy for StateValueStrategy {
+    type Tree = UintValueTree;
+    type Value = U256;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let state = self.state.borrow();
+        let index = runner.rng().gen_range(0..state.len());
+        let value = state.iter().nth(index).expect("fuzz state is never empty");
+        Ok(UintValueTree::new(U256::from(value), false))
+    }
+}
+
 /// Given a function and some state, it returns a strategy which generated valid calldata for the
 /// given function's input types, based on state taken from the EVM.
 pub fn fuzz_calldata_from_state(
@@ -42,7 +75,6 @@ This is a bug, please open an issue: https://github.com/foundry-rs/foundry/issue
                 })
                 .into()
         })
-        .no_shrink()
         .boxed()
 }
 
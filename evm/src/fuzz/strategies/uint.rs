@@ -24,7 +24,7 @@ impl UintValueTree {
     /// # Arguments
     /// * `start` - Starting value for the tree
     /// * `fixed` - If `true` the tree would only contain one element and won't be simplified.
-    fn new(start: U256, fixed: bool) -> Self {
+    pub fn new(start: U256, fixed: bool) -> Self {
         Self { lo: 0.into(), curr: start, hi: start, fixed }
     }
 
@@ -7,6 +7,9 @@ pub mod trace;
 /// Debugger data structures
 pub mod debug;
 
+/// Coverage data structures
+pub mod coverage;
+
 /// Forge test execution backends
 pub mod executor;
 
@@ -304,6 +304,8 @@ pub struct CallTrace {
     pub output: RawOrDecodedReturnData,
     /// The gas cost of the call
     pub gas_cost: u64,
+    /// The gas limit of the call
+    pub gas_limit: u64,
     /// The status of the trace's call
     pub status: Return,
     /// call context of the runtime
@@ -323,6 +325,7 @@ impl CallTrace {
         self.output = new_trace.output;
         self.address = new_trace.address;
         self.gas_cost = new_trace.gas_cost;
+        self.gas_limit = new_trace.gas_limit;
     }
 
     /// Whether this is a contract creation or not
@@ -345,6 +348,7 @@ impl Default for CallTrace {
             data: Default::default(),
             output: Default::default(),
             gas_cost: Default::default(),
+            gas_limit: Default::default(),
             status: Return::Continue,
             call_context: Default::default(),
         }
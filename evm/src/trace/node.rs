@@ -75,7 +75,7 @@ impl CallTraceNode {
                     from: self.trace.caller,
                     to: self.trace.address,
                     value: self.trace.value,
-                    gas: self.trace.gas_cost.into(),
+                    gas: self.trace.gas_limit.into(),
                     input: self.trace.data.to_raw().into(),
                     call_type: self.kind().into(),
                 })
@@ -83,7 +83,7 @@ impl CallTraceNode {
             CallKind::Create => Action::Create(Create {
                 from: self.trace.caller,
                 value: self.trace.value,
-                gas: self.trace.gas_cost.into(),
+                gas: self.trace.gas_limit.into(),
                 init: self.trace.data.to_raw().into(),
             }),
         }
@@ -2,6 +2,9 @@
 
 use std::fmt::Write;
 
+use foundry_config::fmt::{
+    FormatterConfig, IntTypes, MultilineFuncHeaderStyle, NumberUnderscore, QuoteStyle,
+};
 use indent_write::fmt::IndentWriter;
 use itertools::Itertools;
 use solang_parser::pt::*;
@@ -12,23 +15,6 @@ use crate::{
     visit::{ParameterList, VResult, Visitable, Visitor},
 };
 
-/// Contains the config and rule set
-#[derive(Debug, Clone)]
-pub struct FormatterConfig {
-    /// Maximum line length where formatter will try to wrap the line
-    pub line_length: usize,
-    /// Number of spaces per indentation level
-    pub tab_width: usize,
-    /// Print spaces between brackets
-    pub bracket_spacing: bool,
-}
-
-impl Default for FormatterConfig {
-    fn default() -> Self {
-        FormatterConfig { line_length: 80, tab_width: 4, bracket_spacing: false }
-    }
-}
-
 // TODO: use it inside Formatter since they're sharing same fields
 #[derive(Default)]
 struct FormatBuffer {
@@ -55,6 +41,8 @@ pub struct Formatter<'a, W> {
     current_line: usize,
     bufs: Vec<FormatBuffer>,
     context: Context,
+    /// Whether expressions and statements are written without breaking them into multiple lines
+    single_line: bool,
 }
 
 impl<'a, W: Write> Formatter<'a, W> {
@@ -68,6 +56,7 @@ impl<'a, W: Write> Formatter<'a, W> {
             bufs: Vec::new(),
             current_line: 0,
             context: Context::default(),
+            single_line: false,
         }
     }
 
@@ -174,10 +163,233 @@ impl<'a, W: Write> Formatter<'a, W> {
         Ok(buf.w)
     }
 
+    /// Visit `visitable` to a string without breaking any of its expressions into multiple lines.
+    /// Used to check whether an expression or a statement fits into the current line
+    fn visit_to_single_line(
+        &mut self,
+        visitable: &mut impl Visitable,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let single_line = std::mem::replace(&mut self.single_line, true);
+        let res = self.visit_to_string(visitable);
+        self.single_line = single_line;
+
+        res
+    }
+
+    /// Is `text` a single line that fits into the current line. Always `true` when writing in the
+    /// single line mode
+    fn fits_in_line(&self, text: impl AsRef<str>) -> bool {
+        self.single_line || (!text.as_ref().contains('\n') && self.will_it_fit(text))
+    }
+
+    /// Is the code at `loc` wrapped in parentheses in the source. The Parse Tree doesn't contain
+    /// parentheses, so we keep the ones written by the user, as they can change the precedence
+    fn is_parenthesized(&self, loc: Loc) -> bool {
+        self.source[..loc.start()].trim_end().ends_with('(') &&
+            self.source[loc.end()..].trim_start().starts_with(')')
+    }
+
+    /// Visit an operand of an operator, a member access or a function call, preserving its
+    /// parentheses
+    fn visit_operand(&mut self, expr: &mut Expression) -> VResult {
+        let parenthesized = self.is_parenthesized(LineOfCode::loc(expr));
+
+        if parenthesized {
+            self.visit_opening_paren()?;
+        }
+        expr.visit(self)?;
+        if parenthesized {
+            self.visit_closing_paren()?;
+        }
+
+        Ok(())
+    }
+
+    /// Visit an operand to a single line string, see [Self::visit_operand]
+    fn operand_to_single_line(
+        &mut self,
+        expr: &mut Expression,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let operand = self.visit_to_single_line(expr)?;
+
+        Ok(if self.is_parenthesized(LineOfCode::loc(expr)) {
+            format!("({operand})")
+        } else {
+            operand
+        })
+    }
+
+    /// Write `items` separated by commas and enclosed in `open` and `close`. If the items don't fit
+    /// into the current line, each of them is written on a separate line with an extra
+    /// indentation level
+    fn write_list<T: Visitable>(&mut self, open: &str, items: &mut [T], close: &str) -> VResult {
+        if items.is_empty() {
+            write!(self, "{}{}", open.trim_end(), close.trim_start())?;
+            return Ok(())
+        }
+
+        let list = items
+            .iter_mut()
+            .map(|item| self.visit_to_single_line(item))
+            .collect::<Result<Vec<_>, _>>()?
+            .join(", ");
+        let list = format!("{open}{list}{close}");
+        if self.fits_in_line(&list) {
+            write!(self, "{list}")?;
+            return Ok(())
+        }
+
+        writeln!(self, "{}", open.trim_end())?;
+        self.indent(1);
+        let mut items = items.iter_mut().peekable();
+        while let Some(item) = items.next() {
+            item.visit(self)?;
+            if items.peek().is_some() {
+                write!(self, ",")?;
+            }
+            writeln!(self)?;
+        }
+        self.dedent(1);
+        write!(self, "{}", close.trim_start())?;
+
+        Ok(())
+    }
+
+    /// Write a binary operation. If it doesn't fit into the current line, the right operand is
+    /// moved to the next line
+    fn write_binary_operation(
+        &mut self,
+        left: &mut Expression,
+        op: &str,
+        right: &mut Expression,
+    ) -> VResult {
+        let left_str = self.operand_to_single_line(left)?;
+        let right_str = self.operand_to_single_line(right)?;
+        let expr = format!("{left_str} {op} {right_str}");
+        if self.fits_in_line(&expr) {
+            write!(self, "{expr}")?;
+            return Ok(())
+        }
+
+        self.visit_operand(left)?;
+        writeln!(self, " {op}")?;
+        self.indent(1);
+        self.visit_operand(right)?;
+        self.dedent(1);
+
+        Ok(())
+    }
+
+    /// Write the right-hand side of an assignment or a variable definition after the `=` sign.
+    /// Function calls, array literals, ternary and binary operations which don't fit stay on the
+    /// same line and are broken up themselves, other expressions are moved to the next line
+    fn write_assignment_rhs(&mut self, expr: &mut Expression) -> VResult {
+        let rhs = self.visit_to_single_line(expr)?;
+        if self.fits_in_line(format!(" {rhs};")) {
+            write!(self, " {rhs}")?;
+        } else if matches!(
+            expr,
+            Expression::FunctionCall(..) |
+                Expression::NamedFunctionCall(..) |
+                Expression::ArrayLiteral(..) |
+                Expression::Ternary(..)
+        ) || helpers::binary_operation(expr).is_some()
+        {
+            write!(self, " ")?;
+            expr.visit(self)?;
+        } else {
+            writeln!(self)?;
+            self.indent(1);
+            expr.visit(self)?;
+            self.dedent(1);
+        }
+
+        Ok(())
+    }
+
+    /// Write the body of a control flow statement. Blocks start on the same line, other
+    /// statements are written on the same line if they fit or on the next line otherwise
+    fn write_body(&mut self, body: &mut Statement) -> VResult {
+        if let Statement::Block { .. } = body {
+            write!(self, " ")?;
+            return body.visit(self)
+        }
+
+        let body_str = self.visit_to_single_line(body)?;
+        if self.fits_in_line(format!(" {body_str}")) {
+            write!(self, " {body_str}")?;
+        } else {
+            writeln!(self)?;
+            self.indent(1);
+            body.visit(self)?;
+            self.dedent(1);
+        }
+
+        Ok(())
+    }
+
+    /// Write an integer type with respect to `config.int_types` setting
+    fn write_int_type(&mut self, loc: Loc, prefix: &str, bits: u16) -> VResult {
+        match self.config.int_types {
+            IntTypes::Long => write!(self, "{prefix}{bits}")?,
+            IntTypes::Short if bits == 256 => write!(self, "{prefix}")?,
+            IntTypes::Short => write!(self, "{prefix}{bits}")?,
+            IntTypes::Preserve => self.visit_source(loc)?,
+        }
+
+        Ok(())
+    }
+
+    /// Quote the string literal with respect to `config.quote_style` setting. Literals that
+    /// contain the preferred quotation mark or have a prefix are written as in the source
+    fn quote_string(&self, literal: &StringLiteral) -> String {
+        let source = &self.source[literal.loc.start()..literal.loc.end()];
+        match self.config.quote_style.quote() {
+            Some(quote)
+                if source.starts_with(|c| c == '"' || c == '\'') &&
+                    !literal.string.contains(quote) =>
+            {
+                format!("{quote}{}{quote}", literal.string)
+            }
+            _ => source.to_string(),
+        }
+    }
+
     /// Returns number of blank lines between two LOCs
     fn blank_lines(&self, a: Loc, b: Loc) -> usize {
         return self.source[a.end()..b.start()].matches('\n').count()
     }
+
+    /// Sort each group of import directives that are not separated by blank lines by their path
+    fn sort_imports(&self, source_unit: &mut SourceUnit, blank_lines_after: &[bool]) {
+        let import_path = |part: &SourceUnitPart| match part {
+            SourceUnitPart::ImportDirective(
+                Import::Plain(path, _) |
+                Import::GlobalSymbol(path, _, _) |
+                Import::Rename(path, _, _),
+            ) => Some(path.string.clone()),
+            _ => None,
+        };
+
+        let mut start = 0;
+        while start < source_unit.0.len() {
+            if import_path(&source_unit.0[start]).is_none() {
+                start += 1;
+                continue
+            }
+
+            let mut end = start + 1;
+            while end < source_unit.0.len() &&
+                !blank_lines_after[end - 1] &&
+                import_path(&source_unit.0[end]).is_some()
+            {
+                end += 1;
+            }
+
+            source_unit.0[start..end].sort_by_key(import_path);
+            start = end;
+        }
+    }
 }
 
 impl<'a, W: Write> Write for Formatter<'a, W> {
@@ -230,8 +442,20 @@ impl<'a, W: Write> Visitor for Formatter<'a, W> {
         //     _ => usize::MAX,
         // });
 
-        let mut source_unit_parts_iter = source_unit.0.iter_mut().peekable();
-        while let Some(unit) = source_unit_parts_iter.next() {
+        // Computed before sorting the imports, so the blank lines of the source are preserved
+        let blank_lines_after = source_unit
+            .0
+            .iter()
+            .tuple_windows()
+            .map(|(a, b)| self.blank_lines(a.loc(), b.loc()) > 1)
+            .collect::<Vec<_>>();
+
+        if self.config.sort_imports {
+            self.sort_imports(source_unit, &blank_lines_after);
+        }
+
+        let mut source_unit_parts_iter = source_unit.0.iter_mut().enumerate().peekable();
+        while let Some((i, unit)) = source_unit_parts_iter.next() {
             let is_pragma =
                 |u: &SourceUnitPart| matches!(u, SourceUnitPart::PragmaDirective(_, _, _));
             let is_import = |u: &SourceUnitPart| matches!(u, SourceUnitPart::ImportDirective(_));
@@ -246,12 +470,12 @@ impl<'a, W: Write> Visitor for Formatter<'a, W> {
                 writeln!(self)?;
             }
 
-            if let Some(next_unit) = source_unit_parts_iter.peek() {
+            if let Some((_, next_unit)) = source_unit_parts_iter.peek() {
                 // If source has zero blank lines between imports or errors, leave it as is. If one
                 // or more, separate with one blank line.
                 let separate = (is_import(unit) || is_error(unit)) &&
                     (is_import(next_unit) || is_error(next_unit)) &&
-                    self.blank_lines(unit.loc(), next_unit.loc()) > 1;
+                    blank_lines_after[i];
 
                 if (is_declaration(unit) || is_declaration(next_unit)) ||
                     (is_pragma(unit) || is_pragma(next_unit)) ||
@@ -410,7 +634,7 @@ impl<'a, W: Write> Visitor for Formatter<'a, W> {
     }
 
     fn visit_import_plain(&mut self, import: &mut StringLiteral) -> VResult {
-        write!(self, "import \"{}\";", &import.string)?;
+        write!(self, "import {};", self.quote_string(import))?;
 
         Ok(())
     }
@@ -420,7 +644,7 @@ impl<'a, W: Write> Visitor for Formatter<'a, W> {
         global: &mut StringLiteral,
         alias: &mut Identifier,
     ) -> VResult {
-        write!(self, "import \"{}\" as {};", global.string, alias.name)?;
+        write!(self, "import {} as {};", self.quote_string(global), alias.name)?;
 
         Ok(())
     }
@@ -462,7 +686,7 @@ impl<'a, W: Write> Visitor for Formatter<'a, W> {
             self.write_closing_bracket()?;
         }
 
-        write!(self, " from \"{}\";", from.string)?;
+        write!(self, " from {};", self.quote_string(from))?;
 
         Ok(())
     }
@@ -494,15 +718,24 @@ impl<'a, W: Write> Visitor for Formatter<'a, W> {
     }
 
     fn visit_expr(&mut self, loc: Loc, expr: &mut Expression) -> VResult {
+        if let Some((left, op, right)) = helpers::binary_operation(expr) {
+            return self.write_binary_operation(left, op, right)
+        }
+        if let Some((left, op, right)) = helpers::assignment_operation(expr) {
+            left.visit(self)?;
+            write!(self, " {op}")?;
+            return self.write_assignment_rhs(right)
+        }
+
         match expr {
-            Expression::Type(_, typ) => match typ {
+            Expression::Type(ty_loc, typ) => match typ {
                 Type::Address => write!(self, "address")?,
                 Type::AddressPayable => write!(self, "address payable")?,
                 Type::Payable => write!(self, "payable")?,
                 Type::Bool => write!(self, "bool")?,
                 Type::String => write!(self, "string")?,
-                Type::Int(n) => write!(self, "int{}", n)?,
-                Type::Uint(n) => write!(self, "uint{}", n)?,
+                Type::Int(n) => self.write_int_type(*ty_loc, "int", *n)?,
+                Type::Uint(n) => self.write_int_type(*ty_loc, "uint", *n)?,
                 Type::Bytes(n) => write!(self, "bytes{}", n)?,
                 Type::Rational => write!(self, "rational")?,
                 Type::DynamicBytes => write!(self, "bytes")?,
@@ -516,27 +749,405 @@ impl<'a, W: Write> Visitor for Formatter<'a, W> {
                 Type::Function { .. } => self.visit_source(loc)?,
             },
             Expression::ArraySubscript(_, ty_exp, size_exp) => {
-                ty_exp.visit(self)?;
+                self.visit_operand(ty_exp)?;
                 write!(self, "[")?;
                 if let Some(size_exp) = size_exp {
                     size_exp.visit(self)?;
                 }
                 write!(self, "]")?;
             }
-            _ => self.visit_source(loc)?,
+            Expression::ArraySlice(_, array, start, end) => {
+                self.visit_operand(array)?;
+                write!(self, "[")?;
+                if let Some(start) = start {
+                    start.visit(self)?;
+                }
+                write!(self, ":")?;
+                if let Some(end) = end {
+                    end.visit(self)?;
+                }
+                write!(self, "]")?;
+            }
+            Expression::MemberAccess(_, expr, ident) => {
+                self.visit_operand(expr)?;
+                write!(self, ".{}", ident.name)?;
+            }
+            Expression::FunctionCall(_, expr, args) => {
+                self.visit_operand(expr)?;
+                self.write_list("(", args, ")")?;
+            }
+            Expression::FunctionCallBlock(_, expr, block) => {
+                self.visit_operand(expr)?;
+                block.visit(self)?;
+            }
+            Expression::NamedFunctionCall(_, expr, args) => {
+                self.visit_operand(expr)?;
+                if self.config.bracket_spacing {
+                    self.write_list("({ ", args, " })")?;
+                } else {
+                    self.write_list("({", args, "})")?;
+                }
+            }
+            Expression::Ternary(_, cond, left, right) => {
+                let cond_str = self.operand_to_single_line(cond)?;
+                let left_str = self.operand_to_single_line(left)?;
+                let right_str = self.operand_to_single_line(right)?;
+                let ternary = format!("{cond_str} ? {left_str} : {right_str}");
+                if self.fits_in_line(&ternary) {
+                    write!(self, "{ternary}")?;
+                } else {
+                    self.visit_operand(cond)?;
+                    writeln!(self)?;
+                    self.indent(1);
+                    write!(self, "? ")?;
+                    self.visit_operand(left)?;
+                    writeln!(self)?;
+                    write!(self, ": ")?;
+                    self.visit_operand(right)?;
+                    self.dedent(1);
+                }
+            }
+            Expression::Not(_, expr) => {
+                write!(self, "!")?;
+                self.visit_operand(expr)?;
+            }
+            Expression::Complement(_, expr) => {
+                write!(self, "~")?;
+                self.visit_operand(expr)?;
+            }
+            Expression::Delete(_, expr) => {
+                write!(self, "delete ")?;
+                self.visit_operand(expr)?;
+            }
+            Expression::PreIncrement(_, expr) => {
+                write!(self, "++")?;
+                self.visit_operand(expr)?;
+            }
+            Expression::PreDecrement(_, expr) => {
+                write!(self, "--")?;
+                self.visit_operand(expr)?;
+            }
+            Expression::UnaryPlus(_, expr) => {
+                write!(self, "+")?;
+                self.visit_operand(expr)?;
+            }
+            Expression::UnaryMinus(_, expr) => {
+                write!(self, "-")?;
+                self.visit_operand(expr)?;
+            }
+            Expression::PostIncrement(_, expr) => {
+                self.visit_operand(expr)?;
+                write!(self, "++")?;
+            }
+            Expression::PostDecrement(_, expr) => {
+                self.visit_operand(expr)?;
+                write!(self, "--")?;
+            }
+            Expression::New(_, expr) => {
+                write!(self, "new ")?;
+                expr.visit(self)?;
+            }
+            Expression::List(_, params) => {
+                let params = params
+                    .iter_mut()
+                    .map(|(_, param)| match param {
+                        Some(param) => self.visit_to_single_line(param),
+                        None => Ok(String::new()),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                write!(self, "({})", params.join(", "))?;
+            }
+            Expression::ArrayLiteral(_, exprs) => self.write_list("[", exprs, "]")?,
+            Expression::Unit(_, expr, unit) => {
+                expr.visit(self)?;
+                let unit = match unit {
+                    Unit::Seconds(_) => "seconds",
+                    Unit::Minutes(_) => "minutes",
+                    Unit::Hours(_) => "hours",
+                    Unit::Days(_) => "days",
+                    Unit::Weeks(_) => "weeks",
+                    Unit::Wei(_) => "wei",
+                    Unit::Gwei(_) => "gwei",
+                    Unit::Ether(_) => "ether",
+                };
+                write!(self, " {unit}")?;
+            }
+            Expression::BoolLiteral(_, value) => write!(self, "{value}")?,
+            Expression::NumberLiteral(loc, ..) | Expression::RationalNumberLiteral(loc, ..) => {
+                let number = &self.source[loc.start()..loc.end()];
+                let number = helpers::format_number(number, self.config.number_underscore);
+                write!(self, "{number}")?;
+            }
+            Expression::StringLiteral(literals) => {
+                let literals = literals.iter().map(|literal| self.quote_string(literal)).join(" ");
+                write!(self, "{literals}")?;
+            }
+            Expression::Variable(ident) => write!(self, "{}", ident.name)?,
+            Expression::This(_) => write!(self, "this")?,
+            _ => self.visit_source(LineOfCode::loc(expr))?,
         };
 
         Ok(())
     }
 
+    fn visit_args(&mut self, _loc: Loc, args: &mut Vec<NamedArgument>) -> VResult {
+        if self.config.bracket_spacing {
+            self.write_list("{ ", args, " }")
+        } else {
+            self.write_list("{", args, "}")
+        }
+    }
+
+    fn visit_named_arg(&mut self, arg: &mut NamedArgument) -> VResult {
+        write!(self, "{}: ", arg.name.name)?;
+        arg.expr.visit(self)?;
+
+        Ok(())
+    }
+
     fn visit_emit(&mut self, _loc: Loc, event: &mut Expression) -> VResult {
         write!(self, "emit ")?;
-        event.loc().visit(self)?;
+        event.visit(self)?;
         write!(self, ";")?;
 
         Ok(())
     }
 
+    fn visit_var_definition_stmt(
+        &mut self,
+        _loc: Loc,
+        declaration: &mut VariableDeclaration,
+        expr: &mut Option<Expression>,
+    ) -> VResult {
+        declaration.visit(self)?;
+
+        if let Some(expr) = expr {
+            write!(self, " =")?;
+            self.write_assignment_rhs(expr)?;
+        }
+
+        write!(self, ";")?;
+
+        Ok(())
+    }
+
+    fn visit_return(&mut self, _loc: Loc, expr: &mut Option<Expression>) -> VResult {
+        write!(self, "return")?;
+
+        if let Some(expr) = expr {
+            write!(self, " ")?;
+            expr.visit(self)?;
+        }
+
+        write!(self, ";")?;
+
+        Ok(())
+    }
+
+    fn visit_revert(
+        &mut self,
+        _loc: Loc,
+        error: &mut Option<Expression>,
+        args: &mut Vec<Expression>,
+    ) -> VResult {
+        write!(self, "revert")?;
+
+        if let Some(error) = error {
+            write!(self, " ")?;
+            error.visit(self)?;
+        }
+
+        self.write_list("(", args, ")")?;
+        write!(self, ";")?;
+
+        Ok(())
+    }
+
+    fn visit_revert_named_args(
+        &mut self,
+        _loc: Loc,
+        error: &mut Option<Expression>,
+        args: &mut Vec<NamedArgument>,
+    ) -> VResult {
+        write!(self, "revert")?;
+
+        if let Some(error) = error {
+            write!(self, " ")?;
+            error.visit(self)?;
+        }
+
+        if self.config.bracket_spacing {
+            self.write_list("({ ", args, " })")?;
+        } else {
+            self.write_list("({", args, "})")?;
+        }
+        write!(self, ";")?;
+
+        Ok(())
+    }
+
+    fn visit_if(
+        &mut self,
+        _loc: Loc,
+        cond: &mut Expression,
+        if_branch: &mut Box<Statement>,
+        else_branch: &mut Option<Box<Statement>>,
+    ) -> VResult {
+        write!(self, "if (")?;
+        cond.visit(self)?;
+        write!(self, ")")?;
+        self.write_body(if_branch)?;
+
+        if let Some(else_branch) = else_branch {
+            if let Statement::Block { .. } = **if_branch {
+                write!(self, " ")?;
+            } else {
+                writeln!(self)?;
+            }
+            write!(self, "else")?;
+
+            if let Statement::If(..) = **else_branch {
+                write!(self, " ")?;
+                else_branch.visit(self)?;
+            } else {
+                self.write_body(else_branch)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn visit_while(&mut self, _loc: Loc, cond: &mut Expression, body: &mut Statement) -> VResult {
+        write!(self, "while (")?;
+        cond.visit(self)?;
+        write!(self, ")")?;
+        self.write_body(body)?;
+
+        Ok(())
+    }
+
+    fn visit_do_while(
+        &mut self,
+        _loc: Loc,
+        body: &mut Statement,
+        cond: &mut Expression,
+    ) -> VResult {
+        write!(self, "do")?;
+        self.write_body(body)?;
+
+        if let Statement::Block { .. } = body {
+            write!(self, " ")?;
+        } else {
+            writeln!(self)?;
+        }
+        write!(self, "while (")?;
+        cond.visit(self)?;
+        write!(self, ");")?;
+
+        Ok(())
+    }
+
+    fn visit_for(
+        &mut self,
+        _loc: Loc,
+        init: &mut Option<Box<Statement>>,
+        cond: &mut Option<Box<Expression>>,
+        update: &mut Option<Box<Statement>>,
+        body: &mut Option<Box<Statement>>,
+    ) -> VResult {
+        write!(self, "for (")?;
+
+        match init {
+            Some(init) => init.visit(self)?,
+            None => write!(self, ";")?,
+        }
+
+        if let Some(cond) = cond {
+            write!(self, " ")?;
+            cond.visit(self)?;
+        }
+        write!(self, ";")?;
+
+        if let Some(update) = update {
+            write!(self, " ")?;
+            // The update is parsed as an expression statement, but written without a semicolon
+            match &mut **update {
+                Statement::Expression(_, expr) => expr.visit(self)?,
+                update => update.visit(self)?,
+            }
+        }
+        write!(self, ")")?;
+
+        match body {
+            Some(body) => self.write_body(body)?,
+            None => write!(self, ";")?,
+        }
+
+        Ok(())
+    }
+
+    fn visit_try(
+        &mut self,
+        _loc: Loc,
+        expr: &mut Expression,
+        returns: &mut Option<(ParameterList, Box<Statement>)>,
+        clauses: &mut Vec<CatchClause>,
+    ) -> VResult {
+        write!(self, "try ")?;
+        expr.visit(self)?;
+
+        if let Some((params, block)) = returns {
+            if !params.is_empty() {
+                write!(self, " returns ")?;
+                params.visit(self)?;
+            }
+            write!(self, " ")?;
+            block.visit(self)?;
+        }
+
+        for clause in clauses.iter_mut() {
+            write!(self, " catch ")?;
+            match clause {
+                CatchClause::Simple(_, param, block) => {
+                    if let Some(param) = param {
+                        self.visit_opening_paren()?;
+                        param.visit(self)?;
+                        self.visit_closing_paren()?;
+                        write!(self, " ")?;
+                    }
+                    block.visit(self)?;
+                }
+                CatchClause::Named(_, ident, param, block) => {
+                    write!(self, "{}", ident.name)?;
+                    self.visit_opening_paren()?;
+                    param.visit(self)?;
+                    self.visit_closing_paren()?;
+                    write!(self, " ")?;
+                    block.visit(self)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn visit_assembly(
+        &mut self,
+        _loc: Loc,
+        dialect: &mut Option<StringLiteral>,
+        block: &mut YulBlock,
+    ) -> VResult {
+        write!(self, "assembly ")?;
+
+        if let Some(dialect) = dialect {
+            write!(self, "\"{}\" ", dialect.string)?;
+        }
+
+        block.visit(self)?;
+
+        Ok(())
+    }
+
     fn visit_var_declaration(&mut self, var: &mut VariableDeclaration) -> VResult {
         var.ty.visit(self)?;
 
@@ -570,16 +1181,46 @@ impl<'a, W: Write> Visitor for Formatter<'a, W> {
             write!(self, " {name}")?;
         }
 
-        let params = self.visit_to_string(&mut func.params)?;
-        let params_multiline = params.contains('\n');
-
-        self.write_items(params.lines(), params_multiline)?;
+        let mut params = self.visit_to_string(&mut func.params)?;
+        let mut params_multiline = params.contains('\n');
+        let mut params_forced_multiline = false;
 
         let attributes = self.visit_to_string(&mut func.attributes)?;
         let attributes = attributes.lines().collect::<Vec<_>>();
 
         let returns = self.visit_to_string(&mut func.returns)?;
         let returns_multiline = returns.contains('\n');
+
+        // With the `all` style, the parameters are written multiline as well if the whole header
+        // doesn't fit in one line.
+        if self.config.multiline_func_header == MultilineFuncHeaderStyle::All &&
+            !params_multiline &&
+            !func.params.is_empty()
+        {
+            let header = format!(
+                "{params} {} {} {}",
+                attributes.join(" "),
+                if func.returns.is_empty() { "".to_string() } else { format!("returns {returns}") },
+                if func.body.is_some() { "{" } else { ";" }
+            );
+            if !self.will_it_fit(header.split_whitespace().join(" ")) {
+                let indent = " ".repeat(self.config.tab_width);
+                let items = func
+                    .params
+                    .iter_mut()
+                    .filter_map(|(_, param)| param.as_mut())
+                    .map(|param| self.visit_to_string(param))
+                    .collect::<Result<Vec<_>, _>>()?;
+                params = format!(
+                    "(\n{}\n)",
+                    items.iter().map(|param| format!("{indent}{param}")).join(",\n")
+                );
+                params_multiline = true;
+                params_forced_multiline = true;
+            }
+        }
+
+        self.write_items(params.lines(), params_multiline)?;
         let returns_indent = params_multiline || !attributes.is_empty() || returns_multiline;
 
         // Compose one line string consisting of attributes and return parameters.
@@ -602,7 +1243,8 @@ impl<'a, W: Write> Visitor for Formatter<'a, W> {
 
         let attributes_returns_fits_one_line = self
             .will_it_fit(&format!(" {attributes_returns}{body_first_line}")) &&
-            !returns_multiline;
+            !returns_multiline &&
+            !params_forced_multiline;
 
         // Check that we can fit both attributes and return arguments in one line.
         if !attributes_returns.is_empty() && attributes_returns_fits_one_line {
@@ -657,15 +1299,22 @@ impl<'a, W: Write> Visitor for Formatter<'a, W> {
     /// visit function regarding one line/multiline cases. We can transform it into one line later
     /// by `.split("\n").join(" ")`.
     fn visit_function_attribute_list(&mut self, list: &mut Vec<FunctionAttribute>) -> VResult {
+        let sort = self.config.sort_func_attributes;
         let mut attributes = list
             .iter_mut()
-            .sorted_by_key(|attribute| match attribute {
-                FunctionAttribute::Visibility(_) => 0,
-                FunctionAttribute::Mutability(_) => 1,
-                FunctionAttribute::Virtual(_) => 2,
-                FunctionAttribute::Immutable(_) => 3,
-                FunctionAttribute::Override(_, _) => 4,
-                FunctionAttribute::BaseOrModifier(_, _) => 5,
+            .sorted_by_key(|attribute| {
+                if !sort {
+                    // the sort is stable, so the attributes keep their order
+                    return 0
+                }
+                match attribute {
+                    FunctionAttribute::Visibility(_) => 0,
+                    FunctionAttribute::Mutability(_) => 1,
+                    FunctionAttribute::Virtual(_) => 2,
+                    FunctionAttribute::Immutable(_) => 3,
+                    FunctionAttribute::Override(_, _) => 4,
+                    FunctionAttribute::BaseOrModifier(_, _) => 5,
+                }
             })
             .peekable();
 
@@ -744,7 +1393,7 @@ impl<'a, W: Write> Visitor for Formatter<'a, W> {
         if let Some(args) = &mut base.args {
             let args = args
                 .iter_mut()
-                .map(|arg| self.visit_to_string(arg))
+                .map(|arg| self.visit_to_single_line(arg))
                 .collect::<Result<Vec<_>, _>>()?;
 
             let multiline = self.is_separated_multiline(&args, ", ");
@@ -1090,15 +1739,15 @@ impl<'a, W: Write> Visitor for Formatter<'a, W> {
         if let Some(init) = &mut var.initializer {
             write!(self, " =")?;
 
-            let init = self.visit_to_string(init)?;
-            if self.will_it_fit(format!(" {init}")) {
-                write!(self, " {init}")?;
+            let init_str = self.visit_to_single_line(init)?;
+            if self.fits_in_line(format!(" {init_str}")) {
+                write!(self, " {init_str}")?;
             } else {
                 writeln!(self)?;
                 if !multiline {
                     self.indent(1);
                 }
-                write!(self, "{init}")?;
+                init.visit(self)?;
                 if !multiline {
                     self.dedent(1);
                 }
@@ -1113,6 +1762,213 @@ impl<'a, W: Write> Visitor for Formatter<'a, W> {
 
         Ok(())
     }
+
+    fn visit_yul_block(&mut self, loc: Loc, statements: &mut Vec<YulStatement>) -> VResult {
+        if statements.is_empty() {
+            self.write_empty_brackets()?;
+            return Ok(())
+        }
+
+        let multiline = self.source[loc.start()..loc.end()].contains('\n');
+
+        if multiline {
+            writeln!(self, "{{")?;
+            self.indent(1);
+        } else {
+            self.write_opening_bracket()?;
+        }
+
+        let mut statements_iter = statements.iter_mut().peekable();
+        while let Some(stmt) = statements_iter.next() {
+            stmt.visit(self)?;
+            if multiline {
+                writeln!(self)?;
+            }
+
+            if let Some(next_stmt) = statements_iter.peek() {
+                if multiline {
+                    // If source has zero blank lines between statements, leave it as is. If one
+                    //  or more, separate statements with one blank line.
+                    if self.blank_lines(stmt.loc(), next_stmt.loc()) > 1 {
+                        writeln!(self)?;
+                    }
+                } else {
+                    write!(self, " ")?;
+                }
+            }
+        }
+
+        if multiline {
+            self.dedent(1);
+            write!(self, "}}")?;
+        } else {
+            self.write_closing_bracket()?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_yul_expr(&mut self, expr: &mut YulExpression) -> VResult {
+        let loc = LineOfCode::loc(expr);
+
+        match expr {
+            YulExpression::BoolLiteral(_, _, ty) |
+            YulExpression::NumberLiteral(_, _, _, ty) |
+            YulExpression::HexNumberLiteral(_, _, ty) |
+            YulExpression::HexStringLiteral(_, ty) |
+            YulExpression::StringLiteral(_, ty) => {
+                // Literals are written as they are in the source, including the type if any
+                let end = ty.as_ref().map_or(loc.end(), |ty| ty.loc.end().max(loc.end()));
+                self.visit_source(Loc::File(loc.file_no(), loc.start(), end))?;
+            }
+            YulExpression::Variable(ident) => write!(self, "{}", ident.name)?,
+            YulExpression::FunctionCall(call) => self.visit_yul_fun_call(call)?,
+            YulExpression::Member(_, expr, ident) => {
+                expr.visit(self)?;
+                write!(self, ".{}", ident.name)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn visit_yul_assignment(
+        &mut self,
+        _loc: Loc,
+        exprs: &mut Vec<YulExpression>,
+        expr: &mut YulExpression,
+    ) -> VResult {
+        let exprs = exprs
+            .iter_mut()
+            .map(|expr| self.visit_to_single_line(expr))
+            .collect::<Result<Vec<_>, _>>()?;
+        write!(self, "{} := ", exprs.join(", "))?;
+        expr.visit(self)?;
+
+        Ok(())
+    }
+
+    fn visit_yul_var_declaration(
+        &mut self,
+        _loc: Loc,
+        idents: &mut Vec<YulTypedIdentifier>,
+        expr: &mut Option<YulExpression>,
+    ) -> VResult {
+        let idents = idents
+            .iter_mut()
+            .map(|ident| self.visit_to_single_line(ident))
+            .collect::<Result<Vec<_>, _>>()?;
+        write!(self, "let {}", idents.join(", "))?;
+
+        if let Some(expr) = expr {
+            write!(self, " := ")?;
+            expr.visit(self)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_yul_if(
+        &mut self,
+        _loc: Loc,
+        expr: &mut YulExpression,
+        block: &mut YulBlock,
+    ) -> VResult {
+        write!(self, "if ")?;
+        expr.visit(self)?;
+        write!(self, " ")?;
+        block.visit(self)?;
+
+        Ok(())
+    }
+
+    fn visit_yul_for(&mut self, stmt: &mut YulFor) -> VResult {
+        write!(self, "for ")?;
+        stmt.init_block.visit(self)?;
+        write!(self, " ")?;
+        stmt.condition.visit(self)?;
+        write!(self, " ")?;
+        stmt.post_block.visit(self)?;
+        write!(self, " ")?;
+        stmt.execution_block.visit(self)?;
+
+        Ok(())
+    }
+
+    fn visit_yul_switch(&mut self, stmt: &mut YulSwitch) -> VResult {
+        write!(self, "switch ")?;
+        stmt.condition.visit(self)?;
+
+        for case in stmt.cases.iter_mut().chain(stmt.default.iter_mut()) {
+            writeln!(self)?;
+            match case {
+                YulSwitchOptions::Case(_, expr, block) => {
+                    write!(self, "case ")?;
+                    expr.visit(self)?;
+                    write!(self, " ")?;
+                    block.visit(self)?;
+                }
+                YulSwitchOptions::Default(_, block) => {
+                    write!(self, "default ")?;
+                    block.visit(self)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn visit_yul_leave(&mut self) -> VResult {
+        write!(self, "leave")?;
+
+        Ok(())
+    }
+
+    fn visit_yul_break(&mut self) -> VResult {
+        write!(self, "break")?;
+
+        Ok(())
+    }
+
+    fn visit_yul_continue(&mut self) -> VResult {
+        write!(self, "continue")?;
+
+        Ok(())
+    }
+
+    fn visit_yul_fun_def(&mut self, def: &mut YulFunctionDefinition) -> VResult {
+        write!(self, "function {}", def.id.name)?;
+        self.write_list("(", &mut def.params, ")")?;
+
+        if !def.returns.is_empty() {
+            let returns = def
+                .returns
+                .iter_mut()
+                .map(|ident| self.visit_to_single_line(ident))
+                .collect::<Result<Vec<_>, _>>()?;
+            write!(self, " -> {}", returns.join(", "))?;
+        }
+
+        write!(self, " ")?;
+        def.body.visit(self)?;
+
+        Ok(())
+    }
+
+    fn visit_yul_fun_call(&mut self, call: &mut YulFunctionCall) -> VResult {
+        write!(self, "{}", call.id.name)?;
+        self.write_list("(", &mut call.arguments, ")")
+    }
+
+    fn visit_yul_typed_ident(&mut self, ident: &mut YulTypedIdentifier) -> VResult {
+        write!(self, "{}", ident.id.name)?;
+
+        if let Some(ty) = &ident.ty {
+            write!(self, ": {}", ty.name)?;
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -1143,7 +1999,7 @@ mod tests {
                     .map(|filename| filename.strip_suffix('.'))
                     .is_some()
                 {
-                    let mut config = FormatterConfig::default();
+                    let mut config_entries = vec![];
 
                     let mut lines = source.split('\n').peekable();
                     while let Some(line) = lines.peek() {
@@ -1156,19 +2012,15 @@ mod tests {
                         }
 
                         if let Some((key, value)) = entry.unwrap().split_once('=') {
-                            match key {
-                                "line-length" => config.line_length = value.parse().unwrap(),
-                                "tab-width" => config.tab_width = value.parse().unwrap(),
-                                "bracket-spacing" => {
-                                    config.bracket_spacing = value.parse().unwrap()
-                                }
-                                _ => panic!("Unknown config key: {key}"),
-                            }
+                            config_entries.push(format!("{} = {value}", key.replace('-', "_")));
                         }
 
                         lines.next();
                     }
 
+                    let config: FormatterConfig = toml::from_str(&config_entries.join("\n"))
+                        .unwrap_or_else(|err| panic!("Invalid config in {filename}: {err}"));
+
                     return Some((filename.to_string(), config, lines.join("\n")))
                 }
             }
@@ -1199,6 +2051,13 @@ mod tests {
 
         let (mut source_pt, _source_comments) = solang_parser::parse(source, 1).unwrap();
 
+        let mut result = String::new();
+        let mut f = Formatter::new(&mut result, source, config);
+
+        source_pt.visit(&mut f).unwrap();
+
+        // Compared after visiting, because the formatter may reorder parts of the tree, e.g.
+        // imports
         let (expected_pt, _expected_comments) = solang_parser::parse(expected, 1).unwrap();
         if !source_pt.ast_eq(&expected_pt) {
             pretty_assertions::assert_eq!(
@@ -1209,11 +2068,6 @@ mod tests {
             );
         }
 
-        let mut result = String::new();
-        let mut f = Formatter::new(&mut result, source, config);
-
-        source_pt.visit(&mut f).unwrap();
-
         let formatted = PrettyString(result);
         let expected = PrettyString(expected.trim_start().to_string());
 
@@ -1241,13 +2095,23 @@ mod tests {
     test_directory! { EnumDefinition }
     test_directory! { ErrorDefinition }
     test_directory! { EventDefinition }
+    test_directory! { ForStatement }
+    test_directory! { FunctionCall }
     test_directory! { FunctionDefinition }
     test_directory! { FunctionType }
+    test_directory! { IfStatement }
     test_directory! { ImportDirective }
+    test_directory! { IntTypes }
     test_directory! { ModifierDefinition }
+    test_directory! { NumberLiteral }
+    test_directory! { OperatorExpressions }
     test_directory! { StatementBlock }
+    test_directory! { StringLiteral }
     test_directory! { StructDefinition }
+    test_directory! { TryStatement }
     test_directory! { TypeDefinition }
     test_directory! { UsingDirective }
     test_directory! { VariableDefinition }
+    test_directory! { WhileStatement }
+    test_directory! { Yul }
 }
@@ -1,3 +1,4 @@
+use foundry_config::fmt::NumberUnderscore;
 use solang_parser::pt::*;
 
 pub fn namespace_matches(left: &Expression, right: &Expression) -> bool {
@@ -15,3 +16,82 @@ pub fn namespace_matches(left: &Expression, right: &Expression) -> bool {
         _ => false,
     }
 }
+
+/// Returns the operands and the operator of a binary operation, or `None` if `expr` is not one
+pub fn binary_operation(
+    expr: &mut Expression,
+) -> Option<(&mut Expression, &'static str, &mut Expression)> {
+    let (left, op, right) = match expr {
+        Expression::Power(_, left, right) => (left, "**", right),
+        Expression::Multiply(_, left, right) => (left, "*", right),
+        Expression::Divide(_, left, right) => (left, "/", right),
+        Expression::Modulo(_, left, right) => (left, "%", right),
+        Expression::Add(_, left, right) => (left, "+", right),
+        Expression::Subtract(_, left, right) => (left, "-", right),
+        Expression::ShiftLeft(_, left, right) => (left, "<<", right),
+        Expression::ShiftRight(_, left, right) => (left, ">>", right),
+        Expression::BitwiseAnd(_, left, right) => (left, "&", right),
+        Expression::BitwiseXor(_, left, right) => (left, "^", right),
+        Expression::BitwiseOr(_, left, right) => (left, "|", right),
+        Expression::Less(_, left, right) => (left, "<", right),
+        Expression::More(_, left, right) => (left, ">", right),
+        Expression::LessEqual(_, left, right) => (left, "<=", right),
+        Expression::MoreEqual(_, left, right) => (left, ">=", right),
+        Expression::Equal(_, left, right) => (left, "==", right),
+        Expression::NotEqual(_, left, right) => (left, "!=", right),
+        Expression::And(_, left, right) => (left, "&&", right),
+        Expression::Or(_, left, right) => (left, "||", right),
+        _ => return None,
+    };
+
+    Some((&mut **left, op, &mut **right))
+}
+
+/// Returns the operands and the operator of an assignment, or `None` if `expr` is not one
+pub fn assignment_operation(
+    expr: &mut Expression,
+) -> Option<(&mut Expression, &'static str, &mut Expression)> {
+    let (left, op, right) = match expr {
+        Expression::Assign(_, left, right) => (left, "=", right),
+        Expression::AssignOr(_, left, right) => (left, "|=", right),
+        Expression::AssignAnd(_, left, right) => (left, "&=", right),
+        Expression::AssignXor(_, left, right) => (left, "^=", right),
+        Expression::AssignShiftLeft(_, left, right) => (left, "<<=", right),
+        Expression::AssignShiftRight(_, left, right) => (left, ">>=", right),
+        Expression::AssignAdd(_, left, right) => (left, "+=", right),
+        Expression::AssignSubtract(_, left, right) => (left, "-=", right),
+        Expression::AssignMultiply(_, left, right) => (left, "*=", right),
+        Expression::AssignDivide(_, left, right) => (left, "/=", right),
+        Expression::AssignModulo(_, left, right) => (left, "%=", right),
+        _ => return None,
+    };
+
+    Some((&mut **left, op, &mut **right))
+}
+
+/// Formats the underscores of a decimal number literal like `1_000`, `1.5` or `1e18` with respect
+/// to the `style`
+pub fn format_number(number: &str, style: NumberUnderscore) -> String {
+    match style {
+        NumberUnderscore::Preserve => number.to_string(),
+        NumberUnderscore::Remove => number.replace('_', ""),
+        NumberUnderscore::Thousands => {
+            let number = number.replace('_', "");
+            let integer_len = number.find(|c: char| !c.is_ascii_digit()).unwrap_or(number.len());
+            let (integer, rest) = number.split_at(integer_len);
+            if integer.len() <= 4 {
+                return number
+            }
+
+            let mut formatted = String::with_capacity(number.len() + integer.len() / 3);
+            for (i, digit) in integer.chars().enumerate() {
+                if i > 0 && (integer.len() - i) % 3 == 0 {
+                    formatted.push('_');
+                }
+                formatted.push(digit);
+            }
+            formatted.push_str(rest);
+            formatted
+        }
+    }
+}
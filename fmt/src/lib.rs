@@ -5,5 +5,6 @@ mod helpers;
 pub mod solang_ext;
 mod visit;
 
-pub use formatter::{Formatter, FormatterConfig};
+pub use formatter::Formatter;
+pub use foundry_config::fmt::*;
 pub use visit::{Visitable, Visitor};
@@ -1,7 +1,7 @@
-#[cfg(test)]
+// `ast_eq` is not test-only: `forge fmt` uses it to reject output that changes the semantics of
+// the formatted code
 mod ast_eq;
 mod loc;
 
-#[cfg(test)]
 pub use ast_eq::*;
 pub use loc::*;
@@ -83,6 +83,10 @@ pub trait Visitor {
         self.visit_source(loc)
     }
 
+    fn visit_named_arg(&mut self, arg: &mut NamedArgument) -> VResult {
+        self.visit_source(arg.loc)
+    }
+
     /// Don't write semicolon at the end because expressions can appear as both
     /// part of other node and a statement in the function body
     fn visit_expr(&mut self, loc: Loc, _expr: &mut Expression) -> VResult {
@@ -184,8 +188,8 @@ pub trait Visitor {
     fn visit_do_while(
         &mut self,
         loc: Loc,
-        _cond: &mut Statement,
-        _body: &mut Expression,
+        _body: &mut Statement,
+        _cond: &mut Expression,
     ) -> VResult {
         self.visit_source(loc)
     }
@@ -310,6 +314,73 @@ pub trait Visitor {
 
         Ok(())
     }
+
+    fn visit_yul_block(&mut self, loc: Loc, _statements: &mut Vec<YulStatement>) -> VResult {
+        self.visit_source(loc)
+    }
+
+    fn visit_yul_expr(&mut self, expr: &mut YulExpression) -> VResult {
+        self.visit_source(LineOfCode::loc(expr))
+    }
+
+    fn visit_yul_assignment(
+        &mut self,
+        loc: Loc,
+        _exprs: &mut Vec<YulExpression>,
+        _expr: &mut YulExpression,
+    ) -> VResult {
+        self.visit_source(loc)
+    }
+
+    fn visit_yul_var_declaration(
+        &mut self,
+        loc: Loc,
+        _idents: &mut Vec<YulTypedIdentifier>,
+        _expr: &mut Option<YulExpression>,
+    ) -> VResult {
+        self.visit_source(loc)
+    }
+
+    fn visit_yul_if(
+        &mut self,
+        loc: Loc,
+        _expr: &mut YulExpression,
+        _block: &mut YulBlock,
+    ) -> VResult {
+        self.visit_source(loc)
+    }
+
+    fn visit_yul_for(&mut self, stmt: &mut YulFor) -> VResult {
+        self.visit_source(stmt.loc)
+    }
+
+    fn visit_yul_switch(&mut self, stmt: &mut YulSwitch) -> VResult {
+        self.visit_source(stmt.loc)
+    }
+
+    fn visit_yul_leave(&mut self) -> VResult {
+        Ok(())
+    }
+
+    fn visit_yul_break(&mut self) -> VResult {
+        Ok(())
+    }
+
+    fn visit_yul_continue(&mut self) -> VResult {
+        Ok(())
+    }
+
+    fn visit_yul_fun_def(&mut self, def: &mut YulFunctionDefinition) -> VResult {
+        self.visit_source(def.loc)
+    }
+
+    fn visit_yul_fun_call(&mut self, call: &mut YulFunctionCall) -> VResult {
+        self.visit_source(call.loc)
+    }
+
+    fn visit_yul_typed_ident(&mut self, ident: &mut YulTypedIdentifier) -> VResult {
+        self.visit_source(ident.loc)
+    }
 }
 
 /// All `solang::pt::*` types, such as [Statement](solang::pt::Statement) should implement the
@@ -391,7 +462,7 @@ impl Visitable for Statement {
             Statement::For(loc, init, cond, update, body) => {
                 v.visit_for(*loc, init, cond, update, body)
             }
-            Statement::DoWhile(loc, cond, body) => v.visit_do_while(*loc, cond, body),
+            Statement::DoWhile(loc, body, cond) => v.visit_do_while(*loc, body, cond),
             Statement::Continue(_) => v.visit_continue(),
             Statement::Break(_) => v.visit_break(),
             Statement::Return(loc, expr) => v.visit_return(*loc, expr),
@@ -408,6 +479,32 @@ impl Visitable for Statement {
     }
 }
 
+impl Visitable for YulStatement {
+    fn visit(&mut self, v: &mut impl Visitor) -> VResult {
+        match self {
+            YulStatement::Assign(loc, exprs, expr) => v.visit_yul_assignment(*loc, exprs, expr),
+            YulStatement::VariableDeclaration(loc, idents, expr) => {
+                v.visit_yul_var_declaration(*loc, idents, expr)
+            }
+            YulStatement::If(loc, expr, block) => v.visit_yul_if(*loc, expr, block),
+            YulStatement::For(stmt) => v.visit_yul_for(stmt),
+            YulStatement::Switch(stmt) => v.visit_yul_switch(stmt),
+            YulStatement::Leave(_) => v.visit_yul_leave(),
+            YulStatement::Break(_) => v.visit_yul_break(),
+            YulStatement::Continue(_) => v.visit_yul_continue(),
+            YulStatement::Block(block) => v.visit_yul_block(block.loc, &mut block.statements),
+            YulStatement::FunctionDefinition(def) => v.visit_yul_fun_def(def),
+            YulStatement::FunctionCall(call) => v.visit_yul_fun_call(call),
+        }
+    }
+}
+
+impl Visitable for YulBlock {
+    fn visit(&mut self, v: &mut impl Visitor) -> VResult {
+        v.visit_yul_block(self.loc, &mut self.statements)
+    }
+}
+
 impl Visitable for Loc {
     fn visit(&mut self, v: &mut impl Visitor) -> VResult {
         v.visit_source(*self)
@@ -441,3 +538,7 @@ impl_visitable!(ParameterList, visit_parameter_list);
 impl_visitable!(Base, visit_base);
 impl_visitable!(EventParameter, visit_event_parameter);
 impl_visitable!(ErrorParameter, visit_error_parameter);
+impl_visitable!(NamedArgument, visit_named_arg);
+impl_visitable!(YulExpression, visit_yul_expr);
+impl_visitable!(YulFunctionCall, visit_yul_fun_call);
+impl_visitable!(YulTypedIdentifier, visit_yul_typed_ident);
@@ -16,10 +16,7 @@ contract HelloWorld {
     /// Constructs the dude
     /// @param age The dude's age
     constructor(uint256 age) {
-        theDude = Person({
-            age: age,
-            wallet: msg.sender
-        });
+        theDude = Person({age: age, wallet: msg.sender});
     }
 
     /**
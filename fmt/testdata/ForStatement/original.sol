@@ -0,0 +1,20 @@
+contract ForStatement {
+    function test() {
+        for
+        (uint256 i1
+        ; i1 < 100; i1++)
+        {
+            i1++;
+        }
+
+        uint256 i2;
+        for(;i2<10;i2++) i2++;
+
+        uint256 i3;
+        for (;;) {
+            i3++;
+        }
+
+        for (uint256 i4 = 0; i4 < 10; i4++) {}
+    }
+}
@@ -0,0 +1,14 @@
+contract FunctionCall {
+    function test() {
+        foo();
+        foo(a, b);
+        foo({a: 1, b: 2});
+        veryLongFunctionNameNumberOne(
+            firstArgument,
+            secondArgument,
+            thirdArgument
+        );
+        target.call{value: 1 ether, gas: 100}(data);
+        return foo(a, b);
+    }
+}
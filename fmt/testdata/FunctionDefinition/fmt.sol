@@ -329,6 +329,16 @@ contract FunctionDefinitions {
         a = 1;
     }
 
+    function oneParamManyModifiers(uint256 x)
+        modifier1
+        modifier2
+        modifier3
+        modifier4
+        modifier5
+    {
+        a = 1;
+    }
+
     function manyParams(
         uint256 x1,
         uint256 x2,
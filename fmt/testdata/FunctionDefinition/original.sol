@@ -76,6 +76,10 @@ contract FunctionDefinitions {
         a = 1;
     }
 
+    function oneParamManyModifiers(uint x) modifier1 modifier2 modifier3 modifier4 modifier5 {
+        a = 1;
+    }
+
     function manyParams(uint x1, uint x2, uint x3, uint x4, uint x5, uint x6, uint x7, uint x8, uint x9, uint x10) {
         a = 1;
     }
@@ -6,8 +6,8 @@ library ArrayUtils {
             uint256[] memory r
         )
     {
-        r = new uint[](self.length);
-        for (uint i = 0; i < self.length; i++) {
+        r = new uint256[](self.length);
+        for (uint256 i = 0; i < self.length; i++) {
             r[i] = f(self[i]);
         }
     }
@@ -17,14 +17,14 @@ library ArrayUtils {
         function (uint, uint) pure returns (uint) f
     ) internal pure returns (uint256 r) {
         r = self[0];
-        for (uint i = 1; i < self.length; i++) {
+        for (uint256 i = 1; i < self.length; i++) {
             r = f(r, self[i]);
         }
     }
 
     function range(uint256 length) internal pure returns (uint256[] memory r) {
-        r = new uint[](length);
-        for (uint i = 0; i < r.length; i++) {
+        r = new uint256[](length);
+        for (uint256 i = 0; i < r.length; i++) {
             r[i] = i;
         }
     }
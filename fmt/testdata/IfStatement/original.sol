@@ -0,0 +1,23 @@
+contract IfStatement {
+    function test() {
+        if (true)
+        {
+            run();
+        }
+
+        if (true)  run();
+
+        if (condition) {
+            run();
+        } else if (other) {
+            run2();
+        }
+        else {
+            run3();
+        }
+
+        if (a) run(); else run2();
+
+        if (veryLongConditionNumberOne && veryLongConditionNumberTwo) executeSomething();
+    }
+}
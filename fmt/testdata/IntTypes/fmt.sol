@@ -0,0 +1,11 @@
+contract Contract {
+    uint256 constant UINT = 0;
+    uint8 constant UINT8 = 1;
+    uint256 constant UINT256 = 2;
+    int256 constant INT = 3;
+    int256 constant INT256 = 4;
+
+    function test(uint256 a, int256 b) public {
+        uint256 c = uint256(a);
+    }
+}
@@ -0,0 +1,12 @@
+// config: int-types="preserve"
+contract Contract {
+    uint constant UINT = 0;
+    uint8 constant UINT8 = 1;
+    uint256 constant UINT256 = 2;
+    int constant INT = 3;
+    int256 constant INT256 = 4;
+
+    function test(uint a, int256 b) public {
+        uint256 c = uint(a);
+    }
+}
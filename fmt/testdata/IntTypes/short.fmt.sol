@@ -0,0 +1,12 @@
+// config: int-types="short"
+contract Contract {
+    uint constant UINT = 0;
+    uint8 constant UINT8 = 1;
+    uint constant UINT256 = 2;
+    int constant INT = 3;
+    int constant INT256 = 4;
+
+    function test(uint a, int b) public {
+        uint c = uint(a);
+    }
+}
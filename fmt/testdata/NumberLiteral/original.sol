@@ -0,0 +1,13 @@
+contract NumberLiteral {
+    function test() external {
+        1;
+        123_000;
+        1_000e18;
+        10000;
+        100000;
+        1000000;
+        100000.5;
+        0.1;
+        2.5e1;
+    }
+}
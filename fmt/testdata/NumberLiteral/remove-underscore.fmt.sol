@@ -0,0 +1,14 @@
+// config: number-underscore="remove"
+contract NumberLiteral {
+    function test() external {
+        1;
+        123000;
+        1000e18;
+        10000;
+        100000;
+        1000000;
+        100000.5;
+        0.1;
+        2.5e1;
+    }
+}
@@ -0,0 +1,14 @@
+// config: number-underscore="thousands"
+contract NumberLiteral {
+    function test() external {
+        1;
+        123_000;
+        1000e18;
+        10_000;
+        100_000;
+        1_000_000;
+        100_000.5;
+        0.1;
+        2.5e1;
+    }
+}
@@ -0,0 +1,17 @@
+contract OperatorExpressions {
+    function test() {
+        uint256 expr001 = (1 + 2) * 3;
+        bool expr002 = !(a && b) || c;
+        expr003 = a ? b : c;
+        x += y;
+        i++;
+        --j;
+        delete arr[0];
+        uint256 expr004 = veryLongVariableNameNumberOne +
+            veryLongVariableNameNumberTwo +
+            three;
+        expr005 = veryLongConditionExpression
+            ? veryLongTrueExpression
+            : veryLongFalseExpression;
+    }
+}
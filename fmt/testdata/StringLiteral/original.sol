@@ -0,0 +1,9 @@
+contract StringLiteral {
+    function test() external {
+        "hello";
+        'world';
+        "don't";
+        'say "hi"';
+        "a" 'b';
+    }
+}
@@ -0,0 +1,10 @@
+// config: quote-style="single"
+contract StringLiteral {
+    function test() external {
+        'hello';
+        'world';
+        "don't";
+        'say "hi"';
+        'a' 'b';
+    }
+}
@@ -0,0 +1,17 @@
+contract TryStatement {
+    function test() {
+        try unknown.lookup() returns (uint256 value) {
+            result = value;
+        } catch Error(string memory reason) {
+            revert(reason);
+        } catch (bytes memory data) {
+            emit Failed(data);
+        }
+
+        try unknown.empty() {
+            run();
+        } catch {
+            fail();
+        }
+    }
+}
@@ -0,0 +1,16 @@
+contract WhileStatement {
+    function test() {
+        while(i<10){
+            i++;
+        }
+
+        while (i < 10)  i++;
+
+        do {
+            i++;
+        }
+        while (i < 10);
+
+        do i++; while (i < 10);
+    }
+}
@@ -0,0 +1,26 @@
+contract Yul {
+    function test() {
+        assembly {
+            let x := add(1, 2)
+            let y, z := f()
+            if lt(x, 3) {
+                x := 3
+            }
+            for {let i := 0} lt(i, 10) {i := add(i, 1)} {
+                mstore(i, x)
+            }
+            switch x
+            case 0 {
+                revert(0, 0)
+            }
+            default {
+                stop()
+            }
+
+            function f() -> a, b {
+                a := 1
+                b := 2
+            }
+        }
+    }
+}
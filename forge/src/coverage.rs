@@ -0,0 +1,255 @@
+use comfy_table::{modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL, *};
+use ethers::solc::sourcemap::SourceMap;
+use foundry_evm::revm::opcode;
+use std::{collections::BTreeMap, fmt::Display, io};
+
+pub use foundry_evm::coverage::{HitMap, HitMaps};
+
+/// Line and branch coverage of a set of source files, built from the program counters hit during
+/// test runs and the source maps of the executed bytecode.
+#[derive(Debug, Default)]
+pub struct CoverageReport {
+    /// Coverage per source file, `path -> coverage`
+    pub files: BTreeMap<String, FileCoverage>,
+    /// The source files coverage is reported for, `source id -> file`
+    sources: BTreeMap<u32, SourceFile>,
+}
+
+/// A source file coverage is reported for
+#[derive(Debug)]
+struct SourceFile {
+    /// The path of the file
+    path: String,
+    /// The byte offsets at which each line of the file starts
+    line_starts: Vec<usize>,
+}
+
+impl SourceFile {
+    /// Returns the (1-based) line number of the given byte offset
+    fn line(&self, offset: usize) -> usize {
+        self.line_starts.partition_point(|start| *start <= offset)
+    }
+}
+
+/// The coverage of a single source file
+#[derive(Clone, Debug, Default)]
+pub struct FileCoverage {
+    /// The number of times each line with executable code was hit, `line -> hits`
+    pub lines: BTreeMap<usize, u64>,
+    /// The number of times each branch jumped and did not jump,
+    /// `(line, source offset) -> (taken, not taken)`
+    pub branches: BTreeMap<(usize, usize), (u64, u64)>,
+}
+
+impl FileCoverage {
+    /// Returns the number of lines that were hit at least once
+    pub fn lines_hit(&self) -> usize {
+        self.lines.values().filter(|hits| **hits > 0).count()
+    }
+
+    /// Returns the number of branch directions found, two for every branch
+    pub fn branches_found(&self) -> usize {
+        self.branches.len() * 2
+    }
+
+    /// Returns the number of branch directions that were taken at least once
+    pub fn branches_hit(&self) -> usize {
+        self.branches
+            .values()
+            .map(|(taken, not_taken)| (*taken > 0) as usize + (*not_taken > 0) as usize)
+            .sum()
+    }
+}
+
+impl CoverageReport {
+    /// Adds a source file to the report.
+    ///
+    /// Only code that maps to one of the added source files is reported.
+    pub fn add_source(&mut self, id: u32, path: impl Into<String>, source: &str) {
+        let path = path.into();
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(idx, _)| idx + 1))
+            .collect();
+        self.files.entry(path.clone()).or_default();
+        self.sources.insert(id, SourceFile { path, line_starts });
+    }
+
+    /// Adds a piece of bytecode (creation or runtime code) with its source map to the report,
+    /// attributing all hits recorded for it in `maps`.
+    pub fn add_bytecode(&mut self, code: &[u8], source_map: &SourceMap, maps: &HitMaps) {
+        let hit_maps: Vec<&HitMap> = maps.matching(code).collect();
+
+        // The hits of this piece of bytecode, `source id -> line -> hits`. A line is considered to
+        // be hit as often as its most executed instruction
+        let mut lines: BTreeMap<u32, BTreeMap<usize, u64>> = BTreeMap::new();
+
+        let mut pc = 0;
+        let mut ic = 0;
+        while pc < code.len() && ic < source_map.len() {
+            let op = code[pc];
+            let element = &source_map[ic];
+
+            if let Some((id, source)) = element
+                .index
+                .filter(|_| element.length > 0)
+                .and_then(|id| self.sources.get(&id).map(|source| (id, source)))
+            {
+                let line = source.line(element.offset);
+                let hits = hit_maps.iter().filter_map(|map| map.hits.get(&pc)).sum::<u64>();
+                let line_hits = lines.entry(id).or_default().entry(line).or_default();
+                *line_hits = (*line_hits).max(hits);
+
+                if op == opcode::JUMPI {
+                    let file = self.files.entry(source.path.clone()).or_default();
+                    let branch = file.branches.entry((line, element.offset)).or_default();
+                    for (taken, not_taken) in
+                        hit_maps.iter().filter_map(|map| map.branches.get(&pc))
+                    {
+                        branch.0 += taken;
+                        branch.1 += not_taken;
+                    }
+                }
+            }
+
+            // Skip the push bytes
+            if (opcode::PUSH1..=opcode::PUSH32).contains(&op) {
+                pc += (op - opcode::PUSH1 + 1) as usize;
+            }
+            pc += 1;
+            ic += 1;
+        }
+
+        for (id, lines) in lines {
+            let file = self.files.entry(self.sources[&id].path.clone()).or_default();
+            for (line, hits) in lines {
+                *file.lines.entry(line).or_default() += hits;
+            }
+        }
+    }
+
+    /// Writes the report in the [LCOV](https://github.com/linux-test-project/lcov) tracefile format
+    pub fn write_lcov(&self, mut out: impl io::Write) -> io::Result<()> {
+        for (path, file) in &self.files {
+            writeln!(out, "TN:")?;
+            writeln!(out, "SF:{path}")?;
+
+            for (line, hits) in &file.lines {
+                writeln!(out, "DA:{line},{hits}")?;
+            }
+            writeln!(out, "LF:{}", file.lines.len())?;
+            writeln!(out, "LH:{}", file.lines_hit())?;
+
+            for (block, ((line, _), (taken, not_taken))) in file.branches.iter().enumerate() {
+                // A branch that was never reached is reported as `-`
+                let reached = taken + not_taken > 0;
+                for (branch, hits) in [taken, not_taken].into_iter().enumerate() {
+                    if reached {
+                        writeln!(out, "BRDA:{line},{block},{branch},{hits}")?;
+                    } else {
+                        writeln!(out, "BRDA:{line},{block},{branch},-")?;
+                    }
+                }
+            }
+            writeln!(out, "BRF:{}", file.branches_found())?;
+            writeln!(out, "BRH:{}", file.branches_hit())?;
+
+            writeln!(out, "end_of_record")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for CoverageReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL).apply_modifier(UTF8_ROUND_CORNERS);
+        table.set_header(vec![
+            Cell::new("File").add_attribute(Attribute::Bold),
+            Cell::new("% Lines").add_attribute(Attribute::Bold),
+            Cell::new("% Branches").add_attribute(Attribute::Bold),
+        ]);
+
+        let (mut lines_hit, mut lines_found, mut branches_hit, mut branches_found) = (0, 0, 0, 0);
+        for (path, file) in &self.files {
+            table.add_row(vec![
+                Cell::new(path),
+                format_coverage(file.lines_hit(), file.lines.len()),
+                format_coverage(file.branches_hit(), file.branches_found()),
+            ]);
+
+            lines_hit += file.lines_hit();
+            lines_found += file.lines.len();
+            branches_hit += file.branches_hit();
+            branches_found += file.branches_found();
+        }
+
+        table.add_row(vec![
+            Cell::new("Total").add_attribute(Attribute::Bold),
+            format_coverage(lines_hit, lines_found),
+            format_coverage(branches_hit, branches_found),
+        ]);
+
+        writeln!(f, "{}", table)
+    }
+}
+
+/// Formats a coverage ratio as a colored table cell, e.g. `75.00% (3/4)`
+fn format_coverage(hit: usize, total: usize) -> Cell {
+    let percentage = if total == 0 { 100.0 } else { hit as f64 / total as f64 * 100.0 };
+    let color = match percentage {
+        p if p >= 90.0 => Color::Green,
+        p if p >= 50.0 => Color::Yellow,
+        _ => Color::Red,
+    };
+    Cell::new(format!("{percentage:.2}% ({hit}/{total})")).fg(color)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::solc::sourcemap;
+
+    #[test]
+    fn can_build_line_and_branch_coverage() {
+        // PUSH1 0x01, PUSH1 0x00, JUMPI, STOP
+        let code = vec![0x60, 0x01, 0x60, 0x00, 0x57, 0x00];
+        let source_map = sourcemap::parse("0:1:0;2:1;4:1;2:1").unwrap();
+
+        let mut map = HitMap::new(code.clone().into());
+        map.hit(0);
+        map.hit(2);
+        map.hit(4);
+        map.branch(4, false);
+        let mut maps = HitMaps::default();
+        maps.0.insert(Default::default(), map);
+
+        let mut report = CoverageReport::default();
+        report.add_source(0, "src/A.sol", "a\nb\nc\n");
+        report.add_bytecode(&code, &source_map, &maps);
+
+        let file = &report.files["src/A.sol"];
+        assert_eq!(file.lines, BTreeMap::from([(1, 1), (2, 1), (3, 1)]));
+        assert_eq!(file.branches, BTreeMap::from([((3, 4), (0, 1))]));
+        assert_eq!(file.branches_hit(), 1);
+
+        let mut lcov = Vec::new();
+        report.write_lcov(&mut lcov).unwrap();
+        assert_eq!(
+            String::from_utf8(lcov).unwrap(),
+            "TN:\nSF:src/A.sol\nDA:1,1\nDA:2,1\nDA:3,1\nLF:3\nLH:3\n\
+             BRDA:3,0,0,0\nBRDA:3,0,1,1\nBRF:2\nBRH:1\nend_of_record\n"
+        );
+    }
+
+    #[test]
+    fn ignores_unknown_sources() {
+        let code = vec![0x60, 0x01, 0x00];
+        let source_map = sourcemap::parse("0:1:1;2:1").unwrap();
+
+        let mut report = CoverageReport::default();
+        report.add_source(0, "src/A.sol", "a\nb\n");
+        report.add_bytecode(&code, &source_map, &HitMaps::default());
+
+        assert!(report.files["src/A.sol"].lines.is_empty());
+    }
+}
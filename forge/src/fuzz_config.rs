@@ -0,0 +1,415 @@
+use eyre::{eyre, Result};
+use foundry_config::FuzzConfig;
+use proptest::test_runner::TestRunner;
+use solang_parser::pt::{
+    Base, CommentType, ContractPart, DocComment, Expression, SourceUnit, SourceUnitPart,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The prefix of comments that configure the test contract or test below them
+const INLINE_CONFIG_PREFIX: &str = "forge-config:";
+
+/// The fuzz settings of a test contract and its tests.
+///
+/// The settings are collected from the `fuzz` tables of the config and the
+/// `/// forge-config: fuzz.<key> = <value>` doc comments in the sources of the contract and its
+/// base contracts.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContractFuzzConfig {
+    /// The settings of the contract
+    pub contract: FuzzConfig,
+    /// The settings of the individual tests, by test name
+    pub tests: BTreeMap<String, FuzzConfig>,
+}
+
+impl ContractFuzzConfig {
+    /// Collects the fuzz settings of the contract `<identifier>` named `name` in the source at
+    /// `path`.
+    ///
+    /// A test setting takes precedence over a contract setting and an inline setting takes
+    /// precedence over a setting of the config.
+    pub fn new(
+        name: &str,
+        identifier: &str,
+        path: &str,
+        inline_configs: &InlineFuzzConfigs,
+        config: &BTreeMap<String, FuzzConfig>,
+    ) -> Self {
+        let mut fuzz_config = inline_configs.resolve(path, name);
+
+        let contract_config = |key: &str| config.get(key).copied().unwrap_or_default();
+        fuzz_config.contract =
+            fuzz_config.contract.or(contract_config(identifier).or(contract_config(name)));
+
+        for (key, test_config) in config {
+            let test = key
+                .strip_prefix(identifier)
+                .or_else(|| key.strip_prefix(name))
+                .and_then(|test| test.strip_prefix('.'));
+            if let Some(test) = test {
+                let entry = fuzz_config.tests.entry(test.to_string()).or_default();
+                *entry = entry.or(*test_config);
+            }
+        }
+
+        fuzz_config
+    }
+
+    /// Returns the settings of the given test
+    pub fn test(&self, test: &str) -> FuzzConfig {
+        self.tests.get(test).copied().unwrap_or_default().or(self.contract)
+    }
+
+    /// Applies the settings of the given test to the fuzzer
+    pub fn fuzzer(&self, test: &str, mut fuzzer: TestRunner) -> TestRunner {
+        let fuzz_config = self.test(test);
+        if fuzz_config == FuzzConfig::default() {
+            return fuzzer
+        }
+
+        let mut config = fuzzer.config().clone();
+        if let Some(runs) = fuzz_config.runs {
+            config.cases = runs;
+        }
+        if let Some(max_local_rejects) = fuzz_config.max_local_rejects {
+            config.max_local_rejects = max_local_rejects;
+        }
+        if let Some(max_global_rejects) = fuzz_config.max_global_rejects {
+            config.max_global_rejects = max_global_rejects;
+        }
+        TestRunner::new_with_rng(config, fuzzer.rng().clone())
+    }
+}
+
+/// The `forge-config` settings written in the sources of a project
+#[derive(Debug, Clone, Default)]
+pub struct InlineFuzzConfigs {
+    /// The settings of the contracts of each source, by source path and contract name
+    sources: BTreeMap<String, BTreeMap<String, InlineContractConfig>>,
+}
+
+/// The `forge-config` settings written in a contract
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct InlineContractConfig {
+    /// The settings above the contract
+    contract: FuzzConfig,
+    /// The names of the contracts it inherits from, in declaration order
+    bases: Vec<String>,
+    /// The settings above each function, by function name
+    functions: BTreeMap<String, FuzzConfig>,
+}
+
+impl InlineFuzzConfigs {
+    /// Adds the settings of the contracts in the source at `path`.
+    ///
+    /// A source that can't be parsed is skipped, since it is checked by the compiler anyway.
+    pub fn add_source(&mut self, path: &str, source: &str) -> Result<()> {
+        let (source_unit, _comments) = match solang_parser::parse(source, 0) {
+            Ok(parsed) => parsed,
+            Err(diags) => {
+                tracing::warn!(?diags, path, "failed to parse the forge-config comments");
+                return Ok(())
+            }
+        };
+        self.sources.insert(path.to_string(), parse_inline_config(source, &source_unit)?);
+        Ok(())
+    }
+
+    /// Returns the path and settings of the contract named `name`, preferring the contract in
+    /// the source at `path`
+    fn contract(&self, path: &str, name: &str) -> Option<(&str, &InlineContractConfig)> {
+        let contract = |(path, contracts): (&String, &BTreeMap<String, InlineContractConfig>)| {
+            contracts.get(name).map(|contract| (path.as_str(), contract))
+        };
+        self.sources
+            .get_key_value(path)
+            .and_then(contract)
+            .or_else(|| self.sources.iter().find_map(contract))
+    }
+
+    /// Collects the settings of the contract named `name` in the source at `path` and of the
+    /// tests it declares or inherits.
+    ///
+    /// The settings of a test are the ones above its most derived declaration. The settings
+    /// above a base contract apply to the tests declared in the base contract.
+    fn resolve(&self, path: &str, name: &str) -> ContractFuzzConfig {
+        let mut fuzz_config = ContractFuzzConfig::default();
+        if let Some((_, contract)) = self.contract(path, name) {
+            fuzz_config.contract = contract.contract;
+        }
+        self.inherit(path, name, false, &mut fuzz_config.tests, &mut BTreeSet::new());
+        fuzz_config.tests.retain(|_, test_config| *test_config != FuzzConfig::default());
+        fuzz_config
+    }
+
+    /// Adds the settings of the functions of the contract and its bases that aren't overridden
+    /// yet, from the most derived to the most base contract
+    fn inherit(
+        &self,
+        path: &str,
+        name: &str,
+        is_base: bool,
+        tests: &mut BTreeMap<String, FuzzConfig>,
+        visited: &mut BTreeSet<(String, String)>,
+    ) {
+        let (path, contract) = match self.contract(path, name) {
+            Some((path, contract)) => (path, contract),
+            None => return,
+        };
+        if !visited.insert((path.to_string(), name.to_string())) {
+            return
+        }
+
+        for (function, function_config) in &contract.functions {
+            let function_config =
+                if is_base { function_config.or(contract.contract) } else { *function_config };
+            tests.entry(function.clone()).or_insert(function_config);
+        }
+        // the last base is the most derived one
+        for base in contract.bases.iter().rev() {
+            self.inherit(path, base, true, tests, visited);
+        }
+    }
+}
+
+/// Parses the `forge-config` doc comments of the contracts and their functions in a source
+fn parse_inline_config(
+    source: &str,
+    source_unit: &SourceUnit,
+) -> Result<BTreeMap<String, InlineContractConfig>> {
+    let mut contracts = BTreeMap::new();
+    // The settings of the doc comments above the current definition
+    let mut pending = FuzzConfig::default();
+
+    for part in &source_unit.0 {
+        match part {
+            SourceUnitPart::DocComment(doc) => {
+                parse_doc_comment(source, doc, &mut pending)?;
+                continue
+            }
+            SourceUnitPart::ContractDefinition(contract) => {
+                let mut contract_config = InlineContractConfig {
+                    contract: pending,
+                    bases: contract.base.iter().filter_map(base_name).collect(),
+                    ..Default::default()
+                };
+
+                let mut pending = FuzzConfig::default();
+                for part in &contract.parts {
+                    match part {
+                        ContractPart::DocComment(doc) => {
+                            parse_doc_comment(source, doc, &mut pending)?;
+                            continue
+                        }
+                        ContractPart::FunctionDefinition(function) => {
+                            if let Some(name) = &function.name {
+                                let entry =
+                                    contract_config.functions.entry(name.name.clone()).or_default();
+                                *entry = entry.or(pending);
+                            }
+                        }
+                        _ => {}
+                    }
+                    pending = FuzzConfig::default();
+                }
+
+                contracts.insert(contract.name.name.clone(), contract_config);
+            }
+            _ => {}
+        }
+        pending = FuzzConfig::default();
+    }
+
+    Ok(contracts)
+}
+
+/// Applies the `forge-config` settings of the doc comment to `config`
+fn parse_doc_comment(source: &str, doc: &DocComment, config: &mut FuzzConfig) -> Result<()> {
+    let first_line = source[..doc.loc.start()].matches('\n').count() + 1;
+    for (idx, line) in doc.comment.lines().enumerate() {
+        let mut line = line.trim();
+        if doc.ty == CommentType::Block {
+            // the lines of a block comment usually start with `*`
+            line = line.strip_prefix('*').unwrap_or(line).trim_start();
+        }
+        if let Some(setting) = line.strip_prefix(INLINE_CONFIG_PREFIX) {
+            let err = |msg: String| eyre!("line {}: {}", first_line + idx, msg);
+            let (key, value) = setting
+                .split_once('=')
+                .ok_or_else(|| err(format!("invalid setting `{}`", setting.trim())))?;
+            let key = key
+                .trim()
+                .strip_prefix("fuzz.")
+                .ok_or_else(|| err(format!("unknown setting `{}`", key.trim())))?;
+            config.set(key, value).map_err(err)?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the name of the inherited contract, without its namespace
+fn base_name(base: &Base) -> Option<String> {
+    match &base.name {
+        Expression::Variable(ident) | Expression::MemberAccess(_, _, ident) => {
+            Some(ident.name.clone())
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+        /// forge-config: fuzz.runs = 10
+        contract OtherTest {
+            /// forge-config: fuzz.runs = 20
+            function testOther(uint256) public {}
+        }
+
+        /// forge-config: fuzz.runs = 1_000
+        contract MyTest is DSTest {
+            /// forge-config: fuzz.runs = 100
+            /// forge-config: fuzz.max_local_rejects = 5
+            function testFew(uint256) public {}
+
+            /**
+             * forge-config: fuzz.max_global_rejects = 7
+             */
+            function testRejects(uint256) public {}
+
+            /// forge-config: fuzz.runs = 100
+            uint256 value;
+
+            /*
+             * forge-config: fuzz.runs = 3
+             */
+            function testDefault(uint256) public {}
+        }
+    "#;
+
+    const BASE_SOURCE: &str = r#"
+        /// forge-config: fuzz.runs = 50
+        abstract contract BaseTest {
+            /// forge-config: fuzz.max_local_rejects = 2
+            function testBase(uint256) public {}
+
+            function testBaseDefault(uint256) public {}
+
+            /// forge-config: fuzz.runs = 60
+            function testOverridden(uint256) public virtual {}
+        }
+    "#;
+
+    const DERIVED_SOURCE: &str = r#"
+        import "./Base.sol";
+
+        contract DerivedTest is BaseTest {
+            /// forge-config: fuzz.runs = 5
+            function testOverridden(uint256) public override {}
+
+            function testDerived(uint256) public {}
+        }
+    "#;
+
+    fn inline_configs(sources: &[(&str, &str)]) -> InlineFuzzConfigs {
+        let mut inline_configs = InlineFuzzConfigs::default();
+        for (path, source) in sources {
+            inline_configs.add_source(path, source).unwrap();
+        }
+        inline_configs
+    }
+
+    #[test]
+    fn parses_inline_config() {
+        let fuzz_config =
+            inline_configs(&[("test/My.t.sol", SOURCE)]).resolve("test/My.t.sol", "MyTest");
+        assert_eq!(fuzz_config.contract, FuzzConfig { runs: Some(1000), ..Default::default() });
+        assert_eq!(
+            fuzz_config.tests,
+            BTreeMap::from([
+                (
+                    "testFew".to_string(),
+                    FuzzConfig {
+                        runs: Some(100),
+                        max_local_rejects: Some(5),
+                        ..Default::default()
+                    }
+                ),
+                (
+                    "testRejects".to_string(),
+                    FuzzConfig { max_global_rejects: Some(7), ..Default::default() }
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_inline_config() {
+        for setting in ["fuzz.runs = many", "fuzz.depth = 1", "runs = 1"] {
+            let source = format!("/// forge-config: {setting}\ncontract MyTest {{}}");
+            assert!(InlineFuzzConfigs::default().add_source("test/My.t.sol", &source).is_err());
+        }
+    }
+
+    #[test]
+    fn inherits_inline_config() {
+        let fuzz_config = inline_configs(&[
+            ("test/Base.sol", BASE_SOURCE),
+            ("test/Derived.t.sol", DERIVED_SOURCE),
+        ])
+        .resolve("test/Derived.t.sol", "DerivedTest");
+        assert_eq!(fuzz_config.contract, FuzzConfig::default());
+        assert_eq!(
+            fuzz_config.tests,
+            BTreeMap::from([
+                (
+                    "testBase".to_string(),
+                    FuzzConfig { runs: Some(50), max_local_rejects: Some(2), ..Default::default() }
+                ),
+                (
+                    "testBaseDefault".to_string(),
+                    FuzzConfig { runs: Some(50), ..Default::default() }
+                ),
+                ("testOverridden".to_string(), FuzzConfig { runs: Some(5), ..Default::default() }),
+            ])
+        );
+    }
+
+    #[test]
+    fn merges_config_tables() {
+        let config = BTreeMap::from([
+            ("MyTest".to_string(), FuzzConfig { max_local_rejects: Some(1), ..Default::default() }),
+            (
+                "test/My.t.sol:MyTest.testFew".to_string(),
+                FuzzConfig { runs: Some(5), max_global_rejects: Some(3), ..Default::default() },
+            ),
+            ("MyTest.testDefault".to_string(), FuzzConfig { runs: Some(50), ..Default::default() }),
+            (
+                "OtherTest.testDefault".to_string(),
+                FuzzConfig { runs: Some(1), ..Default::default() },
+            ),
+        ]);
+        let fuzz_config = ContractFuzzConfig::new(
+            "MyTest",
+            "test/My.t.sol:MyTest",
+            "test/My.t.sol",
+            &inline_configs(&[("test/My.t.sol", SOURCE)]),
+            &config,
+        );
+
+        assert_eq!(
+            fuzz_config.test("testFew"),
+            FuzzConfig { runs: Some(100), max_local_rejects: Some(5), max_global_rejects: Some(3) }
+        );
+        assert_eq!(
+            fuzz_config.test("testDefault"),
+            FuzzConfig { runs: Some(50), max_local_rejects: Some(1), ..Default::default() }
+        );
+        assert_eq!(
+            fuzz_config.test("testUnknown"),
+            FuzzConfig { runs: Some(1000), max_local_rejects: Some(1), ..Default::default() }
+        );
+    }
+}
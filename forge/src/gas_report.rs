@@ -4,8 +4,39 @@ use crate::{
 };
 use comfy_table::{modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL, *};
 use ethers::types::U256;
+use eyre::WrapErr;
 use serde::{Deserialize, Serialize};
-use std::{collections::BTreeMap, fmt::Display};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Display, Write},
+    fs,
+    path::Path,
+    str::FromStr,
+};
+
+/// The formats a gas report can be rendered in
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum GasReportFormat {
+    /// A table for the terminal
+    Table,
+    /// JSON, which can be used as a baseline for later reports
+    Json,
+    /// Markdown tables
+    Markdown,
+}
+
+impl FromStr for GasReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(GasReportFormat::Table),
+            "json" => Ok(GasReportFormat::Json),
+            "markdown" | "md" => Ok(GasReportFormat::Markdown),
+            _ => Err(format!("Unrecognized gas report format `{s}`")),
+        }
+    }
+}
 
 #[derive(Default, Debug, Serialize, Deserialize)]
 pub struct GasReport {
@@ -34,6 +65,14 @@ impl GasReport {
         Self { report_for, ..Default::default() }
     }
 
+    /// Reads a report in JSON format, e.g. a baseline for [GasReport::diff]
+    pub fn read(path: &Path) -> eyre::Result<Self> {
+        let report = fs::read_to_string(path)
+            .wrap_err_with(|| format!("Failed to read gas report {}", path.display()))?;
+        serde_json::from_str(&report)
+            .wrap_err_with(|| format!("Invalid gas report {}", path.display()))
+    }
+
     pub fn analyze(&mut self, traces: &[(TraceKind, CallTraceArena)]) {
         let report_for_all = self.report_for.is_empty() || self.report_for.iter().any(|s| s == "*");
         traces.iter().for_each(|(_, trace)| {
@@ -108,6 +147,276 @@ impl GasReport {
         });
         self
     }
+
+    /// Renders the report in the given format
+    pub fn render(&self, format: GasReportFormat) -> serde_json::Result<String> {
+        Ok(match format {
+            GasReportFormat::Table => self.to_string(),
+            GasReportFormat::Json => serde_json::to_string_pretty(self)?,
+            GasReportFormat::Markdown => self.to_markdown(),
+        })
+    }
+
+    /// Returns the report as markdown tables, one for each contract
+    pub fn to_markdown(&self) -> String {
+        markdown_tables(
+            self.contracts.iter().map(|(name, contract)| {
+                (name, [contract.gas.to_string(), contract.size.to_string()], &contract.functions)
+            }),
+            |function| {
+                [
+                    function.min.to_string(),
+                    function.mean.to_string(),
+                    function.median.to_string(),
+                    function.max.to_string(),
+                    function.calls.len().to_string(),
+                ]
+            },
+        )
+    }
+
+    /// Compares the report to the given baseline report.
+    ///
+    /// Both reports are expected to be finalized.
+    pub fn diff(&self, baseline: &GasReport) -> GasReportDiff {
+        let names: BTreeSet<_> = self.contracts.keys().chain(baseline.contracts.keys()).collect();
+        let contracts = names
+            .into_iter()
+            .map(|name| {
+                let current = self.contracts.get(name);
+                let old = baseline.contracts.get(name);
+                let delta = |f: fn(&ContractInfo) -> U256| GasDelta {
+                    baseline: old.map(f),
+                    current: current.map(f),
+                };
+
+                let functions: BTreeSet<_> = current
+                    .into_iter()
+                    .chain(old)
+                    .flat_map(|contract| contract.functions.keys())
+                    .collect();
+                let functions = functions
+                    .into_iter()
+                    .map(|fname| {
+                        let current = current.and_then(|c| c.functions.get(fname));
+                        let old = old.and_then(|c| c.functions.get(fname));
+                        let delta = |f: fn(&GasInfo) -> U256| GasDelta {
+                            baseline: old.map(f),
+                            current: current.map(f),
+                        };
+                        let diff = FunctionDiff {
+                            min: delta(|f| f.min),
+                            mean: delta(|f| f.mean),
+                            median: delta(|f| f.median),
+                            max: delta(|f| f.max),
+                            calls: current.map(|f| f.calls.len()).unwrap_or_default(),
+                        };
+                        (fname.clone(), diff)
+                    })
+                    .collect();
+
+                let diff =
+                    ContractDiff { gas: delta(|c| c.gas), size: delta(|c| c.size), functions };
+                (name.clone(), diff)
+            })
+            .collect();
+        GasReportDiff { contracts }
+    }
+}
+
+/// The header of the function rows of a report
+const FUNCTION_HEADER: [&str; 6] = ["Function Name", "min", "avg", "median", "max", "# calls"];
+
+/// Returns a markdown table for every contract that has functions
+///
+/// The contracts are given by their name, deployment cost and size and functions, `function_row`
+/// returns the min, avg, median and max gas and the number of calls of a function.
+fn markdown_tables<'a, F: 'a>(
+    contracts: impl Iterator<Item = (&'a String, [String; 2], &'a BTreeMap<String, F>)>,
+    function_row: impl Fn(&F) -> [String; 5],
+) -> String {
+    let mut out = String::new();
+    for (name, deployment, functions) in contracts {
+        if functions.is_empty() {
+            continue
+        }
+
+        let mut rows = vec![
+            vec!["Deployment Cost".to_string(), "Deployment Size".to_string()],
+            deployment.to_vec(),
+            FUNCTION_HEADER.iter().map(|s| s.to_string()).collect(),
+        ];
+        rows.extend(functions.iter().map(|(fname, function)| {
+            std::iter::once(fname.to_string()).chain(function_row(function)).collect()
+        }));
+        write_markdown_table(&mut out, &format!("{name} contract"), rows);
+    }
+    out
+}
+
+/// Writes a markdown table with the given title and rows, padding all rows to the same width
+fn write_markdown_table(out: &mut String, title: &str, rows: Vec<Vec<String>>) {
+    let width = rows.iter().map(Vec::len).max().unwrap_or_default();
+    let write_row = |out: &mut String, cells: &[String]| {
+        let cells = (0..width).map(|i| cells.get(i).map(String::as_str).unwrap_or_default());
+        let _ = writeln!(out, "| {} |", cells.collect::<Vec<_>>().join(" | "));
+    };
+
+    write_row(out, &[title.to_string()]);
+    let _ = writeln!(out, "|{}", "---|".repeat(width));
+    for row in rows.iter() {
+        write_row(out, row);
+    }
+    out.push('\n');
+}
+
+/// A gas value of a report compared to the same value of a baseline report
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasDelta {
+    /// The value in the baseline report, `None` if it was added
+    pub baseline: Option<U256>,
+    /// The value in the current report, `None` if it was removed
+    pub current: Option<U256>,
+}
+
+impl GasDelta {
+    /// Returns the change of the value, if it is present in both reports
+    pub fn change(&self) -> Option<i128> {
+        let (baseline, current) = (self.baseline?, self.current?);
+        Some(current.as_u128() as i128 - baseline.as_u128() as i128)
+    }
+
+    /// Returns the change of the value in percent of the baseline value
+    pub fn percentage(&self) -> Option<f64> {
+        let baseline = self.baseline.filter(|baseline| !baseline.is_zero())?;
+        Some(self.change()? as f64 / baseline.as_u128() as f64 * 100.)
+    }
+
+    /// Returns the color of the value in the terminal table
+    fn color(&self) -> Color {
+        match self.change() {
+            Some(change) if change > 0 => Color::Red,
+            Some(change) if change < 0 => Color::Green,
+            _ => Color::Reset,
+        }
+    }
+}
+
+impl Display for GasDelta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.baseline, self.current) {
+            (Some(baseline), Some(current)) if baseline == current => write!(f, "{current}"),
+            (Some(_), Some(current)) => {
+                write!(f, "{current} ({:+}", self.change().unwrap_or_default())?;
+                if let Some(percentage) = self.percentage() {
+                    write!(f, ", {percentage:+.2}%")?;
+                }
+                write!(f, ")")
+            }
+            (None, Some(current)) => write!(f, "{current} (new)"),
+            (Some(_), None) => write!(f, "removed"),
+            (None, None) => write!(f, "-"),
+        }
+    }
+}
+
+/// The changes of a gas report compared to a baseline report
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GasReportDiff {
+    pub contracts: BTreeMap<String, ContractDiff>,
+}
+
+/// The changes of the gas usage of a contract
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ContractDiff {
+    pub gas: GasDelta,
+    pub size: GasDelta,
+    pub functions: BTreeMap<String, FunctionDiff>,
+}
+
+/// The changes of the gas usage of a function
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FunctionDiff {
+    pub min: GasDelta,
+    pub mean: GasDelta,
+    pub median: GasDelta,
+    pub max: GasDelta,
+    /// The number of calls in the current report
+    pub calls: usize,
+}
+
+impl GasReportDiff {
+    /// Renders the changes in the given format
+    pub fn render(&self, format: GasReportFormat) -> serde_json::Result<String> {
+        Ok(match format {
+            GasReportFormat::Table => self.to_string(),
+            GasReportFormat::Json => serde_json::to_string_pretty(self)?,
+            GasReportFormat::Markdown => self.to_markdown(),
+        })
+    }
+
+    /// Returns the changes as markdown tables, one for each contract
+    pub fn to_markdown(&self) -> String {
+        markdown_tables(
+            self.contracts.iter().map(|(name, contract)| {
+                (name, [contract.gas.to_string(), contract.size.to_string()], &contract.functions)
+            }),
+            |function| {
+                [
+                    function.min.to_string(),
+                    function.mean.to_string(),
+                    function.median.to_string(),
+                    function.max.to_string(),
+                    function.calls.to_string(),
+                ]
+            },
+        )
+    }
+}
+
+impl Display for GasReportDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, contract) in self.contracts.iter() {
+            if contract.functions.is_empty() {
+                continue
+            }
+
+            let mut table = Table::new();
+            table.load_preset(UTF8_FULL).apply_modifier(UTF8_ROUND_CORNERS);
+            table.set_header(vec![Cell::new(format!("{name} contract"))
+                .add_attribute(Attribute::Bold)
+                .fg(Color::Green)]);
+            table.add_row(vec![
+                Cell::new("Deployment Cost").add_attribute(Attribute::Bold).fg(Color::Cyan),
+                Cell::new("Deployment Size").add_attribute(Attribute::Bold).fg(Color::Cyan),
+            ]);
+            table.add_row(vec![
+                Cell::new(contract.gas.to_string()).fg(contract.gas.color()),
+                Cell::new(contract.size.to_string()).fg(contract.size.color()),
+            ]);
+
+            table.add_row(vec![
+                Cell::new("Function Name").add_attribute(Attribute::Bold).fg(Color::Magenta),
+                Cell::new("min").add_attribute(Attribute::Bold).fg(Color::Green),
+                Cell::new("avg").add_attribute(Attribute::Bold).fg(Color::Yellow),
+                Cell::new("median").add_attribute(Attribute::Bold).fg(Color::Yellow),
+                Cell::new("max").add_attribute(Attribute::Bold).fg(Color::Red),
+                Cell::new("# calls").add_attribute(Attribute::Bold),
+            ]);
+            contract.functions.iter().for_each(|(fname, function)| {
+                table.add_row(vec![
+                    Cell::new(fname.to_string()).add_attribute(Attribute::Bold),
+                    Cell::new(function.min.to_string()).fg(function.min.color()),
+                    Cell::new(function.mean.to_string()).fg(function.mean.color()),
+                    Cell::new(function.median.to_string()).fg(function.median.color()),
+                    Cell::new(function.max.to_string()).fg(function.max.color()),
+                    Cell::new(function.calls.to_string()),
+                ]);
+            });
+            writeln!(f, "{}", table)?
+        }
+        Ok(())
+    }
 }
 
 impl Display for GasReport {
@@ -151,3 +460,52 @@ impl Display for GasReport {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(gas: u64, calls: &[u64]) -> GasReport {
+        let mut report = GasReport::default();
+        let functions = BTreeMap::from([(
+            "transfer".to_string(),
+            GasInfo {
+                calls: calls.iter().map(|gas| (*gas).into()).collect(),
+                ..Default::default()
+            },
+        )]);
+        report.contracts.insert(
+            "Token".to_string(),
+            ContractInfo { gas: gas.into(), size: 100.into(), functions },
+        );
+        report.finalize()
+    }
+
+    #[test]
+    fn diffs_reports() {
+        let baseline = report(1000, &[100, 200]);
+        let mut current = report(900, &[100, 300, 500]);
+        current.contracts.get_mut("Token").unwrap().functions.insert(
+            "approve".to_string(),
+            GasInfo { calls: vec![50.into()], ..Default::default() },
+        );
+        let diff = current.finalize().diff(&baseline);
+
+        let token = &diff.contracts["Token"];
+        assert_eq!(token.gas.change(), Some(-100));
+        assert_eq!(token.gas.to_string(), "900 (-100, -10.00%)");
+        assert_eq!(token.size.to_string(), "100");
+
+        let transfer = &token.functions["transfer"];
+        assert_eq!(transfer.max.to_string(), "500 (+300, +150.00%)");
+        assert_eq!(transfer.median.to_string(), "300 (+150, +100.00%)");
+        assert_eq!(transfer.calls, 3);
+        assert_eq!(token.functions["approve"].min.to_string(), "50 (new)");
+
+        let markdown = diff.to_markdown();
+        assert!(
+            markdown.starts_with("| Token contract |  |  |  |  |  |\n|---|---|---|---|---|---|\n")
+        );
+        assert!(markdown.contains("| transfer | 100 | 300 (+150, +100.00%) |"));
+    }
+}
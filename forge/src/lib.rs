@@ -1,10 +1,17 @@
 /// Gas reports
 pub mod gas_report;
 
+/// Coverage reports
+pub mod coverage;
+
 /// The Forge test runner
 mod runner;
 pub use runner::{ContractRunner, SuiteResult, TestKind, TestKindGas, TestResult};
 
+/// Fuzz settings of individual test contracts and tests
+mod fuzz_config;
+pub use fuzz_config::{ContractFuzzConfig, InlineFuzzConfigs};
+
 /// Forge test runners for multiple contracts
 mod multi_runner;
 pub use multi_runner::{MultiContractRunner, MultiContractRunnerBuilder};
@@ -35,7 +42,7 @@ pub mod test_helpers {
         fuzz::FuzzedExecutor,
         CALLER,
     };
-    use std::str::FromStr;
+    use std::{str::FromStr, sync::Once};
 
     pub static PROJECT: Lazy<Project> = Lazy::new(|| {
         let paths = ProjectPathsConfig::builder()
@@ -73,6 +80,32 @@ pub mod test_helpers {
     pub static COMPILED_WITH_LIBS: Lazy<ProjectCompileOutput> =
         Lazy::new(|| (*LIBS_PROJECT).compile().unwrap());
 
+    /// The environment variables read by the `env*` cheatcodes in `cheats/Env.t.sol`.
+    ///
+    /// They are set once by [`init_cheats_env`], before any runner is built, because modifying the
+    /// environment while tests run in parallel is not safe.
+    const CHEATS_ENV: [(&str, &str); 6] = [
+        ("_FOUNDRY_CHEATS_ENV_BOOL", "true"),
+        ("_FOUNDRY_CHEATS_ENV_UINT", "1000000"),
+        ("_FOUNDRY_CHEATS_ENV_UINT_HEX", "0x10"),
+        ("_FOUNDRY_CHEATS_ENV_ADDRESS", "0x7109709ECfa91a80626fF3989D68f67F5b1DD12D"),
+        (
+            "_FOUNDRY_CHEATS_ENV_BYTES32",
+            "0x0000000000000000000000000000000000000000000000000000000000000001",
+        ),
+        ("_FOUNDRY_CHEATS_ENV_STRING", "hello, world!"),
+    ];
+
+    /// Sets the environment variables read by the `env*` cheatcodes, only once per process
+    pub fn init_cheats_env() {
+        static INIT: Once = Once::new();
+        INIT.call_once(|| {
+            for (key, value) in CHEATS_ENV {
+                std::env::set_var(key, value);
+            }
+        });
+    }
+
     pub static EVM_OPTS: Lazy<EvmOpts> = Lazy::new(|| EvmOpts {
         env: Env {
             gas_limit: 18446744073709551615,
@@ -94,7 +127,7 @@ pub mod test_helpers {
         ExecutorBuilder::new().with_cheatcodes(false).with_config(env).build(Backend::simple())
     }
 
-    pub fn fuzz_executor<DB: DatabaseRef>(executor: &Executor<DB>) -> FuzzedExecutor<DB> {
+    pub fn fuzz_executor<DB: DatabaseRef + Clone>(executor: &Executor<DB>) -> FuzzedExecutor<DB> {
         let cfg = proptest::test_runner::Config { failure_persistence: None, ..Default::default() };
 
         FuzzedExecutor::new(executor, proptest::test_runner::TestRunner::new(cfg), *CALLER)
@@ -108,6 +141,7 @@ pub mod test_helpers {
             test_regex: Regex,
             contract_regex: Regex,
             path_regex: Regex,
+            exclude_paths: Option<Regex>,
         }
 
         impl Filter {
@@ -116,14 +150,22 @@ pub mod test_helpers {
                     test_regex: Regex::new(test_pattern).unwrap(),
                     contract_regex: Regex::new(contract_pattern).unwrap(),
                     path_regex: Regex::new(path_pattern).unwrap(),
+                    exclude_paths: None,
                 }
             }
 
+            /// Skips the paths that match the pattern, even if they match the path pattern
+            pub fn exclude_paths(mut self, pattern: &str) -> Self {
+                self.exclude_paths = Some(Regex::new(pattern).unwrap());
+                self
+            }
+
             pub fn matches_all() -> Self {
                 Filter {
                     test_regex: Regex::new(".*").unwrap(),
                     contract_regex: Regex::new(".*").unwrap(),
                     path_regex: Regex::new(".*").unwrap(),
+                    exclude_paths: None,
                 }
             }
         }
@@ -138,7 +180,9 @@ pub mod test_helpers {
             }
 
             fn matches_path(&self, path: impl AsRef<str>) -> bool {
-                self.path_regex.is_match(path.as_ref())
+                let path = path.as_ref();
+                self.path_regex.is_match(path) &&
+                    !self.exclude_paths.as_ref().map_or(false, |exclude| exclude.is_match(path))
             }
         }
     }
@@ -1,18 +1,27 @@
-use crate::{ContractRunner, SuiteResult, TestFilter};
+use crate::{ContractFuzzConfig, ContractRunner, InlineFuzzConfigs, SuiteResult, TestFilter};
 use ethers::{
     abi::Abi,
     prelude::{artifacts::CompactContractBytecode, ArtifactId, ArtifactOutput},
     solc::{utils::RuntimeOrHandle, Artifact, ProjectCompileOutput},
     types::{Address, Bytes, U256},
 };
-use eyre::Result;
-use foundry_evm::executor::{
-    builder::Backend, opts::EvmOpts, DatabaseRef, Executor, ExecutorBuilder, Fork, SpecId,
+use eyre::{Result, WrapErr};
+use foundry_config::FuzzConfig;
+use foundry_evm::{
+    executor::{
+        builder::Backend, opts::EvmOpts, DatabaseRef, Executor, ExecutorBuilder, Fork, SpecId,
+    },
+    fuzz::invariant::InvariantConfig,
 };
 use foundry_utils::PostLinkInput;
 use proptest::test_runner::TestRunner;
 use rayon::prelude::*;
-use std::{collections::BTreeMap, marker::Sync, path::Path, sync::mpsc::Sender};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    marker::Sync,
+    path::{Path, PathBuf},
+    sync::mpsc::Sender,
+};
 
 /// Builder used for instantiating the multi-contract runner
 #[derive(Debug, Default)]
@@ -28,6 +37,16 @@ pub struct MultiContractRunnerBuilder {
     pub evm_spec: Option<SpecId>,
     /// The fork config
     pub fork: Option<Fork>,
+    /// The configuration of invariant campaigns
+    pub invariant_config: InvariantConfig,
+    /// Whether or not to collect coverage info
+    pub coverage: bool,
+    /// The paths the filesystem cheatcodes are allowed to access
+    pub fs_allowed_paths: Vec<PathBuf>,
+    /// The directory the failing inputs of fuzz tests are persisted to
+    pub fuzz_corpus_dir: Option<PathBuf>,
+    /// The fuzz settings of individual test contracts and tests, see `Config::fuzz`
+    pub fuzz_configs: BTreeMap<String, FuzzConfig>,
 }
 
 pub type DeployableContracts = BTreeMap<ArtifactId, (Abi, Bytes, Vec<Bytes>)>;
@@ -44,6 +63,7 @@ impl MultiContractRunnerBuilder {
     where
         A: ArtifactOutput,
     {
+        let root = root.as_ref();
         // This is just the contracts compiled, but we need to merge this with the read cached
         // artifacts
         let contracts = output
@@ -87,7 +107,9 @@ impl MultiContractRunnerBuilder {
                 let abi = contract.abi.expect("We should have an abi by now");
                 // if its a test, add it to deployable contracts
                 if abi.constructor.as_ref().map(|c| c.inputs.is_empty()).unwrap_or(true) &&
-                    abi.functions().any(|func| func.name.starts_with("test"))
+                    abi.functions().any(|func| {
+                        func.name.starts_with("test") || func.name.starts_with("invariant")
+                    })
                 {
                     deployable_contracts
                         .insert(id.clone(), (abi.clone(), bytecode, dependencies.to_vec()));
@@ -102,6 +124,33 @@ impl MultiContractRunnerBuilder {
             },
         )?;
 
+        // collect the fuzz settings of the test contracts from the config and the sources, which
+        // include the sources of their base contracts
+        let mut inline_configs = InlineFuzzConfigs::default();
+        if !deployable_contracts.is_empty() {
+            for source in source_paths.values().collect::<BTreeSet<_>>() {
+                let path = root.join(source);
+                let content = std::fs::read_to_string(&path)
+                    .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+                inline_configs
+                    .add_source(source, &content)
+                    .wrap_err_with(|| format!("Invalid forge-config in {source}"))?;
+            }
+        }
+        let mut fuzz_configs = BTreeMap::new();
+        for id in deployable_contracts.keys() {
+            let fuzz_config = ContractFuzzConfig::new(
+                &id.name,
+                &id.identifier(),
+                &id.source.to_string_lossy(),
+                &inline_configs,
+                &self.fuzz_configs,
+            );
+            if fuzz_config != ContractFuzzConfig::default() {
+                fuzz_configs.insert(id.identifier(), fuzz_config);
+            }
+        }
+
         let execution_info = foundry_utils::flatten_known_contracts(&known_contracts);
         Ok(MultiContractRunner {
             contracts: deployable_contracts,
@@ -113,6 +162,11 @@ impl MultiContractRunnerBuilder {
             errors: Some(execution_info.2),
             source_paths,
             fork: self.fork,
+            invariant_config: self.invariant_config,
+            coverage: self.coverage,
+            fs_allowed_paths: self.fs_allowed_paths,
+            fuzz_corpus_dir: self.fuzz_corpus_dir,
+            fuzz_configs,
         })
     }
 
@@ -145,6 +199,36 @@ impl MultiContractRunnerBuilder {
         self.fork = fork;
         self
     }
+
+    #[must_use]
+    pub fn invariant_config(mut self, invariant_config: InvariantConfig) -> Self {
+        self.invariant_config = invariant_config;
+        self
+    }
+
+    #[must_use]
+    pub fn set_coverage(mut self, enable: bool) -> Self {
+        self.coverage = enable;
+        self
+    }
+
+    #[must_use]
+    pub fn fs_allowed_paths(mut self, paths: Vec<PathBuf>) -> Self {
+        self.fs_allowed_paths = paths;
+        self
+    }
+
+    #[must_use]
+    pub fn fuzz_corpus_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.fuzz_corpus_dir = Some(dir.into());
+        self
+    }
+
+    #[must_use]
+    pub fn fuzz_configs(mut self, fuzz_configs: BTreeMap<String, FuzzConfig>) -> Self {
+        self.fuzz_configs = fuzz_configs;
+        self
+    }
 }
 
 /// A multi contract runner receives a set of contracts deployed in an EVM instance and proceeds
@@ -169,6 +253,16 @@ pub struct MultiContractRunner {
     pub source_paths: BTreeMap<String, String>,
     /// The fork config
     pub fork: Option<Fork>,
+    /// The configuration of invariant campaigns
+    pub invariant_config: InvariantConfig,
+    /// Whether or not to collect coverage info
+    pub coverage: bool,
+    /// The paths the filesystem cheatcodes are allowed to access
+    pub fs_allowed_paths: Vec<PathBuf>,
+    /// The directory the failing inputs of fuzz tests are persisted to
+    pub fuzz_corpus_dir: Option<PathBuf>,
+    /// The fuzz settings of the test contracts that configure them, by contract identifier
+    pub fuzz_configs: BTreeMap<String, ContractFuzzConfig>,
 }
 
 impl MultiContractRunner {
@@ -194,7 +288,7 @@ impl MultiContractRunner {
                     filter.matches_contract(&id.name)
             })
             .flat_map(|(_, (abi, _, _))| abi.functions().map(|func| func.name.clone()))
-            .filter(|sig| sig.starts_with("test"))
+            .filter(|sig| sig.starts_with("test") || sig.starts_with("invariant"))
             .collect()
     }
 
@@ -214,7 +308,9 @@ impl MultiContractRunner {
                 let name = id.name.clone();
                 let tests = abi
                     .functions()
-                    .filter(|func| func.name.starts_with("test"))
+                    .filter(|func| {
+                        func.name.starts_with("test") || func.name.starts_with("invariant")
+                    })
                     .filter(|func| filter.matches_test(func.signature()))
                     .map(|func| func.name.clone())
                     .collect::<Vec<_>>();
@@ -252,15 +348,23 @@ impl MultiContractRunner {
                     .with_cheatcodes(self.evm_opts.ffi)
                     .with_config(env.clone())
                     .with_spec(self.evm_spec)
-                    .with_gas_limit(self.evm_opts.gas_limit());
+                    .with_gas_limit(self.evm_opts.gas_limit())
+                    .with_fs_allowed_paths(self.fs_allowed_paths.clone());
 
                 if self.evm_opts.verbosity >= 3 {
                     builder = builder.with_tracing();
                 }
+                if self.coverage {
+                    builder = builder.with_coverage();
+                }
+                if let Some(errors) = &self.errors {
+                    builder = builder.with_errors(errors.clone());
+                }
 
                 let executor = builder.build(db.clone());
                 let result = self.run_tests(
                     &id.identifier(),
+                    &id.name,
                     abi,
                     executor,
                     deploy_code.clone(),
@@ -281,16 +385,17 @@ impl MultiContractRunner {
         Ok(results)
     }
 
-    // The _name field is unused because we only want it for tracing
+    #[allow(clippy::too_many_arguments)]
     #[tracing::instrument(
         name = "contract",
         skip_all,
         err,
-        fields(name = %_name)
+        fields(name = %identifier)
     )]
-    fn run_tests<DB: DatabaseRef + Send + Sync>(
+    fn run_tests<DB: DatabaseRef + Clone + Send + Sync>(
         &self,
-        _name: &str,
+        identifier: &str,
+        contract_name: &str,
         contract: &Abi,
         executor: Executor<DB>,
         deploy_code: Bytes,
@@ -298,6 +403,8 @@ impl MultiContractRunner {
         (filter, include_fuzz_tests): (&impl TestFilter, bool),
     ) -> Result<SuiteResult> {
         let mut runner = ContractRunner::new(
+            contract_name,
+            identifier,
             executor,
             contract,
             deploy_code,
@@ -305,8 +412,11 @@ impl MultiContractRunner {
             self.sender,
             self.errors.as_ref(),
             libs,
+            &self.known_contracts,
+            self.fuzz_corpus_dir.as_deref(),
+            self.fuzz_configs.get(identifier).cloned().unwrap_or_default(),
         );
-        runner.run_tests(filter, self.fuzzer.clone(), include_fuzz_tests)
+        runner.run_tests(filter, self.fuzzer.clone(), self.invariant_config, include_fuzz_tests)
     }
 }
 
@@ -316,18 +426,25 @@ mod tests {
     use crate::{
         decode::decode_console_logs,
         test_helpers::{
-            filter::Filter, COMPILED, COMPILED_WITH_LIBS, EVM_OPTS, LIBS_PROJECT, PROJECT,
+            filter::Filter, init_cheats_env, COMPILED, COMPILED_WITH_LIBS, EVM_OPTS, LIBS_PROJECT,
+            PROJECT,
         },
+        TestKind,
     };
-    use foundry_evm::trace::TraceKind;
+    use ethers::abi::Token;
+    use foundry_evm::{fuzz::CounterExample, trace::TraceKind};
 
     /// Builds a base runner
     fn base_runner() -> MultiContractRunnerBuilder {
-        MultiContractRunnerBuilder::default().sender(EVM_OPTS.sender)
+        init_cheats_env();
+        MultiContractRunnerBuilder::default()
+            .sender(EVM_OPTS.sender)
+            .fs_allowed_paths(vec![PathBuf::from("../testdata/fixtures")])
     }
 
     /// Builds a non-tracing runner
     fn runner() -> MultiContractRunner {
+        init_cheats_env();
         base_runner().build(&(*PROJECT).paths.root, (*COMPILED).clone(), EVM_OPTS.clone()).unwrap()
     }
 
@@ -1028,10 +1145,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_expect_revert_decodes_errors() {
+        let mut runner = runner();
+        let suite_result = runner
+            .test(&Filter::new("testFailExpectRevert.*", ".*", ".*cheats/ExpectRevert"), None, true)
+            .unwrap();
+        let test_results = &suite_result["cheats/ExpectRevert.t.sol:ExpectRevertTest"].test_results;
+
+        for (test_name, reason) in [
+            (
+                "testFailExpectRevertCustomErrorArgsDoNotMatch()",
+                "Error != expected error: InsufficientBalance(100, 40) != InsufficientBalance(100, 50)",
+            ),
+            (
+                "testFailExpectRevertPanicCodeDoesNotMatch()",
+                "Error != expected error: Division or modulo by 0 != Arithmetic over/underflow",
+            ),
+            (
+                "testFailExpectRevertErrorDoesNotMatch()",
+                "Error != expected error: 'but reverts with this message' != 'should revert with this message'",
+            ),
+        ] {
+            let result = &test_results[test_name];
+            assert!(result.success, "Test {} did not pass as expected", test_name);
+            assert_eq!(result.reason.as_deref(), Some(reason), "Unexpected reason for {}", test_name);
+        }
+
+        let reason =
+            test_results["testFailExpectRevertCustomErrorDoesNotMatch()"].reason.as_deref();
+        assert!(
+            reason.unwrap().starts_with("Error != expected error: Unauthorized(0x"),
+            "Unexpected reason: {:?}",
+            reason
+        );
+    }
+
     #[test]
     fn test_fuzz() {
         let mut runner = runner();
-        let suite_result = runner.test(&Filter::new(".*", ".*", ".*fuzz"), None, true).unwrap();
+        // the invariant and inline config fixtures are checked by their own tests
+        let filter = Filter::new(".*", ".*", ".*fuzz").exclude_paths("fuzz/(invariant|config)");
+        let suite_result = runner.test(&filter, None, true).unwrap();
 
         for (_, SuiteResult { test_results, .. }) in suite_result {
             for (test_name, result) in test_results {
@@ -1057,6 +1212,99 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_fuzz_shrinks_counterexample() {
+        let cfg = proptest::test_runner::Config { failure_persistence: None, ..Default::default() };
+        let mut runner = base_runner()
+            .fuzzer(TestRunner::new(cfg))
+            .build(&(*PROJECT).paths.root, (*COMPILED).clone(), EVM_OPTS.clone())
+            .unwrap();
+        let results =
+            runner.test(&Filter::new(".*", ".*", ".*fuzz/FuzzShrink"), None, true).unwrap();
+
+        // The smallest failing input is found by shrinking
+        let result = &results["fuzz/FuzzShrink.t.sol:FuzzShrinkTest"].test_results
+            ["testShrinkUint(uint256)"];
+        assert!(!result.success);
+        match &result.counterexample {
+            Some(CounterExample::Single(counterexample)) => {
+                assert_eq!(counterexample.args, vec![Token::Uint(1000.into())]);
+            }
+            _ => panic!("expected a single call as counterexample"),
+        }
+    }
+
+    #[test]
+    fn test_fuzz_config() {
+        let cfg = proptest::test_runner::Config { failure_persistence: None, ..Default::default() };
+        let mut runner = base_runner()
+            .fuzzer(TestRunner::new(cfg))
+            .fuzz_configs(BTreeMap::from([(
+                "InlineConfigTest.testTableRuns".to_string(),
+                FuzzConfig { runs: Some(5), ..Default::default() },
+            )]))
+            .build(&(*PROJECT).paths.root, (*COMPILED).clone(), EVM_OPTS.clone())
+            .unwrap();
+        let results = runner.test(&Filter::new(".*", ".*", ".*fuzz/config"), None, true).unwrap();
+
+        let test_results = &results["fuzz/config/InlineConfig.t.sol:InlineConfigTest"].test_results;
+        for (test, runs) in [
+            ("testContractRuns(uint256)", 10),
+            ("testInlineRuns(uint256)", 20),
+            ("testTableRuns(uint256)", 5),
+            ("testBaseRuns(uint256)", 30),
+        ] {
+            match &test_results[test].kind {
+                TestKind::Fuzz(cases) => assert_eq!(cases.cases().len(), runs, "{}", test),
+                _ => panic!("{} is not a fuzz test", test),
+            }
+        }
+    }
+
+    #[test]
+    fn test_invariant() {
+        let cfg = proptest::test_runner::Config { failure_persistence: None, ..Default::default() };
+        let mut runner = base_runner()
+            .fuzzer(TestRunner::new(cfg))
+            .build(&(*PROJECT).paths.root, (*COMPILED).clone(), EVM_OPTS.clone())
+            .unwrap();
+        let results =
+            runner.test(&Filter::new(".*", ".*", ".*fuzz/invariant"), None, true).unwrap();
+
+        assert_multiple(
+            &results,
+            BTreeMap::from([
+                (
+                    "fuzz/invariant/InvariantTest.t.sol:InvariantTest",
+                    vec![("invariantNeverFalse()", false, Some("false.".into()), None, None)],
+                ),
+                (
+                    "fuzz/invariant/InvariantTest.t.sol:InvariantReturnsBoolTest",
+                    vec![("invariantFlag0()", false, None, None, None)],
+                ),
+                (
+                    "fuzz/invariant/InvariantTest.t.sol:InvariantHoldsTest",
+                    vec![("invariantCountIsBounded()", true, None, None, None)],
+                ),
+                (
+                    "fuzz/invariant/InvariantTest.t.sol:InvariantTargetTest",
+                    vec![("invariantBreakerIsNeverCalled()", true, None, None, None)],
+                ),
+            ]),
+        );
+
+        // The failing call sequence is reported as the counterexample
+        let result = &results["fuzz/invariant/InvariantTest.t.sol:InvariantTest"].test_results
+            ["invariantNeverFalse()"];
+        match &result.counterexample {
+            Some(CounterExample::Sequence(sequence)) => {
+                assert!(!sequence.is_empty());
+                assert!(sequence.len() <= runner.invariant_config.depth as usize);
+            }
+            _ => panic!("expected a call sequence as counterexample"),
+        }
+    }
+
     #[test]
     fn test_trace() {
         let mut runner = tracing_runner();
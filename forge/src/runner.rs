@@ -1,12 +1,21 @@
-use crate::TestFilter;
+use crate::{ContractFuzzConfig, TestFilter};
 use ethers::{
     abi::{Abi, Function},
+    prelude::ArtifactId,
     types::{Address, Bytes, Log, U256},
 };
 use eyre::Result;
 use foundry_evm::{
-    executor::{CallResult, DatabaseRef, DeployResult, EvmError, Executor},
-    fuzz::{CounterExample, FuzzedCases, FuzzedExecutor},
+    coverage::HitMaps,
+    executor::{
+        CallResult, DatabaseRef, DeployResult, EvmError, Executor, CHEATCODE_ADDRESS,
+        HARDHAT_CONSOLE_ADDRESS,
+    },
+    fuzz::{
+        corpus::FuzzCorpus,
+        invariant::{InvariantConfig, InvariantExecutor, TargetedContracts},
+        CounterExample, FuzzedCases, FuzzedExecutor,
+    },
     trace::{CallTraceArena, TraceKind},
     CALLER,
 };
@@ -16,6 +25,7 @@ use serde::{Deserialize, Serialize};
 use std::{
     collections::BTreeMap,
     fmt,
+    path::Path,
     time::{Duration, Instant},
 };
 
@@ -28,6 +38,10 @@ pub struct SuiteResult {
     pub test_results: BTreeMap<String, TestResult>,
     // Warnings
     pub warnings: Vec<String>,
+    /// The coverage info collected once for the whole suite, i.e. during setup and invariant
+    /// campaigns
+    #[serde(skip)]
+    pub coverage: Option<HitMaps>,
 }
 
 impl SuiteResult {
@@ -35,8 +49,9 @@ impl SuiteResult {
         duration: Duration,
         test_results: BTreeMap<String, TestResult>,
         warnings: Vec<String>,
+        coverage: Option<HitMaps>,
     ) -> Self {
-        Self { duration, test_results, warnings }
+        Self { duration, test_results, warnings, coverage }
     }
 
     pub fn is_empty(&self) -> bool {
@@ -76,6 +91,10 @@ pub struct TestResult {
 
     /// Labeled addresses
     pub labeled_addresses: BTreeMap<Address, String>,
+
+    /// The coverage info collected during the test, excluding its setup
+    #[serde(skip)]
+    pub coverage: Option<HitMaps>,
 }
 
 impl TestResult {
@@ -83,13 +102,20 @@ impl TestResult {
     pub fn is_fuzz(&self) -> bool {
         matches!(self.kind, TestKind::Fuzz(_))
     }
+
+    /// Returns `true` if this is the result of an invariant test
+    pub fn is_invariant(&self) -> bool {
+        matches!(self.kind, TestKind::Invariant { .. })
+    }
 }
 
 /// Used gas by a test
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TestKindGas {
     Standard(u64),
     Fuzz { runs: usize, mean: u64, median: u64 },
+    Invariant { runs: usize, calls: usize, reverts: usize },
 }
 
 impl fmt::Display for TestKindGas {
@@ -101,6 +127,9 @@ impl fmt::Display for TestKindGas {
             TestKindGas::Fuzz { runs, mean, median } => {
                 write!(f, "(runs: {}, μ: {}, ~: {})", runs, mean, median)
             }
+            TestKindGas::Invariant { runs, calls, reverts } => {
+                write!(f, "(runs: {}, calls: {}, reverts: {})", runs, calls, reverts)
+            }
         }
     }
 }
@@ -112,6 +141,8 @@ impl TestKindGas {
             TestKindGas::Standard(gas) => *gas,
             // We use the median for comparisons
             TestKindGas::Fuzz { median, .. } => *median,
+            // Invariant campaigns do not measure gas
+            TestKindGas::Invariant { .. } => 0,
         }
     }
 }
@@ -125,6 +156,9 @@ pub enum TestKind {
     Standard(u64),
     /// A solidity fuzz test, that stores all test cases
     Fuzz(FuzzedCases),
+    /// An invariant test, that stores the number of runs, calls and reverted calls of the
+    /// campaign
+    Invariant { runs: usize, calls: usize, reverts: usize },
 }
 
 impl TestKind {
@@ -137,6 +171,9 @@ impl TestKind {
                 median: fuzzed.median_gas(false),
                 mean: fuzzed.mean_gas(false),
             },
+            TestKind::Invariant { runs, calls, reverts } => {
+                TestKindGas::Invariant { runs: *runs, calls: *calls, reverts: *reverts }
+            }
         }
     }
 }
@@ -155,9 +192,15 @@ pub struct TestSetup {
     pub setup_failed: bool,
     /// The reason the setup failed
     pub reason: Option<String>,
+    /// The coverage info collected during setup
+    pub coverage: Option<HitMaps>,
 }
 
 pub struct ContractRunner<'a, DB: DatabaseRef> {
+    /// The name of the test contract
+    pub name: &'a str,
+    /// The artifact identifier of the test contract, `<source>:<name>`
+    pub identifier: &'a str,
     /// The executor used by the runner.
     pub executor: Executor<DB>,
 
@@ -169,16 +212,25 @@ pub struct ContractRunner<'a, DB: DatabaseRef> {
     pub contract: &'a Abi,
     /// All known errors, used to decode reverts
     pub errors: Option<&'a Abi>,
+    /// Compiled contracts by name that have an Abi and runtime bytecode, used to identify
+    /// invariant targets
+    pub known_contracts: &'a BTreeMap<ArtifactId, (Abi, Vec<u8>)>,
 
     /// The initial balance of the test contract
     pub initial_balance: U256,
     /// The address which will be used as the `from` field in all EVM calls
     pub sender: Address,
+    /// The directory the failing inputs of fuzz tests are persisted to
+    pub fuzz_corpus_dir: Option<&'a Path>,
+    /// The fuzz settings of the contract and its tests
+    pub fuzz_config: ContractFuzzConfig,
 }
 
 impl<'a, DB: DatabaseRef> ContractRunner<'a, DB> {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
+        name: &'a str,
+        identifier: &'a str,
         executor: Executor<DB>,
         contract: &'a Abi,
         code: Bytes,
@@ -186,8 +238,13 @@ impl<'a, DB: DatabaseRef> ContractRunner<'a, DB> {
         sender: Option<Address>,
         errors: Option<&'a Abi>,
         predeploy_libs: &'a [Bytes],
+        known_contracts: &'a BTreeMap<ArtifactId, (Abi, Vec<u8>)>,
+        fuzz_corpus_dir: Option<&'a Path>,
+        fuzz_config: ContractFuzzConfig,
     ) -> Self {
         Self {
+            name,
+            identifier,
             executor,
             contract,
             code,
@@ -195,11 +252,14 @@ impl<'a, DB: DatabaseRef> ContractRunner<'a, DB> {
             sender: sender.unwrap_or_default(),
             errors,
             predeploy_libs,
+            known_contracts,
+            fuzz_corpus_dir,
+            fuzz_config,
         }
     }
 }
 
-impl<'a, DB: DatabaseRef + Send + Sync> ContractRunner<'a, DB> {
+impl<'a, DB: DatabaseRef + Clone + Send + Sync> ContractRunner<'a, DB> {
     /// Deploys the test contract inside the runner from the sending account, and optionally runs
     /// the `setUp` function on the test contract.
     pub fn setup(&mut self, setup: bool) -> Result<TestSetup> {
@@ -212,12 +272,14 @@ impl<'a, DB: DatabaseRef + Send + Sync> ContractRunner<'a, DB> {
 
         // Deploy libraries
         let mut traces: Vec<(TraceKind, CallTraceArena)> = vec![];
+        let mut coverage: Option<HitMaps> = None;
         for code in self.predeploy_libs.iter() {
             match self.executor.deploy(self.sender, code.0.clone(), 0u32.into(), self.errors) {
-                Ok(DeployResult { traces: tmp_traces, .. }) => {
+                Ok(DeployResult { traces: tmp_traces, coverage: tmp_coverage, .. }) => {
                     if let Some(tmp_traces) = tmp_traces {
                         traces.push((TraceKind::Deployment, tmp_traces));
                     }
+                    coverage = HitMaps::merge_opt(coverage, tmp_coverage);
                 }
                 Err(EvmError::Execution { reason, traces, logs, labels, .. }) => {
                     // If we failed to call the constructor, force the tracekind to be setup so
@@ -235,6 +297,7 @@ impl<'a, DB: DatabaseRef + Send + Sync> ContractRunner<'a, DB> {
                         labeled_addresses: labels,
                         setup_failed: true,
                         reason: Some(reason),
+                        coverage,
                     })
                 }
                 e => eyre::bail!("Unrecoverable error: {:?}", e),
@@ -242,9 +305,13 @@ impl<'a, DB: DatabaseRef + Send + Sync> ContractRunner<'a, DB> {
         }
 
         // Deploy an instance of the contract
-        let DeployResult { address, mut logs, traces: constructor_traces, .. } = match self
-            .executor
-            .deploy(self.sender, self.code.0.clone(), 0u32.into(), self.errors)
+        let DeployResult {
+            address,
+            mut logs,
+            traces: constructor_traces,
+            coverage: constructor_coverage,
+            ..
+        } = match self.executor.deploy(self.sender, self.code.0.clone(), 0u32.into(), self.errors)
         {
             Ok(d) => d,
             Err(EvmError::Execution { reason, traces, logs, labels, .. }) => {
@@ -261,12 +328,14 @@ impl<'a, DB: DatabaseRef + Send + Sync> ContractRunner<'a, DB> {
                     labeled_addresses: labels,
                     setup_failed: true,
                     reason: Some(reason),
+                    coverage,
                 })
             }
             e => eyre::bail!("Unrecoverable error: {:?}", e),
         };
 
         traces.extend(constructor_traces.map(|traces| (TraceKind::Deployment, traces)).into_iter());
+        coverage = HitMaps::merge_opt(coverage, constructor_coverage);
 
         // Now we set the contracts initial balance, and we also reset `self.sender`s balance to
         // the initial balance we want
@@ -276,13 +345,19 @@ impl<'a, DB: DatabaseRef + Send + Sync> ContractRunner<'a, DB> {
         // Optionally call the `setUp` function
         Ok(if setup {
             tracing::trace!("setting up");
-            let (setup_failed, setup_logs, setup_traces, labeled_addresses, reason) = match self
-                .executor
-                .setup(address)
-            {
-                Ok(CallResult { traces, labels, logs, .. }) => (false, logs, traces, labels, None),
-                Err(EvmError::Execution { traces, labels, logs, reason, .. }) => {
-                    (true, logs, traces, labels, Some(format!("Setup failed: {reason}")))
+            let (
+                setup_failed,
+                setup_logs,
+                setup_traces,
+                labeled_addresses,
+                reason,
+                setup_coverage,
+            ) = match self.executor.setup(address) {
+                Ok(CallResult { traces, labels, logs, coverage, .. }) => {
+                    (false, logs, traces, labels, None, coverage)
+                }
+                Err(EvmError::Execution { traces, labels, logs, reason, coverage, .. }) => {
+                    (true, logs, traces, labels, Some(format!("Setup failed: {reason}")), coverage)
                 }
                 Err(e) => (
                     true,
@@ -290,14 +365,16 @@ impl<'a, DB: DatabaseRef + Send + Sync> ContractRunner<'a, DB> {
                     None,
                     BTreeMap::new(),
                     Some(format!("Setup failed: {}", &e.to_string())),
+                    None,
                 ),
             };
             traces.extend(setup_traces.map(|traces| (TraceKind::Setup, traces)).into_iter());
             logs.extend_from_slice(&setup_logs);
+            coverage = HitMaps::merge_opt(coverage, setup_coverage);
 
-            TestSetup { address, logs, traces, labeled_addresses, setup_failed, reason }
+            TestSetup { address, logs, traces, labeled_addresses, setup_failed, reason, coverage }
         } else {
-            TestSetup { address, logs, traces, ..Default::default() }
+            TestSetup { address, logs, traces, coverage, ..Default::default() }
         })
     }
 
@@ -306,6 +383,7 @@ impl<'a, DB: DatabaseRef + Send + Sync> ContractRunner<'a, DB> {
         &mut self,
         filter: &impl TestFilter,
         fuzzer: Option<TestRunner>,
+        invariant_config: InvariantConfig,
         include_fuzz_tests: bool,
     ) -> Result<SuiteResult> {
         tracing::info!("starting tests");
@@ -341,10 +419,12 @@ impl<'a, DB: DatabaseRef + Send + Sync> ContractRunner<'a, DB> {
                         kind: TestKind::Standard(0),
                         traces: vec![],
                         labeled_addresses: BTreeMap::new(),
+                        coverage: None,
                     },
                 )]
                 .into(),
                 warnings,
+                None,
             ))
         }
 
@@ -363,10 +443,12 @@ impl<'a, DB: DatabaseRef + Send + Sync> ContractRunner<'a, DB> {
                         kind: TestKind::Standard(0),
                         traces: setup.traces,
                         labeled_addresses: setup.labeled_addresses,
+                        coverage: None,
                     },
                 )]
                 .into(),
                 warnings,
+                setup.coverage,
             ))
         }
 
@@ -383,7 +465,10 @@ impl<'a, DB: DatabaseRef + Send + Sync> ContractRunner<'a, DB> {
             .map(|func| (func, func.name.starts_with("testFail")))
             .collect();
 
-        let test_results = tests
+        // The setup is shared by all tests, so its coverage is only counted once
+        let mut coverage = setup.coverage.clone();
+
+        let mut test_results = tests
             .par_iter()
             .filter_map(|(func, should_fail)| {
                 let result = if func.inputs.is_empty() {
@@ -398,6 +483,26 @@ impl<'a, DB: DatabaseRef + Send + Sync> ContractRunner<'a, DB> {
             })
             .collect::<Result<BTreeMap<_, _>>>()?;
 
+        // Collect invariant functions, which are all checked during the same campaign
+        let invariants: Vec<_> = self
+            .contract
+            .functions()
+            .filter(|func| {
+                func.name.starts_with("invariant") &&
+                    func.inputs.is_empty() &&
+                    filter.matches_test(func.signature())
+            })
+            .collect();
+
+        if include_fuzz_tests && !invariants.is_empty() {
+            if let Some(fuzzer) = fuzzer {
+                let (results, campaign_coverage) =
+                    self.run_invariant_test(fuzzer, setup, invariant_config, &invariants)?;
+                test_results.extend(results);
+                coverage = HitMaps::merge_opt(coverage, campaign_coverage);
+            }
+        }
+
         let duration = start.elapsed();
         if !test_results.is_empty() {
             let successful = test_results.iter().filter(|(_, tst)| tst.success).count();
@@ -408,7 +513,7 @@ impl<'a, DB: DatabaseRef + Send + Sync> ContractRunner<'a, DB> {
                 test_results.len()
             );
         }
-        Ok(SuiteResult::new(duration, test_results, warnings))
+        Ok(SuiteResult::new(duration, test_results, warnings, coverage))
     }
 
     #[tracing::instrument(name = "test", skip_all, fields(name = %func.signature(), %should_fail))]
@@ -422,6 +527,7 @@ impl<'a, DB: DatabaseRef + Send + Sync> ContractRunner<'a, DB> {
 
         // Run unit test
         let start = Instant::now();
+        let coverage;
         let (reverted, reason, gas, stipend, execution_traces, state_changeset) = match self
             .executor
             .call::<(), _, _>(self.sender, address, func.clone(), (), 0.into(), self.errors)
@@ -434,10 +540,12 @@ impl<'a, DB: DatabaseRef + Send + Sync> ContractRunner<'a, DB> {
                 traces: execution_trace,
                 labels: new_labels,
                 state_changeset,
+                coverage: execution_coverage,
                 ..
             }) => {
                 labeled_addresses.extend(new_labels);
                 logs.extend(execution_logs);
+                coverage = execution_coverage;
                 (reverted, None, gas, stipend, execution_trace, state_changeset)
             }
             Err(EvmError::Execution {
@@ -449,10 +557,12 @@ impl<'a, DB: DatabaseRef + Send + Sync> ContractRunner<'a, DB> {
                 traces: execution_trace,
                 labels: new_labels,
                 state_changeset,
+                coverage: execution_coverage,
                 ..
             }) => {
                 labeled_addresses.extend(new_labels);
                 logs.extend(execution_logs);
+                coverage = execution_coverage;
                 (reverted, Some(reason), gas, stipend, execution_trace, state_changeset)
             }
             Err(err) => {
@@ -484,6 +594,7 @@ impl<'a, DB: DatabaseRef + Send + Sync> ContractRunner<'a, DB> {
             kind: TestKind::Standard(gas.overflowing_sub(stipend).0),
             traces,
             labeled_addresses,
+            coverage,
         })
     }
 
@@ -496,15 +607,27 @@ impl<'a, DB: DatabaseRef + Send + Sync> ContractRunner<'a, DB> {
         setup: TestSetup,
     ) -> Result<TestResult> {
         let TestSetup { address, mut logs, mut traces, mut labeled_addresses, .. } = setup;
+        let runner = self.fuzz_config.fuzzer(&func.name, runner);
+
+        // Replay the inputs that made the test fail in previous runs before generating new ones
+        let mut corpus =
+            self.fuzz_corpus_dir.map(|dir| FuzzCorpus::load(dir, self.identifier, func));
+        let replay = corpus.as_ref().map(|corpus| corpus.inputs(func)).unwrap_or_default();
 
         // Run fuzz test
         let start = Instant::now();
-        let mut result = FuzzedExecutor::new(&self.executor, runner, self.sender).fuzz(
-            func,
-            address,
-            should_fail,
-            self.errors,
-        );
+        let mut result = FuzzedExecutor::new(&self.executor, runner, self.sender)
+            .with_corpus(replay)
+            .fuzz(func, address, should_fail, self.errors);
+
+        // Persist the counterexample, so it is replayed in later runs
+        if let (Some(corpus), Some(CounterExample::Single(counterexample))) =
+            (corpus.as_mut(), &result.counterexample)
+        {
+            if let Err(err) = corpus.add(counterexample.calldata.clone()) {
+                tracing::warn!(?err, "failed to persist fuzz counterexample");
+            }
+        }
 
         // Record logs, labels and traces
         logs.append(&mut result.logs);
@@ -525,6 +648,225 @@ impl<'a, DB: DatabaseRef + Send + Sync> ContractRunner<'a, DB> {
             kind: TestKind::Fuzz(result.cases),
             traces,
             labeled_addresses,
+            coverage: result.coverage,
         })
     }
+
+    /// Runs a single invariant campaign for all the given invariants.
+    ///
+    /// Returns the result of each invariant and the coverage of the campaign, which is shared by
+    /// all of them.
+    #[tracing::instrument(name = "invariant-test", skip_all)]
+    pub fn run_invariant_test(
+        &self,
+        runner: TestRunner,
+        setup: TestSetup,
+        invariant_config: InvariantConfig,
+        invariants: &[&Function],
+    ) -> Result<(BTreeMap<String, TestResult>, Option<HitMaps>)> {
+        let TestSetup { address, logs, traces, labeled_addresses, .. } = setup;
+
+        // Run invariant campaign
+        let start = Instant::now();
+        let targets = self.target_contracts(address)?;
+        let result = InvariantExecutor::new(&self.executor, runner, self.sender, invariant_config)
+            .invariant_fuzz(invariants, address, &targets, self.errors);
+
+        // Record test execution time
+        tracing::debug!(duration = ?start.elapsed());
+
+        let results = invariants
+            .iter()
+            .map(|func| {
+                let (success, reason, counterexample, kind) = match &result {
+                    Ok(result) => {
+                        let error = result.invariants.get(&func.name);
+                        (
+                            error.is_none(),
+                            error.and_then(|error| error.reason.clone()),
+                            error.and_then(|error| error.counterexample()),
+                            TestKind::Invariant {
+                                runs: result.runs,
+                                calls: result.calls,
+                                reverts: result.reverts,
+                            },
+                        )
+                    }
+                    Err(err) => (
+                        false,
+                        Some(err.to_string()),
+                        None,
+                        TestKind::Invariant { runs: 0, calls: 0, reverts: 0 },
+                    ),
+                };
+
+                (
+                    func.signature(),
+                    TestResult {
+                        success,
+                        reason,
+                        counterexample,
+                        logs: logs.clone(),
+                        kind,
+                        traces: traces.clone(),
+                        labeled_addresses: labeled_addresses.clone(),
+                        coverage: None,
+                    },
+                )
+            })
+            .collect();
+
+        Ok((results, result.ok().and_then(|result| result.coverage)))
+    }
+
+    /// Returns the contracts deployed during `setUp` that an invariant campaign should call.
+    ///
+    /// If the test contract defines `targetContracts()`, only the returned addresses are
+    /// targeted. Contracts are identified by comparing their runtime code with the known
+    /// contracts.
+    fn target_contracts(&self, test_address: Address) -> Result<TargetedContracts> {
+        let selected = self
+            .contract
+            .functions()
+            .find(|func| func.name == "targetContracts")
+            .map(|func| {
+                self.executor
+                    .call::<Vec<Address>, _, _>(
+                        self.sender,
+                        test_address,
+                        func.clone(),
+                        (),
+                        0.into(),
+                        self.errors,
+                    )
+                    .map(|call| call.result)
+            })
+            .transpose()?;
+
+        let mut targets = TargetedContracts::new();
+        for (address, info) in self.executor.db.cache() {
+            if *address == test_address ||
+                *address == CHEATCODE_ADDRESS ||
+                *address == HARDHAT_CONSOLE_ADDRESS ||
+                selected.as_ref().map(|selected| !selected.contains(address)).unwrap_or(false)
+            {
+                continue
+            }
+
+            let code = info
+                .code
+                .clone()
+                .unwrap_or_else(|| self.executor.db.code_by_hash(info.code_hash));
+            if code.is_empty() {
+                continue
+            }
+
+            if let Some((id, abi)) = find_contract(self.known_contracts, &code) {
+                targets.insert(*address, (id.name.clone(), abi.clone()));
+            }
+        }
+        Ok(targets)
+    }
+}
+
+/// Finds the known contract whose runtime code matches `code`.
+///
+/// The metadata solc appends to the code is ignored, and since the runtime code of an artifact
+/// contains zeroed placeholders for immutable variables, the code may only differ from it where
+/// the artifact has a zero byte. The contract with the fewest differences is returned, unless
+/// several contracts match equally well.
+fn find_contract<'b>(
+    known_contracts: &'b BTreeMap<ArtifactId, (Abi, Vec<u8>)>,
+    code: &[u8],
+) -> Option<(&'b ArtifactId, &'b Abi)> {
+    let code = strip_metadata(code);
+    let mut matches = known_contracts
+        .iter()
+        .filter_map(|(id, (abi, known_code))| {
+            let known_code = strip_metadata(known_code);
+            if known_code.len() != code.len() {
+                return None
+            }
+
+            let mut diff = 0;
+            for (known, byte) in known_code.iter().zip(code) {
+                if known != byte {
+                    if *known != 0 {
+                        return None
+                    }
+                    diff += 1;
+                }
+            }
+            Some((id, abi, diff))
+        })
+        .collect::<Vec<_>>();
+    matches.sort_by_key(|(_, _, diff)| *diff);
+
+    match matches.as_slice() {
+        [(id, abi, _)] => Some((id, abi)),
+        [(id, abi, best), (_, _, next), ..] if best < next => Some((id, abi)),
+        _ => None,
+    }
+}
+
+/// Returns the code without the CBOR encoded metadata solc appends to it, whose length is stored
+/// in the last two bytes
+fn strip_metadata(code: &[u8]) -> &[u8] {
+    if let [.., a, b] = code {
+        let len = u16::from_be_bytes([*a, *b]) as usize + 2;
+        if len <= code.len() {
+            return &code[..code.len() - len]
+        }
+    }
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn known_contracts(codes: &[(&str, Vec<u8>)]) -> BTreeMap<ArtifactId, (Abi, Vec<u8>)> {
+        codes
+            .iter()
+            .map(|(name, code)| {
+                let id = ArtifactId {
+                    path: PathBuf::from(format!("out/{name}.sol/{name}.json")),
+                    name: name.to_string(),
+                    source: PathBuf::from(format!("src/{name}.sol")),
+                    version: semver::Version::new(0, 8, 10),
+                };
+                (id, (Abi::default(), code.clone()))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn finds_contract_with_immutables_and_metadata() {
+        // PUSH20 <immutable>, followed by 3 bytes of metadata and its length
+        let mut counter = vec![0x73];
+        counter.extend([0; 20]);
+        counter.extend([0xa1, 0x01, 0x02, 0x00, 0x03]);
+        let mut other = vec![0x72];
+        other.extend([0; 20]);
+        other.extend([0xa1, 0x01, 0x02, 0x00, 0x03]);
+        let known = known_contracts(&[("Counter", counter), ("Other", other)]);
+
+        let mut deployed = vec![0x73];
+        deployed.extend([0x11; 20]);
+        deployed.extend([0xa1, 0x09, 0x09, 0x00, 0x03]);
+        let (id, _) = find_contract(&known, &deployed).unwrap();
+        assert_eq!(id.name, "Counter");
+
+        // a non-zero byte of the known code differs
+        deployed[0] = 0x74;
+        assert!(find_contract(&known, &deployed).is_none());
+    }
+
+    #[test]
+    fn rejects_ambiguous_contracts() {
+        let code = vec![0x60, 0x00, 0x60, 0x00, 0x00, 0x00];
+        let known = known_contracts(&[("A", code.clone()), ("B", code.clone())]);
+        assert!(find_contract(&known, &code).is_none());
+    }
 }
@@ -0,0 +1,97 @@
+// SPDX-License-Identifier: Unlicense
+pragma solidity >=0.8.0;
+
+import "ds-test/test.sol";
+import "./Cheats.sol";
+
+contract Recorder {
+    address public lastSender;
+    address public lastOrigin;
+
+    function record() public {
+        lastSender = msg.sender;
+        lastOrigin = tx.origin;
+    }
+}
+
+contract ConstructorRecorder {
+    address public deployer;
+    address public origin;
+
+    constructor() {
+        deployer = msg.sender;
+        origin = tx.origin;
+    }
+}
+
+contract BroadcastTest is DSTest {
+    Cheats constant cheats = Cheats(HEVM_ADDRESS);
+
+    address constant ACCOUNT_A = 0x7109709ECfa91a80626fF3989D68f67F5b1DD12D;
+    address constant ACCOUNT_B = 0x1000000000000000000000000000000000000001;
+
+    function testBroadcastSetsSenderAndOrigin() public {
+        Recorder recorder = new Recorder();
+        address origin = tx.origin;
+
+        cheats.broadcast(ACCOUNT_B);
+        recorder.record();
+        assertEq(recorder.lastSender(), ACCOUNT_B, "msg.sender was not set during broadcast");
+        assertEq(recorder.lastOrigin(), ACCOUNT_B, "tx.origin was not set during broadcast");
+
+        // The broadcast only applies to the next call
+        recorder.record();
+        assertEq(recorder.lastSender(), address(this), "msg.sender was not reset after broadcast");
+        assertEq(recorder.lastOrigin(), origin, "tx.origin was not reset after broadcast");
+    }
+
+    function testBroadcastSkipsStaticCalls() public {
+        Recorder recorder = new Recorder();
+
+        cheats.broadcast(ACCOUNT_B);
+        recorder.lastSender();
+        recorder.record();
+        assertEq(recorder.lastSender(), ACCOUNT_B, "broadcast was used up by a static call");
+    }
+
+    function testStartBroadcast() public {
+        Recorder recorder = new Recorder();
+
+        cheats.startBroadcast(ACCOUNT_B);
+        recorder.record();
+        assertEq(recorder.lastSender(), ACCOUNT_B, "msg.sender was not set during broadcast");
+        recorder.record();
+        assertEq(recorder.lastSender(), ACCOUNT_B, "msg.sender was reset during broadcast");
+        cheats.stopBroadcast();
+
+        recorder.record();
+        assertEq(recorder.lastSender(), address(this), "msg.sender was not reset after broadcast");
+    }
+
+    function testBroadcastCreate() public {
+        uint64 nonce = cheats.getNonce(ACCOUNT_B);
+
+        cheats.broadcast(ACCOUNT_B);
+        ConstructorRecorder recorder = new ConstructorRecorder();
+        assertEq(recorder.deployer(), ACCOUNT_B, "msg.sender was not set during broadcast");
+        assertEq(recorder.origin(), ACCOUNT_B, "tx.origin was not set during broadcast");
+        assertEq(cheats.getNonce(ACCOUNT_B), nonce + 1, "nonce was not incremented");
+    }
+
+    function testBroadcastIncrementsNonce() public {
+        Recorder recorder = new Recorder();
+        uint64 nonce = cheats.getNonce(ACCOUNT_B);
+
+        cheats.startBroadcast(ACCOUNT_B);
+        recorder.record();
+        recorder.record();
+        cheats.stopBroadcast();
+
+        assertEq(cheats.getNonce(ACCOUNT_B), nonce + 2, "nonce was not incremented per call");
+    }
+
+    function testFailPrankDuringBroadcast() public {
+        cheats.startBroadcast(ACCOUNT_B);
+        cheats.prank(ACCOUNT_A);
+    }
+}
@@ -2,6 +2,13 @@
 pragma solidity >=0.8.0;
 
 interface Cheats {
+    // A log emitted while recording logs with `recordLogs`
+    struct Log {
+        bytes32[] topics;
+        bytes data;
+        address emitter;
+    }
+
     // Set block.timestamp (newTimestamp)
     function warp(uint256) external;
     // Set block.height (newHeight)
@@ -42,6 +49,10 @@ interface Cheats {
     function record() external;
     // Gets all accessed reads and write slot from a recording session, for a given address
     function accesses(address) external returns (bytes32[] memory reads, bytes32[] memory writes);
+    // Record all emitted logs
+    function recordLogs() external;
+    // Gets all logs emitted since `recordLogs` or the last call to `getRecordedLogs`
+    function getRecordedLogs() external returns (Log[] memory);
     // Prepare an expected log with (bool checkTopic1, bool checkTopic2, bool checkTopic3, bool checkData).
     // Call this function, then emit an event, then call a function. Internally after the call, we check if
     // logs were emitted in the expected order with the expected topics and data (as specified by the booleans).
@@ -75,4 +86,46 @@ interface Cheats {
     function getNonce(address) external returns(uint64);
     // Set block.chainid (newChainId)
     function chainId(uint256) external;
+    // Using the address that calls the test contract, has the next call (at this call depth only) create a transaction that can later be signed and sent onchain
+    function broadcast() external;
+    // Has the next call (at this call depth only) create a transaction with the address provided as the sender that can later be signed and sent onchain
+    function broadcast(address) external;
+    // Using the address that calls the test contract, has all subsequent calls (at this call depth only) create transactions that can later be signed and sent onchain
+    function startBroadcast() external;
+    // Has all subsequent calls (at this call depth only) create transactions with the address provided that can later be signed and sent onchain
+    function startBroadcast(address) external;
+    // Stops collecting onchain transactions
+    function stopBroadcast() external;
+    // Creates a fork of the chain behind the given RPC URL at the latest block and returns the identifier of the fork
+    function createFork(string calldata) external returns(uint256);
+    // Creates a fork of the chain behind the given RPC URL at the given block and returns the identifier of the fork
+    function createFork(string calldata,uint256) external returns(uint256);
+    // Takes a fork identifier created by `createFork` and sets the corresponding forked state as active
+    function selectFork(uint256) external;
+    // Updates the active fork to the given block number
+    function rollFork(uint256) external;
+    // Updates the given fork to the given block number
+    function rollFork(uint256 forkId, uint256 blockNumber) external;
+    // Reads the entire content of a file as a string, the path must be in `fs_allowed_paths`
+    function readFile(string calldata) external returns (string memory);
+    // Reads the next line of a file as a string, returns an empty string at the end of the file
+    function readLine(string calldata) external returns (string memory);
+    // Writes a string to a file, creating it if it doesn't exist and replacing its content otherwise
+    function writeFile(string calldata, string calldata) external;
+    // Reads an environment variable as a bool, (name) => (value)
+    function envBool(string calldata) external returns (bool);
+    // Reads an environment variable as a uint256, (name) => (value)
+    function envUint(string calldata) external returns (uint256);
+    // Reads an environment variable as an address, (name) => (value)
+    function envAddress(string calldata) external returns (address);
+    // Reads an environment variable as a bytes32, (name) => (value)
+    function envBytes32(string calldata) external returns (bytes32);
+    // Reads an environment variable as a string, (name) => (value)
+    function envString(string calldata) external returns (string memory);
+    // Selects the value at the key of a JSON string and returns it ABI-encoded, (json, key) => (data)
+    function parseJson(string calldata, string calldata) external returns (bytes memory);
+    // Snapshots the current state of the EVM and returns the id of the snapshot
+    function snapshot() external returns (uint256);
+    // Restores the state of the EVM to the given snapshot, returns false if it does not exist
+    function revertTo(uint256) external returns (bool);
 }
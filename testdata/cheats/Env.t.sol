@@ -0,0 +1,39 @@
+// SPDX-License-Identifier: Unlicense
+pragma solidity >=0.8.0;
+
+import "ds-test/test.sol";
+import "./Cheats.sol";
+
+// The environment variables are set by the test runner
+contract EnvTest is DSTest {
+    Cheats constant cheats = Cheats(HEVM_ADDRESS);
+
+    function testEnvBool() public {
+        assertTrue(cheats.envBool("_FOUNDRY_CHEATS_ENV_BOOL"));
+    }
+
+    function testEnvUint() public {
+        assertEq(cheats.envUint("_FOUNDRY_CHEATS_ENV_UINT"), 1000000);
+        assertEq(cheats.envUint("_FOUNDRY_CHEATS_ENV_UINT_HEX"), 16);
+    }
+
+    function testEnvAddress() public {
+        assertEq(cheats.envAddress("_FOUNDRY_CHEATS_ENV_ADDRESS"), HEVM_ADDRESS);
+    }
+
+    function testEnvBytes32() public {
+        assertEq(cheats.envBytes32("_FOUNDRY_CHEATS_ENV_BYTES32"), bytes32(uint256(1)));
+    }
+
+    function testEnvString() public {
+        assertEq(cheats.envString("_FOUNDRY_CHEATS_ENV_STRING"), "hello, world!");
+    }
+
+    function testFailEnvUnset() public {
+        cheats.envUint("_FOUNDRY_CHEATS_ENV_UNSET");
+    }
+
+    function testFailEnvInvalid() public {
+        cheats.envUint("_FOUNDRY_CHEATS_ENV_STRING");
+    }
+}
@@ -6,6 +6,8 @@ import "./Cheats.sol";
 
 contract Reverter {
     error CustomError();
+    error InsufficientBalance(uint256 available, uint256 required);
+    error Unauthorized(address caller);
 
     function revertWithMessage(string memory message) public pure {
         require(false, message);
@@ -21,6 +23,23 @@ contract Reverter {
         revert CustomError();
     }
 
+    function revertWithInsufficientBalance(uint256 available, uint256 required) public pure {
+        revert InsufficientBalance(available, required);
+    }
+
+    function revertWithUnauthorized() public view {
+        revert Unauthorized(msg.sender);
+    }
+
+    function divide(uint256 a, uint256 b) public pure returns (uint256) {
+        return a / b;
+    }
+
+    function outOfBounds(uint256 index) public pure returns (uint256) {
+        uint256[] memory values = new uint256[](1);
+        return values[index];
+    }
+
     function nestedRevert(Reverter inner, string memory message) public pure {
         inner.revertWithMessage(message);
     }
@@ -73,6 +92,38 @@ contract ExpectRevertTest is DSTest {
         reverter.revertWithCustomError();
     }
 
+    function testExpectRevertCustomErrorWithArgs() public {
+        Reverter reverter = new Reverter();
+        cheats.expectRevert(abi.encodeWithSelector(Reverter.InsufficientBalance.selector, 100, 50));
+        reverter.revertWithInsufficientBalance(100, 50);
+    }
+
+    function testFailExpectRevertCustomErrorArgsDoNotMatch() public {
+        Reverter reverter = new Reverter();
+        cheats.expectRevert(abi.encodeWithSelector(Reverter.InsufficientBalance.selector, 100, 50));
+        reverter.revertWithInsufficientBalance(100, 40);
+    }
+
+    function testFailExpectRevertCustomErrorDoesNotMatch() public {
+        Reverter reverter = new Reverter();
+        cheats.expectRevert(abi.encodeWithSelector(Reverter.InsufficientBalance.selector, 100, 50));
+        reverter.revertWithUnauthorized();
+    }
+
+    function testExpectRevertPanicCodes() public {
+        Reverter reverter = new Reverter();
+        cheats.expectRevert(abi.encodeWithSignature("Panic(uint256)", 0x12));
+        reverter.divide(1, 0);
+        cheats.expectRevert(abi.encodeWithSignature("Panic(uint256)", 0x32));
+        reverter.outOfBounds(1);
+    }
+
+    function testFailExpectRevertPanicCodeDoesNotMatch() public {
+        Reverter reverter = new Reverter();
+        cheats.expectRevert(abi.encodeWithSignature("Panic(uint256)", 0x11));
+        reverter.divide(1, 0);
+    }
+
     function testExpectRevertNested() public {
         Reverter reverter = new Reverter();
         Reverter inner = new Reverter();
@@ -0,0 +1,41 @@
+// SPDX-License-Identifier: Unlicense
+pragma solidity >=0.8.0;
+
+import "ds-test/test.sol";
+import "./Cheats.sol";
+
+contract FsTest is DSTest {
+    Cheats constant cheats = Cheats(HEVM_ADDRESS);
+
+    function testReadFile() public {
+        string memory path = "../testdata/fixtures/File/read.txt";
+        assertEq(cheats.readFile(path), "hello readable world\nthis is the second line!");
+    }
+
+    function testReadLine() public {
+        string memory path = "../testdata/fixtures/File/read.txt";
+        assertEq(cheats.readLine(path), "hello readable world");
+        assertEq(cheats.readLine(path), "this is the second line!");
+        assertEq(cheats.readLine(path), "");
+    }
+
+    function testWriteFile() public {
+        string memory path = "../testdata/fixtures/File/write_file.txt";
+        string memory data = "hello writable world";
+        cheats.writeFile(path, data);
+        assertEq(cheats.readFile(path), data);
+        assertEq(cheats.readLine(path), data);
+    }
+
+    function testFailReadFileOutsideAllowedPaths() public {
+        cheats.readFile("../testdata/cheats/Cheats.sol");
+    }
+
+    function testFailReadFileWithParentDirectory() public {
+        cheats.readFile("../testdata/fixtures/../cheats/Cheats.sol");
+    }
+
+    function testFailWriteFileOutsideAllowedPaths() public {
+        cheats.writeFile("../testdata/cheats/write_file.txt", "not allowed");
+    }
+}
@@ -0,0 +1,77 @@
+// SPDX-License-Identifier: Unlicense
+pragma solidity >=0.8.0;
+
+import "ds-test/test.sol";
+import "./Cheats.sol";
+
+contract JsonTest is DSTest {
+    Cheats constant cheats = Cheats(HEVM_ADDRESS);
+
+    struct Nested {
+        uint256 a;
+        string b;
+    }
+
+    string json;
+
+    function setUp() public {
+        json = cheats.readFile("../testdata/fixtures/Json/test.json");
+    }
+
+    function testParseUint() public {
+        uint256 a = abi.decode(cheats.parseJson(json, ".a"), (uint256));
+        assertEq(a, 123);
+    }
+
+    function testParseString() public {
+        string memory b = abi.decode(cheats.parseJson(json, ".b"), (string));
+        assertEq(b, "test");
+    }
+
+    function testParseObject() public {
+        Nested memory c = abi.decode(cheats.parseJson(json, ".c"), (Nested));
+        assertEq(c.a, 123);
+        assertEq(c.b, "test");
+    }
+
+    function testParseNestedKey() public {
+        string memory b = abi.decode(cheats.parseJson(json, ".c.b"), (string));
+        assertEq(b, "test");
+    }
+
+    function testParseArray() public {
+        address[] memory d = abi.decode(cheats.parseJson(json, ".d"), (address[]));
+        assertEq(d.length, 2);
+        assertEq(d[0], address(1));
+        assertEq(d[1], address(2));
+    }
+
+    function testParseArrayElement() public {
+        address d = abi.decode(cheats.parseJson(json, ".d[1]"), (address));
+        assertEq(d, address(2));
+    }
+
+    function testParseAddress() public {
+        address e = abi.decode(cheats.parseJson(json, ".e"), (address));
+        assertEq(e, HEVM_ADDRESS);
+    }
+
+    function testParseDecimalString() public {
+        uint256 f = abi.decode(cheats.parseJson(json, ".f"), (uint256));
+        assertEq(f, 1e24);
+    }
+
+    function testParseInt() public {
+        int256 g = abi.decode(cheats.parseJson(json, ".g"), (int256));
+        assertEq(g, -5);
+    }
+
+    function testParseBool() public {
+        bool h = abi.decode(cheats.parseJson(json, ".h"), (bool));
+        assertTrue(h);
+    }
+
+    function testFailParseMissingKey() public {
+        cheats.parseJson(json, ".missing");
+    }
+}
@@ -0,0 +1,86 @@
+// SPDX-License-Identifier: Unlicense
+pragma solidity >=0.8.0;
+
+import "ds-test/test.sol";
+import "./Cheats.sol";
+
+contract Emitter {
+    event LogTopic1(uint256 indexed topic1, bytes data);
+    event LogTopic12(uint256 indexed topic1, uint256 indexed topic2, bytes data);
+
+    function emitEvent(uint256 topic1, bytes memory data) public {
+        emit LogTopic1(topic1, data);
+    }
+
+    function emitEvent(uint256 topic1, uint256 topic2, bytes memory data) public {
+        emit LogTopic12(topic1, topic2, data);
+    }
+}
+
+contract RecordLogsTest is DSTest {
+    Cheats constant cheats = Cheats(HEVM_ADDRESS);
+    Emitter emitter;
+
+    event LogTopic1(uint256 indexed topic1, bytes data);
+    event LogTopic12(uint256 indexed topic1, uint256 indexed topic2, bytes data);
+
+    function setUp() public {
+        emitter = new Emitter();
+    }
+
+    function testRecordOffGetsNothing() public {
+        emitter.emitEvent(1, "data");
+        Cheats.Log[] memory entries = cheats.getRecordedLogs();
+
+        assertEq(entries.length, 0);
+    }
+
+    function testRecordOnNoLogs() public {
+        cheats.recordLogs();
+        Cheats.Log[] memory entries = cheats.getRecordedLogs();
+
+        assertEq(entries.length, 0);
+    }
+
+    function testRecordLogs() public {
+        cheats.recordLogs();
+        emitter.emitEvent(1, "one");
+        emitter.emitEvent(2, 3, "two");
+
+        Cheats.Log[] memory entries = cheats.getRecordedLogs();
+        assertEq(entries.length, 2);
+
+        assertEq(entries[0].topics.length, 2);
+        assertEq(entries[0].topics[0], keccak256("LogTopic1(uint256,bytes)"));
+        assertEq(entries[0].topics[1], bytes32(uint256(1)));
+        assertEq(abi.decode(entries[0].data, (string)), "one");
+        assertEq(entries[0].emitter, address(emitter));
+
+        assertEq(entries[1].topics.length, 3);
+        assertEq(entries[1].topics[0], keccak256("LogTopic12(uint256,uint256,bytes)"));
+        assertEq(entries[1].topics[1], bytes32(uint256(2)));
+        assertEq(entries[1].topics[2], bytes32(uint256(3)));
+        assertEq(abi.decode(entries[1].data, (string)), "two");
+        assertEq(entries[1].emitter, address(emitter));
+    }
+
+    function testRecordOwnLogs() public {
+        cheats.recordLogs();
+        emit LogTopic1(42, "own");
+
+        Cheats.Log[] memory entries = cheats.getRecordedLogs();
+        assertEq(entries.length, 1);
+        assertEq(entries[0].emitter, address(this));
+    }
+
+    function testGetRecordedLogsClearsLogs() public {
+        cheats.recordLogs();
+        emitter.emitEvent(1, "one");
+        assertEq(cheats.getRecordedLogs().length, 1);
+
+        emitter.emitEvent(2, "two");
+        Cheats.Log[] memory entries = cheats.getRecordedLogs();
+        assertEq(entries.length, 1);
+        assertEq(entries[0].topics[1], bytes32(uint256(2)));
+    }
+}
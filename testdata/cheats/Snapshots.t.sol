@@ -0,0 +1,102 @@
+// SPDX-License-Identifier: Unlicense
+pragma solidity >=0.8.0;
+
+import "ds-test/test.sol";
+import "./Cheats.sol";
+
+struct Storage {
+    uint256 slot0;
+    uint256 slot1;
+}
+
+contract SnapshotTest is DSTest {
+    Cheats constant cheats = Cheats(HEVM_ADDRESS);
+
+    address constant BOB = address(0xB0B);
+
+    Storage store;
+    uint256 setUpSnapshot;
+
+    function setUp() public {
+        store.slot0 = 10;
+        store.slot1 = 20;
+        uint256 snapshot = cheats.snapshot();
+
+        // changes made after the snapshot are committed at the end of `setUp`
+        store.slot0 = 30;
+        cheats.deal(BOB, 1 ether);
+        setUpSnapshot = snapshot;
+    }
+
+    function testSnapshot() public {
+        uint256 snapshot = cheats.snapshot();
+        store.slot0 = 300;
+        store.slot1 = 400;
+        assertEq(store.slot0, 300);
+        assertEq(store.slot1, 400);
+
+        assertTrue(cheats.revertTo(snapshot));
+        assertEq(store.slot0, 30, "snapshot revert for slot 0 unsuccessful");
+        assertEq(store.slot1, 20, "snapshot revert for slot 1 unsuccessful");
+    }
+
+    function testRevertToRestoresDeletedStorage() public {
+        uint256 snapshot = cheats.snapshot();
+        delete store;
+        assertEq(store.slot0, 0);
+
+        assertTrue(cheats.revertTo(snapshot));
+        assertEq(store.slot0, 30);
+        assertEq(store.slot1, 20);
+    }
+
+    function testRevertToSetUpSnapshot() public {
+        assertEq(store.slot0, 30);
+        assertEq(BOB.balance, 1 ether);
+
+        assertTrue(cheats.revertTo(setUpSnapshot));
+        assertEq(store.slot0, 10);
+        assertEq(store.slot1, 20);
+        assertEq(BOB.balance, 0);
+    }
+
+    function testRevertToMultipleTimes() public {
+        uint256 snapshot = cheats.snapshot();
+
+        store.slot0 = 300;
+        assertTrue(cheats.revertTo(snapshot));
+        assertEq(store.slot0, 30);
+
+        store.slot0 = 400;
+        assertTrue(cheats.revertTo(snapshot));
+        assertEq(store.slot0, 30);
+    }
+
+    function testSnapshotBalance() public {
+        uint256 snapshot = cheats.snapshot();
+        cheats.deal(BOB, 5 ether);
+        assertEq(BOB.balance, 5 ether);
+
+        assertTrue(cheats.revertTo(snapshot));
+        assertEq(BOB.balance, 1 ether);
+    }
+
+    function testSnapshotBlock() public {
+        uint256 timestamp = block.timestamp;
+        uint256 number = block.number;
+        uint256 snapshot = cheats.snapshot();
+
+        cheats.warp(1234);
+        cheats.roll(100);
+        assertEq(block.timestamp, 1234);
+        assertEq(block.number, 100);
+
+        assertTrue(cheats.revertTo(snapshot));
+        assertEq(block.timestamp, timestamp, "snapshot revert for block.timestamp unsuccessful");
+        assertEq(block.number, number, "snapshot revert for block.number unsuccessful");
+    }
+
+    function testRevertToUnknownSnapshot() public {
+        assertTrue(!cheats.revertTo(100));
+    }
+}
@@ -0,0 +1,147 @@
+// SPDX-License-Identifier: Unlicense
+pragma solidity >=0.8.0;
+
+import "ds-test/test.sol";
+import "../cheats/Cheats.sol";
+
+interface IERC20 {
+    function decimals() external view returns (uint8);
+    function totalSupply() external view returns (uint256);
+}
+
+contract Dummy {
+    uint256 public value = 1;
+
+    function set(uint256 _value) public {
+        value = _value;
+    }
+}
+
+contract ForkSelector {
+    Cheats immutable cheats;
+
+    constructor(Cheats _cheats) {
+        cheats = _cheats;
+    }
+
+    function selectAndRevert(uint256 forkId) public {
+        cheats.selectFork(forkId);
+        revert();
+    }
+}
+
+contract MultiForkTest is DSTest {
+    Cheats constant cheats = Cheats(HEVM_ADDRESS);
+
+    string constant RPC_URL = "https://mainnet.infura.io/v3/6770454bc6ea42c58aac12978531b93f";
+    address constant DAI_TOKEN_ADDR = 0x6B175474E89094C44Da98b954EedeAC495271d0F;
+
+    uint256 mainnetFork;
+    uint256 olderFork;
+    uint256 counter;
+
+    function setUp() public {
+        mainnetFork = cheats.createFork(RPC_URL, 15_000_000);
+        olderFork = cheats.createFork(RPC_URL, 14_000_000);
+    }
+
+    function testCreateForkReturnsIds() public {
+        assertEq(mainnetFork, 1);
+        assertEq(olderFork, 2);
+    }
+
+    function testSelectForkSetsBlock() public {
+        cheats.selectFork(mainnetFork);
+        assertEq(block.number, 15_000_000);
+        assertEq(block.chainid, 1);
+
+        cheats.selectFork(olderFork);
+        assertEq(block.number, 14_000_000);
+    }
+
+    function testReadForkState() public {
+        cheats.selectFork(mainnetFork);
+        IERC20 dai = IERC20(DAI_TOKEN_ADDR);
+        assertEq(uint256(dai.decimals()), 18);
+        uint256 supply = dai.totalSupply();
+
+        cheats.selectFork(olderFork);
+        assertTrue(dai.totalSupply() != supply);
+    }
+
+    function testTestContractStatePersists() public {
+        cheats.selectFork(mainnetFork);
+        counter = 42;
+
+        cheats.selectFork(olderFork);
+        assertEq(counter, 42);
+        counter += 1;
+
+        cheats.selectFork(mainnetFork);
+        assertEq(counter, 43);
+    }
+
+    function testLocalChangesStayOnFork() public {
+        cheats.selectFork(mainnetFork);
+        Dummy dummy = new Dummy();
+        dummy.set(5);
+
+        cheats.selectFork(olderFork);
+        assertEq(address(dummy).code.length, 0);
+
+        cheats.selectFork(mainnetFork);
+        assertTrue(address(dummy).code.length > 0);
+        assertEq(dummy.value(), 5);
+    }
+
+    function testSelectInitialState() public {
+        Dummy dummy = new Dummy();
+        dummy.set(7);
+        uint256 localBlock = block.number;
+
+        cheats.selectFork(mainnetFork);
+        assertEq(address(dummy).code.length, 0);
+
+        cheats.selectFork(0);
+        assertEq(block.number, localBlock);
+        assertEq(dummy.value(), 7);
+    }
+
+    function testRevertedSelectIsUndone() public {
+        uint256 localBlock = block.number;
+        ForkSelector selector = new ForkSelector(cheats);
+
+        try selector.selectAndRevert(mainnetFork) {} catch {}
+        assertEq(block.number, localBlock);
+
+        // The selector deployed before is not persistent, so it does not exist on the fork
+        cheats.selectFork(mainnetFork);
+        selector = new ForkSelector(cheats);
+        try selector.selectAndRevert(olderFork) {} catch {}
+        assertEq(block.number, 15_000_000);
+    }
+
+    function testRollFork() public {
+        cheats.selectFork(mainnetFork);
+        cheats.rollFork(15_000_100);
+        assertEq(block.number, 15_000_100);
+
+        cheats.rollFork(olderFork, 14_000_100);
+        assertEq(block.number, 15_000_100);
+
+        cheats.selectFork(olderFork);
+        assertEq(block.number, 14_000_100);
+    }
+
+    function testFailSelectUnknownFork() public {
+        cheats.selectFork(100);
+    }
+
+    function testFailRollWithoutActiveFork() public {
+        cheats.rollFork(15_000_000);
+    }
+
+    function testFailRollInitialState() public {
+        cheats.rollFork(0, 15_000_000);
+    }
+}
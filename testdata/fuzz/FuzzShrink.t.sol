@@ -0,0 +1,10 @@
+// SPDX-License-Identifier: Unlicense
+pragma solidity >=0.8.0;
+
+import "ds-test/test.sol";
+
+contract FuzzShrinkTest is DSTest {
+    function testShrinkUint(uint256 x) public {
+        assertTrue(x < 1000);
+    }
+}
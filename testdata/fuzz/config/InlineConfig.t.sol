@@ -0,0 +1,14 @@
+// SPDX-License-Identifier: Unlicense
+pragma solidity >=0.8.0;
+
+import "./InlineConfigBase.sol";
+
+/// forge-config: fuzz.runs = 10
+contract InlineConfigTest is InlineConfigBase {
+    function testContractRuns(uint256) public {}
+
+    /// forge-config: fuzz.runs = 20
+    function testInlineRuns(uint256) public {}
+
+    function testTableRuns(uint256) public {}
+}
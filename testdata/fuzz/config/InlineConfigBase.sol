@@ -0,0 +1,9 @@
+// SPDX-License-Identifier: Unlicense
+pragma solidity >=0.8.0;
+
+import "ds-test/test.sol";
+
+abstract contract InlineConfigBase is DSTest {
+    /// forge-config: fuzz.runs = 30
+    function testBaseRuns(uint256) public {}
+}
@@ -0,0 +1,82 @@
+// SPDX-License-Identifier: Unlicense
+pragma solidity >=0.8.0;
+
+import "ds-test/test.sol";
+
+contract InvariantBreaker {
+    bool public flag0 = true;
+    bool public flag1 = true;
+
+    function set0(uint256 val) public returns (bool) {
+        if (val % 100 == 0) flag0 = false;
+        return flag0;
+    }
+
+    function set1(uint256 val) public returns (bool) {
+        if (val % 10 == 0 && !flag0) flag1 = false;
+        return flag1;
+    }
+}
+
+contract Counter {
+    uint256 public count;
+
+    function increment() public {
+        count++;
+    }
+}
+
+contract InvariantTest is DSTest {
+    InvariantBreaker inv;
+
+    function setUp() public {
+        inv = new InvariantBreaker();
+    }
+
+    function invariantNeverFalse() public {
+        require(inv.flag1(), "false.");
+    }
+}
+
+contract InvariantReturnsBoolTest is DSTest {
+    InvariantBreaker inv;
+
+    function setUp() public {
+        inv = new InvariantBreaker();
+    }
+
+    function invariantFlag0() public returns (bool) {
+        return inv.flag0();
+    }
+}
+
+contract InvariantHoldsTest is DSTest {
+    Counter counter;
+
+    function setUp() public {
+        counter = new Counter();
+    }
+
+    function invariantCountIsBounded() public {
+        assertTrue(counter.count() < type(uint256).max);
+    }
+}
+
+contract InvariantTargetTest is DSTest {
+    InvariantBreaker inv;
+    Counter counter;
+
+    function setUp() public {
+        inv = new InvariantBreaker();
+        counter = new Counter();
+    }
+
+    function targetContracts() public view returns (address[] memory targets) {
+        targets = new address[](1);
+        targets[0] = address(counter);
+    }
+
+    function invariantBreakerIsNeverCalled() public {
+        require(inv.flag0() && inv.flag1(), "breaker was called");
+    }
+}